@@ -0,0 +1,20 @@
+use std::process::Command;
+
+/// `GIT_HASH` 환경변수로 빌드 커밋을 넘긴다 (`/about`).
+/// git이 없는 환경(Docker 빌드 등)은 빌드 시 `GIT_HASH`를 직접 주거나 "unknown".
+fn main() {
+    let hash = std::env::var("GIT_HASH").ok().filter(|h| !h.is_empty()).or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .and_then(|out| String::from_utf8(out.stdout).ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+    });
+    println!("cargo:rustc-env=GIT_HASH={}", hash.as_deref().unwrap_or("unknown"));
+    println!("cargo:rerun-if-env-changed=GIT_HASH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
@@ -1,11 +1,59 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use teloxide::prelude::*;
-use teloxide::types::ParseMode;
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup, InlineQueryResult,
+    InlineQueryResultArticle, InputMessageContent, InputMessageContentText, MessageId, ParseMode,
+};
 use teloxide::utils::command::BotCommands;
 
-use crate::config::SourceConfig;
-use crate::db::Database;
+use crate::category::Category;
+use crate::config::{AdminConfig, SourceConfig};
+use crate::db::{ChannelPost, Database, Notice, UserSubs};
+use crate::deadline::{d_day, parse_db_date};
+use crate::dm_engine::{
+    body_snippet, classify_dm_error, keyword_matches, normalize_keyword, parse_keyword, DmFailure, KeywordTarget,
+    DISMISS_PREFIX,
+};
+use crate::format::{html_escape, MESSAGE_MAX_CHARS};
+use crate::kst;
+use crate::throttle::RateLimiter;
+use crate::trigger::CrawlTrigger;
+
+/// `/recent`에서 보여줄 공지 수.
+const RECENT_LIMIT: usize = 10;
+
+/// `/latest`, `/search` 한 페이지당 공지 수.
+const PAGE_SIZE: usize = 10;
+
+/// 사용자당 하루 `/feedback` 최대 횟수.
+const FEEDBACK_DAILY_LIMIT: u32 = 3;
+
+/// 피드백 최대 길이 (문자).
+const FEEDBACK_MAX_CHARS: usize = 1000;
+
+/// 인라인 쿼리 결과 최대 개수.
+const INLINE_LIMIT: usize = 20;
+
+/// 인라인 쿼리 결과를 텔레그램이 캐시하는 시간 (초).
+const INLINE_CACHE_SECS: u32 = 60;
+
+/// `/unsubscribe_all` 확인 버튼 callback_data 접두사.
+const UNSUB_ALL_PREFIX: &str = "unsuball|";
+
+/// `/sources` 구독 토글 버튼 callback_data 접두사. 형식: `srcsub|<쪽>|<소스 키>`
+/// (키가 비어 있으면 쪽 이동만).
+const SOURCE_TOGGLE_PREFIX: &str = "srcsub|";
+
+/// `/sources` 버튼 한 쪽당 소스 수.
+const SOURCES_PAGE_SIZE: usize = 8;
+
+/// 텔레그램 callback_data 최대 길이 (바이트).
+const CALLBACK_DATA_MAX: usize = 64;
 
 /// 텔레그램 봇 명령어 정의.
 #[derive(BotCommands, Clone)]
@@ -15,20 +63,60 @@ pub enum Command {
     Start,
     #[command(description = "도움말")]
     Help,
-    #[command(description = "키워드 구독 (예: /sub 장학금)")]
+    #[command(description = "키워드 구독 (예: /sub 장학금, 채용)")]
     Sub(String),
+    #[command(description = "키워드 미리보기: 최근 7일 매칭 결과 (예: /preview 장학금)")]
+    Preview(String),
     #[command(description = "키워드 구독 해제 (예: /unsub 장학금)")]
     Unsub(String),
-    #[command(description = "학과 구독 (예: /dept biz)")]
+    #[command(description = "학과 구독 (예: /dept biz, /dept biz 장학,채용)")]
     Dept(String),
     #[command(description = "학과 구독 해제")]
     Undept(String),
+    #[command(description = "DM 선호 언어 (예: /lang en, /lang off)")]
+    Lang(String),
     #[command(description = "내 구독 현황")]
     Mysubs,
+    #[command(description = "DB에 저장된 내 구독 원본 (문의용)")]
+    Whoami,
+    #[command(rename = "unsubscribe_all", description = "모든 구독 해제")]
+    UnsubscribeAll,
     #[command(description = "사용 가능한 소스 목록")]
     Sources,
+    #[command(description = "특정 소스의 최신 공지 (예: /recent biz)")]
+    Recent(String),
+    #[command(description = "전체 최신 공지")]
+    Latest,
+    #[command(description = "학과 게시 빈도 (예: /stats biz)")]
+    Stats(String),
+    #[command(description = "최근 7일 조회수 많은 공지")]
+    Trending,
+    #[command(description = "분류별 최신 공지 (예: /top 장학)")]
+    Top(String),
+    #[command(description = "아카이브에서 임의의 공지 하나 (예: /random, /random 장학)")]
+    Random(String),
+    #[command(description = "내 구독 중 마감 임박 공지")]
+    Deadlines,
+    #[command(description = "공지 제목 검색 (예: /search 장학금)")]
+    Search(String),
     #[command(description = "봇 상태")]
     Status,
+    #[command(description = "버전 / 가동 시간")]
+    About,
+    #[command(description = "오류 제보 / 의견 보내기 (예: /feedback 제목이 깨져요)")]
+    Feedback(String),
+    #[command(description = "공지 분류 고치기 (관리자 전용, 예: /recat <URL> 장학)", hide)]
+    Recat(String),
+    #[command(description = "공지의 게시일/발견 시각 원본 (관리자 전용, 예: /notice <공지 번호 또는 URL>)", hide)]
+    Notice(String),
+    #[command(description = "실패 중인 소스와 마지막 에러 (관리자 전용)", hide)]
+    Errors,
+    #[command(description = "전체 공지 발송 (관리자 전용)", hide)]
+    Broadcast(String),
+    #[command(description = "채널 게시물 삭제 (관리자 전용, 예: /delete <공지 번호 또는 URL>)", hide)]
+    Delete(String),
+    #[command(description = "지금 바로 크롤링 (관리자 전용, 예: /crawl, /crawl biz)", hide)]
+    Crawl(String),
 }
 
 /// 봇 핸들러의 공유 상태.
@@ -36,6 +124,23 @@ pub enum Command {
 pub struct BotState {
     pub db: Arc<Mutex<Database>>,
     pub sources: Vec<SourceConfig>,
+    /// callback_data에 다 못 넣는 긴 검색어 (토큰 → 검색어).
+    pub query_tokens: Arc<Mutex<HashMap<String, String>>>,
+    pub admin: AdminConfig,
+    /// 연속 발송 간격 (ms). `/broadcast`에서 사용.
+    pub message_delay_ms: u64,
+    /// `/feedback`을 전달할 로그 채널. 없으면 DB에만 저장.
+    pub log_channel: Option<String>,
+    /// 기본 채널. `/delete`에서 게시 채널 기록이 없는 옛 게시물용.
+    pub channel: String,
+    /// 제목 키워드를 본문에서도 찾는다 (`bot.match_body`). DM 매칭과 같게.
+    pub match_body: bool,
+    /// 크롤 루프 깨우기 (`/crawl`). serve 모드에서만 있다.
+    pub crawl: Option<CrawlTrigger>,
+    /// 프로세스 시작 시각 (`/about` 가동 시간).
+    pub started: Instant,
+    /// 크롤 루프의 채널/DM 발송과 나눠 쓰는 속도 제한. `/broadcast`에서 사용.
+    pub limiter: Arc<RateLimiter>,
 }
 
 /// 명령어 핸들러.
@@ -73,11 +178,110 @@ pub async fn handle_command(
         Command::Help => handle_help(),
         Command::Sub(kw) => handle_sub(&state, user_id, &kw),
         Command::Unsub(kw) => handle_unsub(&state, user_id, &kw),
+        Command::Preview(kw) => handle_preview(&state, &kw),
         Command::Dept(key) => handle_dept(&state, user_id, &key),
         Command::Undept(key) => handle_undept(&state, user_id, &key),
+        Command::Lang(lang) => handle_lang(&state, user_id, &lang),
         Command::Mysubs => handle_mysubs(&state, user_id),
-        Command::Sources => handle_sources(&state),
+        Command::Whoami => handle_whoami(&state, user_id),
+        Command::UnsubscribeAll => {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback("\u{1f5d1} 정말 삭제", format!("{}{}", UNSUB_ALL_PREFIX, user_id)),
+                InlineKeyboardButton::callback("취소", format!("{}cancel", UNSUB_ALL_PREFIX)),
+            ]]);
+            let text = "\u{26a0}\u{fe0f} 모든 키워드/학과 구독을 해제할까요?".to_string();
+            return send_with_keyboard(&bot, chat_id, text, Some(keyboard)).await;
+        }
+        Command::Sources => {
+            let text = handle_sources(&state);
+            let keyboard = sources_keyboard(&state, user_id, 0);
+            return send_with_keyboard(&bot, chat_id, text, Some(keyboard)).await;
+        }
         Command::Status => handle_status(&state),
+        Command::About => handle_about(&state),
+        Command::Recent(key) => {
+            let (text, keyboard) = handle_recent(&state, &key);
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Latest => {
+            let page = PageRequest { kind: PageKind::Latest, offset: 0, query: String::new() };
+            let (text, keyboard) = render_page(&state, &page);
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Stats(key) => handle_stats(&state, key.trim()),
+        Command::Trending => {
+            let (text, keyboard) = handle_trending(&state);
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Top(category) => {
+            let (text, keyboard) = handle_top(&state, category.trim());
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Random(category) => {
+            let (text, keyboard) = handle_random(&state, category.trim());
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Deadlines => {
+            let (text, keyboard) = handle_deadlines(&state, user_id);
+            return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+        }
+        Command::Feedback(text) => {
+            handle_feedback(&bot, &state, user_id, user.username.as_deref(), text.trim()).await
+        }
+        Command::Recat(args) => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else {
+                handle_recat(&state, user_id, &args)
+            }
+        }
+        Command::Notice(args) => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else {
+                handle_notice_debug(&state, &args)
+            }
+        }
+        Command::Errors => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else {
+                handle_errors(&state)
+            }
+        }
+        Command::Broadcast(text) => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else if text.trim().is_empty() {
+                "\u{26a0}\u{fe0f} 보낼 내용을 입력하세요.\n예: /broadcast 오늘 밤 점검 예정입니다".to_string()
+            } else {
+                handle_broadcast(&bot, &state, text.trim()).await
+            }
+        }
+        Command::Delete(args) => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else {
+                handle_delete(&bot, &state, &args).await
+            }
+        }
+        Command::Crawl(source) => {
+            if !state.admin.is_admin(user_id) {
+                "\u{1f6ab} 관리자만 사용할 수 있는 명령어입니다.".to_string()
+            } else {
+                handle_crawl(&bot, chat_id, &state, source.trim()).await
+            }
+        }
+        Command::Search(query) => {
+            let query = query.trim();
+            if query.is_empty() {
+                "\u{26a0}\u{fe0f} 검색어를 입력하세요.\n예: /search 장학금".to_string()
+            } else {
+                let page = PageRequest { kind: PageKind::Search, offset: 0, query: query.to_string() };
+                let (text, keyboard) = render_page(&state, &page);
+                return send_with_keyboard(&bot, chat_id, text, keyboard).await;
+            }
+        }
     };
 
     bot.send_message(chat_id, response)
@@ -86,6 +290,460 @@ pub async fn handle_command(
     Ok(())
 }
 
+/// 피드백을 로그 채널로 전달 (하루 `FEEDBACK_DAILY_LIMIT`회). 채널이 없거나
+/// 전달에 실패하면 DB에만 남긴다.
+async fn handle_feedback(
+    bot: &Bot,
+    state: &BotState,
+    user_id: i64,
+    username: Option<&str>,
+    text: &str,
+) -> String {
+    if text.is_empty() {
+        return "\u{26a0}\u{fe0f} 내용을 입력하세요.\n예: /feedback 공대 공지 제목이 깨져요".to_string();
+    }
+    if text.chars().count() > FEEDBACK_MAX_CHARS {
+        return format!("\u{26a0}\u{fe0f} 피드백이 너무 깁니다 (최대 {}자).", FEEDBACK_MAX_CHARS);
+    }
+    match state.db.lock().unwrap().count_feedback_today(user_id) {
+        Ok(n) if n >= FEEDBACK_DAILY_LIMIT => {
+            return format!(
+                "\u{23f3} 피드백은 하루 {}회까지 보낼 수 있습니다. 내일 다시 시도해주세요.",
+                FEEDBACK_DAILY_LIMIT
+            )
+        }
+        Ok(_) => {}
+        Err(e) => return format!("\u{274c} 피드백 저장 실패: {}", e),
+    }
+
+    let mut forwarded = false;
+    if let Some(channel) = &state.log_channel {
+        let who = match username {
+            Some(u) => format!("@{} ({})", u, user_id),
+            None => user_id.to_string(),
+        };
+        let message = format!("\u{1f4ac} 사용자 피드백\n\n보낸 사람: {}\n\n{}", who, text);
+        match bot.send_message(ChatId(0), message).chat_id(channel.clone()).await {
+            Ok(_) => forwarded = true,
+            Err(e) => tracing::warn!(error = %e, "Failed to forward feedback"),
+        }
+    }
+
+    match state.db.lock().unwrap().add_feedback(user_id, username, text, forwarded) {
+        Ok(_) => "\u{1f64f} 소중한 의견 감사합니다! 확인 후 반영하겠습니다.".to_string(),
+        Err(e) => format!("\u{274c} 피드백 저장 실패: {}", e),
+    }
+}
+
+/// 활성 사용자 전원에게 메시지 발송. 차단(403)한 사용자는 비활성화한다 (`classify_dm_error`).
+async fn handle_broadcast(bot: &Bot, state: &BotState, text: &str) -> String {
+    let user_ids = match state.db.lock().unwrap().get_active_user_ids() {
+        Ok(ids) => ids,
+        Err(e) => return format!("\u{274c} 사용자 조회 실패: {}", e),
+    };
+    tracing::info!(recipients = user_ids.len(), "Broadcast started");
+
+    let (mut sent, mut failed, mut deactivated) = (0u32, 0u32, 0u32);
+    for telegram_id in user_ids {
+        state.limiter.acquire().await;
+        match crate::notifier::send_text_with_backoff(bot, ChatId(telegram_id), text).await {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                failed += 1;
+                tracing::warn!(telegram_id, error = %e, "Broadcast send failed");
+                if classify_dm_error(&e.to_string()) == DmFailure::Blocked
+                    && state.db.lock().unwrap().deactivate_user(telegram_id).is_ok()
+                {
+                    deactivated += 1;
+                }
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(state.message_delay_ms)).await;
+    }
+
+    tracing::info!(sent, failed, deactivated, "Broadcast complete");
+    format!(
+        "\u{1f4e3} 전체 발송 완료\n\n성공: {}명\n실패: {}명 (비활성화 {}명)",
+        sent, failed, deactivated
+    )
+}
+
+/// `/delete <공지 번호 또는 URL>`: 잘못 올라간 채널 게시물을 지우고 DB에 삭제로 기록.
+/// 텔레그램은 48시간이 지난 게시물은 봇이 지울 수 없게 하므로 그 전에만 시도한다.
+async fn handle_delete(bot: &Bot, state: &BotState, args: &str) -> String {
+    let (id, post) = match find_channel_post(state, args) {
+        Ok(found) => found,
+        Err(reply) => return reply,
+    };
+    let chat = post.chat.clone().unwrap_or_else(|| {
+        state
+            .sources
+            .iter()
+            .find(|s| s.key == post.source_key)
+            .and_then(|s| s.channel.clone())
+            .unwrap_or_else(|| state.channel.clone())
+    });
+
+    let gone = match bot.delete_message(chat, MessageId(post.message_id)).await {
+        Ok(_) => false,
+        // 채널에서 이미 손으로 지운 경우: DB 기록만 맞춘다
+        Err(e) if e.to_string().to_lowercase().contains("message to delete not found") => true,
+        Err(e) => {
+            tracing::warn!(id, error = %e, "Channel post delete failed");
+            return format!("\u{274c} 삭제 실패: {}\n채널에서 직접 삭제하세요.", html_escape(&e.to_string()));
+        }
+    };
+    if let Err(e) = state.db.lock().unwrap().mark_deleted(id) {
+        return format!("\u{274c} 삭제 기록 실패: {}", e);
+    }
+    tracing::info!(id, gone, "Channel post deleted");
+    format!(
+        "\u{1f5d1} {}\n{}",
+        if gone { "이미 지워진 게시물입니다. 삭제로 기록했습니다." } else { "채널 게시물을 삭제했습니다." },
+        html_escape(&post.title)
+    )
+}
+
+/// `/crawl [source]`: 예약 주기를 기다리지 않고 크롤 루프를 바로 한 번 돌린다.
+/// 예약 사이클이 도는 중이면 그게 끝난 뒤에 이어서 돈다.
+async fn handle_crawl(bot: &Bot, chat_id: ChatId, state: &BotState, source: &str) -> String {
+    let Some(trigger) = &state.crawl else {
+        return "\u{26a0}\u{fe0f} 크롤 루프가 없는 모드입니다.".to_string();
+    };
+    let source = (!source.is_empty()).then(|| source.to_string());
+    if let Some(key) = &source {
+        if !state.sources.iter().any(|s| &s.key == key && s.enabled) {
+            return format!("\u{274c} 활성화된 소스가 아닙니다: {}\n/sources 로 목록을 확인하세요.", html_escape(key));
+        }
+    }
+    let target = source.clone().unwrap_or_else(|| "전체".to_string());
+    let _ = bot
+        .send_message(chat_id, format!("\u{1f504} 크롤링 시작 ({})…", html_escape(&target)))
+        .await;
+    match trigger.run(source).await {
+        Ok(counts) => format!(
+            "\u{2705} 크롤링 완료 ({})\n신규 {}건 · 채널 {}건 · DM {}건",
+            html_escape(&target),
+            counts.new,
+            counts.sent,
+            counts.dm_sent
+        ),
+        Err(e) => format!("\u{274c} 크롤링 실패: {}", html_escape(&e.to_string())),
+    }
+}
+
+/// `/delete` 대상 찾기: 숫자면 공지 번호(DB ID), 아니면 URL. 지울 수 없으면 안내 문구.
+fn find_channel_post(state: &BotState, args: &str) -> Result<(i64, ChannelPost), String> {
+    let target = args.trim();
+    if target.is_empty() {
+        return Err("\u{26a0}\u{fe0f} 지울 공지 번호나 URL을 입력하세요.\n예: /delete 1234".to_string());
+    }
+    let db = state.db.lock().unwrap();
+    let lookup_failed = |e: anyhow::Error| format!("\u{274c} 조회 실패: {}", e);
+    let id = match target.parse::<i64>() {
+        Ok(id) => Some(id),
+        Err(_) => db.get_notice_by_url(target).map_err(lookup_failed)?.map(|n| n.id),
+    };
+    let post = match id {
+        Some(id) => db.get_channel_post(id).map_err(lookup_failed)?,
+        None => None,
+    };
+    match (id, post) {
+        (Some(id), Some(post)) if !post.deleted && post.deletable => Ok((id, post)),
+        (_, Some(post)) if post.deleted => Err(format!(
+            "\u{2139}\u{fe0f} 이미 삭제한 게시물입니다.\n{}",
+            html_escape(&post.title)
+        )),
+        (_, Some(post)) => Err(format!(
+            "\u{23f0} 올린 지 48시간이 지나 봇이 지울 수 없습니다. 채널에서 직접 삭제하세요.\n{}",
+            html_escape(&post.title)
+        )),
+        _ => Err("\u{274c} 채널 게시 기록이 있는 공지를 찾을 수 없습니다. (묶음/사진 게시물은 채널에서 직접 삭제하세요)".to_string()),
+    }
+}
+
+/// `/notice <공지 번호 또는 URL>`: 게시일(`published`)과 처음 발견한 시각(`crawled_at`),
+/// 정렬에 실제로 쓰는 시각을 나란히 보여준다. 크롤링이 밀렸을 때 순서 문의용.
+fn handle_notice_debug(state: &BotState, args: &str) -> String {
+    let target = args.trim();
+    if target.is_empty() {
+        return "\u{26a0}\u{fe0f} 공지 번호나 URL을 입력하세요.\n예: /notice 1234".to_string();
+    }
+    let db = state.db.lock().unwrap();
+    let id = match target.parse::<i64>() {
+        Ok(id) => Ok(Some(id)),
+        Err(_) => db.get_notice_by_url(target).map(|n| n.map(|n| n.id)),
+    };
+    let notice = match id.and_then(|id| id.map_or(Ok(None), |id| db.get_notice_debug(id))) {
+        Ok(Some(notice)) => notice,
+        Ok(None) => return "\u{274c} 공지를 찾을 수 없습니다.".to_string(),
+        Err(e) => return format!("\u{274c} 조회 실패: {}", e),
+    };
+    let basis = if notice.published.as_deref() == Some(notice.sort_time.as_str()) {
+        "published"
+    } else {
+        "crawled_at"
+    };
+    let text = format!(
+        "id          = {}\nsource_key  = {}\nnotice_id   = {}\ntitle       = {}\npublished   = {}\ncrawled_at  = {}\nnotified_at = {}\nsort_time   = {} ({})",
+        notice.id,
+        notice.source_key,
+        notice.notice_id,
+        notice.title,
+        notice.published.as_deref().unwrap_or("NULL"),
+        notice.crawled_at,
+        notice.notified_at.as_deref().unwrap_or("NULL"),
+        notice.sort_time,
+        basis,
+    );
+    format!("<pre>{}</pre>", html_escape(&text))
+}
+
+/// 인라인 키보드가 있을 수도 있는 HTML 메시지 전송.
+async fn send_with_keyboard(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: String,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> ResponseResult<()> {
+    let req = bot.send_message(chat_id, text).parse_mode(ParseMode::Html);
+    match keyboard {
+        Some(kb) => req.reply_markup(kb).await?,
+        None => req.await?,
+    };
+    Ok(())
+}
+
+/// 인라인 버튼 콜백 핸들러 (`/latest`, `/search` 페이지 이동, `/unsubscribe_all` 확인, DM 확인).
+pub async fn handle_callback(bot: Bot, q: CallbackQuery, state: Arc<BotState>) -> ResponseResult<()> {
+    if let Some(id) = q.data.as_deref().and_then(|d| d.strip_prefix(DISMISS_PREFIX)) {
+        let id = id.parse::<i64>().ok();
+        return handle_dismiss(bot, q, state, id).await;
+    }
+    if let Some(target) = q.data.as_deref().and_then(|d| d.strip_prefix(UNSUB_ALL_PREFIX)) {
+        let target = target.to_string();
+        return handle_unsubscribe_all_confirm(bot, q, state, &target).await;
+    }
+    if let Some(rest) = q.data.as_deref().and_then(|d| d.strip_prefix(SOURCE_TOGGLE_PREFIX)) {
+        let rest = rest.to_string();
+        return handle_source_toggle(bot, q, state, &rest).await;
+    }
+
+    let page = q
+        .data
+        .as_deref()
+        .and_then(|d| decode_page_callback(d, &state.query_tokens));
+
+    let (page, message) = match (page, q.message.as_ref()) {
+        (Some(p), Some(m)) => (p, m),
+        _ => {
+            bot.answer_callback_query(q.id)
+                .text("만료된 버튼입니다. 명령어를 다시 입력해주세요.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let (text, keyboard) = render_page(&state, &page);
+    let req = bot
+        .edit_message_text(message.chat().id, message.id(), text)
+        .parse_mode(ParseMode::Html);
+    match keyboard {
+        Some(kb) => req.reply_markup(kb).await?,
+        None => req.await?,
+    };
+    bot.answer_callback_query(q.id).await?;
+    Ok(())
+}
+
+/// `/sources` 구독 버튼 처리: 누른 사람의 학과 구독을 켜고/끄고 버튼 표시를 바꾼다.
+async fn handle_source_toggle(
+    bot: Bot,
+    q: CallbackQuery,
+    state: Arc<BotState>,
+    data: &str,
+) -> ResponseResult<()> {
+    let user_id = q.from.id.0 as i64;
+    let Some((page, key)) = data.split_once('|').and_then(|(p, k)| Some((p.parse::<usize>().ok()?, k))) else {
+        bot.answer_callback_query(q.id).text("만료된 버튼입니다.").await?;
+        return Ok(());
+    };
+
+    let notice = if key.is_empty() {
+        None
+    } else {
+        Some(match toggle_source_sub(&state, user_id, key) {
+            Ok(true) => "\u{2705} 구독했습니다".to_string(),
+            Ok(false) => "구독을 해제했습니다".to_string(),
+            Err(e) => e,
+        })
+    };
+
+    if let Some(message) = q.message.as_ref() {
+        bot.edit_message_reply_markup(message.chat().id, message.id())
+            .reply_markup(sources_keyboard(&state, user_id, page))
+            .await?;
+    }
+    let answer = bot.answer_callback_query(q.id);
+    match notice {
+        Some(text) => answer.text(text).await?,
+        None => answer.await?,
+    };
+    Ok(())
+}
+
+/// 학과 구독을 뒤집는다. 반환: 이제 구독 중이면 `true`. 모르는 소스 키면 안내 문구.
+fn toggle_source_sub(state: &BotState, user_id: i64, source_key: &str) -> Result<bool, String> {
+    if !state.sources.iter().any(|s| s.key == source_key) {
+        return Err("알 수 없는 소스입니다. /sources 를 다시 입력해주세요.".to_string());
+    }
+    let db = state.db.lock().unwrap();
+    let subscribed = db
+        .get_user_subs(user_id)
+        .map_err(|e| format!("\u{274c} 조회 실패: {}", e))?
+        .sources
+        .iter()
+        .any(|k| k == source_key);
+    let result = if subscribed {
+        db.remove_source_sub(user_id, source_key)
+    } else {
+        db.add_source_sub(user_id, source_key)
+    };
+    result.map_err(|e| format!("\u{274c} 처리 실패: {}", e))?;
+    Ok(!subscribed)
+}
+
+/// `/sources` 아래 구독 토글 버튼 (소스당 한 줄, `SOURCES_PAGE_SIZE`개씩 쪽 나눔).
+fn sources_keyboard(state: &BotState, user_id: i64, page: usize) -> InlineKeyboardMarkup {
+    let subscribed = state
+        .db
+        .lock()
+        .unwrap()
+        .get_user_subs(user_id)
+        .map(|s| s.sources)
+        .unwrap_or_default();
+    let pages = state.sources.len().div_ceil(SOURCES_PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = state
+        .sources
+        .iter()
+        .skip(page * SOURCES_PAGE_SIZE)
+        .take(SOURCES_PAGE_SIZE)
+        .filter_map(|src| {
+            let data = format!("{}{}|{}", SOURCE_TOGGLE_PREFIX, page, src.key);
+            if data.len() > CALLBACK_DATA_MAX {
+                return None;
+            }
+            let mark = if subscribed.contains(&src.key) { "\u{2705}" } else { "\u{2795}" };
+            Some(vec![InlineKeyboardButton::callback(format!("{} {}", mark, src.display_name), data)])
+        })
+        .collect();
+
+    let mut nav = Vec::new();
+    if page > 0 {
+        nav.push(InlineKeyboardButton::callback(
+            "\u{25c0} 이전",
+            format!("{}{}|", SOURCE_TOGGLE_PREFIX, page - 1),
+        ));
+    }
+    if page + 1 < pages {
+        nav.push(InlineKeyboardButton::callback(
+            "다음 \u{25b6}",
+            format!("{}{}|", SOURCE_TOGGLE_PREFIX, page + 1),
+        ));
+    }
+    if !nav.is_empty() {
+        rows.push(nav);
+    }
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// `/unsubscribe_all` 확인 버튼 처리. 버튼을 누른 사람이 요청자일 때만 삭제한다.
+async fn handle_unsubscribe_all_confirm(
+    bot: Bot,
+    q: CallbackQuery,
+    state: Arc<BotState>,
+    target: &str,
+) -> ResponseResult<()> {
+    let user_id = q.from.id.0 as i64;
+    let text = if target == "cancel" {
+        "\u{2139}\u{fe0f} 구독 해제를 취소했습니다.".to_string()
+    } else if target.parse::<i64>().ok() != Some(user_id) {
+        bot.answer_callback_query(q.id)
+            .text("본인 구독만 해제할 수 있습니다.")
+            .await?;
+        return Ok(());
+    } else {
+        match state.db.lock().unwrap().clear_user_subs(user_id) {
+            Ok(0) => "\u{2139}\u{fe0f} 해제할 구독이 없습니다.".to_string(),
+            Ok(n) => format!("\u{2705} 구독 {}개를 모두 해제했습니다.", n),
+            Err(e) => format!("\u{274c} 구독 해제 실패: {}", e),
+        }
+    };
+
+    if let Some(message) = q.message.as_ref() {
+        bot.edit_message_text(message.chat().id, message.id(), text)
+            .await?;
+    }
+    bot.answer_callback_query(q.id).await?;
+    Ok(())
+}
+
+/// DM "확인" 버튼 처리: 읽음 기록 후 메시지에 표시하고 버튼을 뗀다.
+async fn handle_dismiss(
+    bot: Bot,
+    q: CallbackQuery,
+    state: Arc<BotState>,
+    notice_db_id: Option<i64>,
+) -> ResponseResult<()> {
+    let Some(notice_db_id) = notice_db_id else {
+        bot.answer_callback_query(q.id).text("만료된 버튼입니다.").await?;
+        return Ok(());
+    };
+    let user_id = q.from.id.0 as i64;
+    let recorded = state.db.lock().unwrap().dismiss_notice(notice_db_id, user_id);
+    if let Err(e) = recorded {
+        tracing::warn!(error = %e, notice_db_id, "Failed to record dismiss");
+        bot.answer_callback_query(q.id).text("처리하지 못했습니다. 잠시 후 다시 시도해주세요.").await?;
+        return Ok(());
+    }
+
+    // 원래 서식(entities)을 유지한 채 끝에 표시만 덧붙인다
+    if let Some(message) = q.message.as_ref().and_then(|m| m.regular_message()) {
+        if let Some(text) = message.text() {
+            let req = bot
+                .edit_message_text(message.chat.id, message.id, format!("{}\n\n\u{2714} 확인함", text))
+                .entities(message.entities().unwrap_or_default().to_vec());
+            let result = match message.reply_markup().map(without_dismiss_button) {
+                Some(kb) => req.reply_markup(kb).await,
+                None => req.await,
+            };
+            if let Err(e) = result {
+                tracing::debug!(error = %e, "Failed to mark DM as read");
+            }
+        }
+    }
+    bot.answer_callback_query(q.id).text("확인했습니다. 리마인더에서 제외됩니다.").await?;
+    Ok(())
+}
+
+/// DM 키보드에서 "확인" 버튼만 뺀 사본.
+fn without_dismiss_button(markup: &InlineKeyboardMarkup) -> InlineKeyboardMarkup {
+    let rows = markup
+        .inline_keyboard
+        .iter()
+        .map(|row| {
+            row.iter()
+                .filter(|b| !matches!(&b.kind, InlineKeyboardButtonKind::CallbackData(d) if d.starts_with(DISMISS_PREFIX)))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<_>>();
+    InlineKeyboardMarkup::new(rows)
+}
+
 fn handle_start(user_id: i64, first_name: &str) -> String {
     let _ = user_id; // 이미 handle_command에서 등록 완료
     format!(
@@ -104,54 +762,319 @@ fn handle_start(user_id: i64, first_name: &str) -> String {
 fn handle_help() -> String {
     "\u{2139}\u{fe0f} <b>충북대 공지 봇 도움말</b>\n\n\
      <b>키워드 구독</b>\n\
-     /sub &lt;키워드&gt; — 키워드가 포함된 공지를 DM으로 받기\n\
+     /sub &lt;키워드&gt; — 키워드가 포함된 공지를 DM으로 받기 (쉼표로 여러 개)\n\
+     /sub author:&lt;작성자&gt; — 특정 부서/작성자의 공지 받기\n\
+     /preview &lt;키워드&gt; — 최근 7일간 몇 건이 걸렸을지 미리보기\n\
      /unsub &lt;키워드&gt; — 키워드 구독 해제\n\n\
      <b>학과 구독</b>\n\
      /dept &lt;학과코드&gt; — 특정 학과 공지를 DM으로 받기\n\
-     /undept &lt;학과코드&gt; — 학과 구독 해제\n\n\
+     /dept &lt;학과코드&gt; 장학,채용 — 해당 분류만 받기\n\
+     /undept &lt;학과코드&gt; — 학과 구독 해제\n\
+     /lang en|ko — 선호 언어 공지를 먼저 받기 (off: 해제)\n\n\
      <b>조회</b>\n\
      /mysubs — 내 구독 현황 보기\n\
+     /whoami — DB에 저장된 내 구독 원본 (문의할 때 첨부)\n\
+     /unsubscribe_all — 모든 구독 해제\n\
      /sources — 사용 가능한 학과/소스 목록\n\
-     /status — 봇 상태 확인\n\n\
+     /recent &lt;학과코드&gt; — 해당 학과 최신 공지\n\
+     /latest — 전체 최신 공지\n\
+     /trending — 최근 7일 인기 공지\n\
+     /top &lt;분류&gt; — 분류별 최신 공지 (학사/장학/채용/모집/행사/국제)\n\
+     /random [분류] — 지난 공지 중 하나를 무작위로 (양식·규정 찾기)\n\
+     /stats &lt;학과코드&gt; — 최근 8주 게시 빈도\n\
+     /deadlines — 내 구독 공지 중 마감 임박 순 (D-day)\n\
+     /search &lt;검색어&gt; — 공지 제목 검색\n\
+     /status — 봇 상태 확인\n\
+     /about — 버전 / 가동 시간\n\
+     /feedback &lt;내용&gt; — 오류 제보 / 의견 보내기\n\n\
      \u{1f4a1} <b>예시</b>\n\
      <code>/sub 장학금</code> → '장학금' 관련 공지 알림\n\
      <code>/dept biz</code> → 경영학부 공지 알림"
         .to_string()
 }
 
-fn handle_sub(state: &BotState, user_id: i64, keyword: &str) -> String {
-    let keyword = keyword.trim();
-    if keyword.is_empty() {
-        return "\u{26a0}\u{fe0f} 키워드를 입력하세요.\n예: /sub 장학금".to_string();
+/// 오타로 보고 제안할 최대 편집 거리.
+const SUGGEST_MAX_DISTANCE: usize = 2;
+
+/// 명령어로 파싱되지 않은 메시지의 해석 (`guess_command`).
+#[derive(Debug, PartialEq)]
+pub(crate) enum CommandGuess {
+    /// 띄어쓰기만 빠진 명령어를 고친 문장 (`/sub장학금` → `/sub 장학금`).
+    Split(String),
+    /// 가장 가까운 명령어 (이름, 설명).
+    Suggest(String, String),
+    Unknown,
+}
+
+/// 두 문자열의 편집 거리 (Levenshtein).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(diag + usize::from(ca != *cb));
+            diag = row[j + 1];
+            row[j + 1] = next;
+        }
     }
-    if keyword.len() > 50 {
-        return "\u{26a0}\u{fe0f} 키워드가 너무 깁니다 (최대 50자).".to_string();
+    row[b.len()]
+}
+
+/// 잘못 입력한 명령어 추정 (관리자용 숨은 명령어는 제외).
+/// 알려진 명령어 뒤에 한글 등이 바로 붙었으면 띄어 쓴 문장으로 고치고,
+/// 아니면 편집 거리 `SUGGEST_MAX_DISTANCE` 이내, 그것도 없으면 알려진 명령어로
+/// 시작하는 것(`/subscribe` → `/sub`)을 제안한다.
+pub(crate) fn guess_command(text: &str) -> CommandGuess {
+    let Some(rest) = text.trim().strip_prefix('/') else {
+        return CommandGuess::Unknown;
+    };
+    let (token, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let token = token.split('@').next().unwrap_or(token).to_lowercase();
+    let commands: Vec<(String, String)> = Command::bot_commands()
+        .into_iter()
+        .map(|c| (c.command.trim_start_matches('/').to_string(), c.description))
+        .collect();
+
+    // 가장 긴 명령어부터: `/unsub장학` 이 `/un…`이 아니라 `/unsub`로 잡히게
+    let mut by_length: Vec<&(String, String)> = commands.iter().collect();
+    by_length.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    for (name, _) in &by_length {
+        if let Some(arg) = token.strip_prefix(name.as_str()) {
+            if arg.chars().next().is_some_and(|c| !c.is_ascii()) {
+                let arg = format!("{} {}", arg, tail);
+                return CommandGuess::Split(format!("/{} {}", name, arg.trim()));
+            }
+        }
     }
 
-    let db = state.db.lock().unwrap();
-    match db.add_keyword_sub(user_id, keyword) {
-        Ok(true) => format!("\u{2705} '{}' 키워드 구독 완료!", keyword),
-        Ok(false) => format!("\u{2139}\u{fe0f} '{}' 이미 구독 중입니다.", keyword),
-        Err(e) => format!("\u{274c} 구독 실패: {}", e),
+    let nearest = commands
+        .iter()
+        .map(|c| (edit_distance(&token, &c.0), c))
+        // 거리가 같으면 입력을 이어 쓴 명령어 우선 (`/stat` → `/stats`, `/start` 아님)
+        .min_by_key(|(distance, c)| (*distance, !c.0.starts_with(&token)))
+        .filter(|(distance, _)| *distance <= SUGGEST_MAX_DISTANCE)
+        .map(|(_, c)| c)
+        .or_else(|| by_length.into_iter().find(|(name, _)| token.starts_with(name.as_str())));
+    match nearest {
+        Some((name, description)) => CommandGuess::Suggest(name.clone(), description.clone()),
+        None => CommandGuess::Unknown,
+    }
+}
+
+/// 명령어로 파싱되지 않은 개인 메시지. 띄어쓰기만 빠졌으면 그대로 실행하고,
+/// 아니면 가장 가까운 명령어와 사용법을 알려준다. 그룹 대화는 무시한다.
+pub async fn handle_unknown(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
+    if !msg.chat.is_private() {
+        return Ok(());
     }
+    let Some(text) = msg.text() else {
+        return Ok(());
+    };
+    let reply = match guess_command(text) {
+        CommandGuess::Split(fixed) => match Command::parse(&fixed, "") {
+            Ok(cmd) => return handle_command(bot, msg, cmd, state).await,
+            Err(_) => "\u{2753} 명령어와 내용 사이를 띄어 주세요.\n예: /sub 장학금".to_string(),
+        },
+        CommandGuess::Suggest(name, description) => format!(
+            "\u{2753} 알 수 없는 명령어입니다. 혹시 /{} 인가요?\n/{} — {}",
+            name,
+            name,
+            html_escape(&description)
+        ),
+        CommandGuess::Unknown if text.starts_with('/') => {
+            "\u{2753} 알 수 없는 명령어입니다. /help 로 명령어 목록을 확인하세요.".to_string()
+        }
+        CommandGuess::Unknown => {
+            "\u{1f4ac} 명령어는 /로 시작합니다. 예: /sub 장학금\n명령어 목록은 /help".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).parse_mode(ParseMode::Html).await?;
+    Ok(())
 }
 
-fn handle_unsub(state: &BotState, user_id: i64, keyword: &str) -> String {
+/// `/preview` 조회 기간 (일).
+const PREVIEW_DAYS: i64 = 7;
+
+/// `/preview`에 보여줄 최근 매칭 공지 수.
+const PREVIEW_SHOWN: usize = 5;
+
+/// `/preview`가 훑어볼 최대 공지 수.
+const PREVIEW_SCAN_LIMIT: usize = 2000;
+
+/// 구독 전에 키워드가 최근 공지에 얼마나 걸렸을지 보여준다.
+/// 매칭은 DM 발송과 같은 `keyword_matches`를 쓴다.
+fn handle_preview(state: &BotState, keyword: &str) -> String {
     let keyword = keyword.trim();
-    if keyword.is_empty() {
-        return "\u{26a0}\u{fe0f} 키워드를 입력하세요.\n예: /unsub 장학금".to_string();
+    if keyword.is_empty() || parse_keyword(keyword) == (KeywordTarget::Author, "") {
+        return "\u{26a0}\u{fe0f} 키워드를 입력하세요.\n예: /preview 장학금".to_string();
+    }
+    let keyword = normalize_keyword(keyword);
+
+    let notices = match state.db.lock().unwrap().get_recent_days(PREVIEW_DAYS, PREVIEW_SCAN_LIMIT) {
+        Ok(n) => n,
+        Err(e) => return format!("\u{274c} 조회 실패: {}", e),
+    };
+    let matched: Vec<&Notice> = notices.iter().filter(|n| keyword_matches(n, &keyword, state.match_body)).collect();
+    if matched.is_empty() {
+        return format!(
+            "\u{1f50e} <b>{}</b>: 최근 {}일간 매칭된 공지가 없습니다.",
+            html_escape(&keyword),
+            PREVIEW_DAYS
+        );
+    }
+
+    let mut text = format!(
+        "\u{1f50e} <b>{}</b>: 최근 {}일간 <b>{}건</b> 매칭\n\n",
+        html_escape(&keyword),
+        PREVIEW_DAYS,
+        matched.len()
+    );
+    for notice in matched.iter().take(PREVIEW_SHOWN) {
+        let category = Category::from_str_tag(&notice.category);
+        text.push_str(&format!(
+            "{} <a href=\"{}\">{}</a>\n",
+            category.emoji(),
+            html_escape(&notice.url),
+            html_escape(&notice.title),
+        ));
+    }
+    text.push_str(&format!("\n구독하려면 /sub {}", html_escape(&keyword)));
+    text
+}
+
+/// 키워드 최대 길이 (바이트).
+const KEYWORD_MAX_LEN: usize = 50;
+
+/// `/sub`로 받을 수 없는 키워드.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InvalidKeyword {
+    TooLong,
+    NoAuthor,
+}
+
+impl InvalidKeyword {
+    /// 키워드 하나만 입력했을 때의 안내.
+    fn message(self) -> &'static str {
+        match self {
+            Self::TooLong => "\u{26a0}\u{fe0f} 키워드가 너무 깁니다 (최대 50자).",
+            Self::NoAuthor => "\u{26a0}\u{fe0f} 작성자를 입력하세요.\n예: /sub author:학사과",
+        }
+    }
+
+    /// 여러 개를 한꺼번에 입력했을 때 키워드 옆에 붙이는 사유.
+    fn reason(self) -> &'static str {
+        match self {
+            Self::TooLong => "너무 김 (최대 50자)",
+            Self::NoAuthor => "작성자 없음",
+        }
     }
+}
+
+/// 키워드 하나의 처리 결과 (`/sub`, `/unsub` 공통).
+#[derive(Debug, PartialEq)]
+enum KeywordOutcome {
+    /// 추가/해제됨.
+    Changed,
+    /// 이미 구독 중 (`/sub`) 또는 구독 중이 아님 (`/unsub`).
+    Unchanged,
+    Invalid(InvalidKeyword),
+    Failed(String),
+}
 
+/// `/sub` 키워드 검증.
+fn validate_sub_keyword(keyword: &str) -> Result<(), InvalidKeyword> {
+    if keyword.len() > KEYWORD_MAX_LEN {
+        return Err(InvalidKeyword::TooLong);
+    }
+    if parse_keyword(keyword) == (KeywordTarget::Author, "") {
+        return Err(InvalidKeyword::NoAuthor);
+    }
+    Ok(())
+}
+
+/// `장학금, 채용` → 키워드마다 검증 후 `apply`(DB 추가/삭제)를 적용한다.
+/// 반환: (키워드, 결과). 통과한 키워드는 저장 형태로 정규화된다.
+fn apply_keywords(
+    input: &str,
+    validate: fn(&str) -> Result<(), InvalidKeyword>,
+    mut apply: impl FnMut(&str) -> anyhow::Result<bool>,
+) -> Vec<(String, KeywordOutcome)> {
+    let mut results: Vec<(String, KeywordOutcome)> = Vec::new();
+    for raw in input.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        if let Err(invalid) = validate(raw) {
+            results.push((raw.to_string(), KeywordOutcome::Invalid(invalid)));
+            continue;
+        }
+        let keyword = normalize_keyword(raw);
+        // 같은 입력에 두 번 쓴 키워드는 한 번만
+        if results.iter().any(|(k, _)| *k == keyword) {
+            continue;
+        }
+        let outcome = match apply(&keyword) {
+            Ok(true) => KeywordOutcome::Changed,
+            Ok(false) => KeywordOutcome::Unchanged,
+            Err(e) => KeywordOutcome::Failed(e.to_string()),
+        };
+        results.push((keyword, outcome));
+    }
+    results
+}
+
+/// 여러 키워드 결과를 한 줄로: `✅ 장학금, ℹ️ 채용 이미 구독, ❌ author: 작성자 없음`.
+fn summarize_keywords(results: &[(String, KeywordOutcome)], unchanged: &str) -> String {
+    results
+        .iter()
+        .map(|(keyword, outcome)| {
+            let keyword = html_escape(keyword);
+            match outcome {
+                KeywordOutcome::Changed => format!("\u{2705} {}", keyword),
+                KeywordOutcome::Unchanged => format!("\u{2139}\u{fe0f} {} {}", keyword, unchanged),
+                KeywordOutcome::Invalid(invalid) => format!("\u{274c} {} ({})", keyword, invalid.reason()),
+                KeywordOutcome::Failed(e) => format!("\u{274c} {} ({})", keyword, html_escape(e)),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn handle_sub(state: &BotState, user_id: i64, input: &str) -> String {
     let db = state.db.lock().unwrap();
-    match db.remove_keyword_sub(user_id, keyword) {
-        Ok(true) => format!("\u{2705} '{}' 구독 해제 완료!", keyword),
-        Ok(false) => format!("\u{2139}\u{fe0f} '{}' 구독 중이 아닙니다.", keyword),
-        Err(e) => format!("\u{274c} 해제 실패: {}", e),
+    let results = apply_keywords(input, validate_sub_keyword, |k| db.add_keyword_sub(user_id, k));
+    match results.as_slice() {
+        [] => "\u{26a0}\u{fe0f} 키워드를 입력하세요.\n예: /sub 장학금".to_string(),
+        [(keyword, outcome)] => match outcome {
+            KeywordOutcome::Changed => format!("\u{2705} '{}' 키워드 구독 완료!", keyword),
+            KeywordOutcome::Unchanged => format!("\u{2139}\u{fe0f} '{}' 이미 구독 중입니다.", keyword),
+            KeywordOutcome::Invalid(invalid) => invalid.message().to_string(),
+            KeywordOutcome::Failed(e) => format!("\u{274c} 구독 실패: {}", e),
+        },
+        _ => summarize_keywords(&results, "이미 구독"),
     }
 }
 
-fn handle_dept(state: &BotState, user_id: i64, source_key: &str) -> String {
-    let source_key = source_key.trim();
+fn handle_unsub(state: &BotState, user_id: i64, input: &str) -> String {
+    let db = state.db.lock().unwrap();
+    let results = apply_keywords(input, |_| Ok(()), |k| db.remove_keyword_sub(user_id, k));
+    match results.as_slice() {
+        [] => "\u{26a0}\u{fe0f} 키워드를 입력하세요.\n예: /unsub 장학금".to_string(),
+        [(keyword, outcome)] => match outcome {
+            KeywordOutcome::Changed => format!("\u{2705} '{}' 구독 해제 완료!", keyword),
+            KeywordOutcome::Unchanged | KeywordOutcome::Invalid(_) => {
+                format!("\u{2139}\u{fe0f} '{}' 구독 중이 아닙니다.", keyword)
+            }
+            KeywordOutcome::Failed(e) => format!("\u{274c} 해제 실패: {}", e),
+        },
+        _ => summarize_keywords(&results, "구독 중 아님"),
+    }
+}
+
+fn handle_dept(state: &BotState, user_id: i64, args: &str) -> String {
+    // "/dept biz 장학,채용" → 학과 코드 + 카테고리 필터
+    let mut parts = args.trim().splitn(2, char::is_whitespace);
+    let source_key = parts.next().unwrap_or("").trim();
+    let filter_arg = parts.next().unwrap_or("").trim();
     if source_key.is_empty() {
         return "\u{26a0}\u{fe0f} 학과 코드를 입력하세요.\n/sources 로 목록을 확인하세요."
             .to_string();
@@ -166,19 +1089,87 @@ fn handle_dept(state: &BotState, user_id: i64, source_key: &str) -> String {
         );
     }
 
+    let mut categories: Vec<Category> = Vec::new();
+    for name in filter_arg.split(|c: char| c == ',' || c.is_whitespace()).filter(|n| !n.is_empty()) {
+        match Category::lookup(name) {
+            Some(c) if !categories.contains(&c) => categories.push(c),
+            Some(_) => {}
+            None => {
+                return format!(
+                    "\u{274c} '{}' 는 알 수 없는 분류입니다.\n예: /dept {} 장학,채용",
+                    html_escape(name),
+                    source_key
+                )
+            }
+        }
+    }
+    let tags: Vec<String> = categories.iter().map(|c| c.as_str().to_string()).collect();
+    let labels: Vec<&str> = categories.iter().map(|c| c.label()).collect();
+
     let db = state.db.lock().unwrap();
-    match db.add_source_sub(user_id, source_key) {
-        Ok(true) => {
-            let display = state
-                .sources
-                .iter()
-                .find(|s| s.key == source_key)
-                .map(|s| s.display_name.as_str())
-                .unwrap_or(source_key);
-            format!("\u{2705} {} 구독 완료!", display)
+    let display = state
+        .sources
+        .iter()
+        .find(|s| s.key == source_key)
+        .map(|s| s.display_name.as_str())
+        .unwrap_or(source_key);
+    let added = match db.add_source_sub(user_id, source_key) {
+        Ok(added) => added,
+        Err(e) => return format!("\u{274c} 구독 실패: {}", e),
+    };
+    if let Err(e) = db.set_source_sub_categories(user_id, source_key, &tags) {
+        return format!("\u{274c} 구독 실패: {}", e);
+    }
+
+    let filter_note = if labels.is_empty() {
+        String::new()
+    } else {
+        format!(" (분류: {})", labels.join(", "))
+    };
+    if added {
+        format!("\u{2705} {} 구독 완료!{}", display, filter_note)
+    } else if !labels.is_empty() {
+        format!("\u{2705} {} 구독 분류를 변경했습니다{}", display, filter_note)
+    } else {
+        format!("\u{2139}\u{fe0f} '{}' 이미 구독 중입니다.", source_key)
+    }
+}
+
+fn handle_lang(state: &BotState, user_id: i64, input: &str) -> String {
+    let input = input.trim();
+    let db = state.db.lock().unwrap();
+    if input.is_empty() {
+        return match db.get_user_lang(user_id) {
+            Ok(Some(lang)) => format!(
+                "\u{1f310} 선호 언어: {}\n해제: /lang off",
+                crate::lang::label(&lang)
+            ),
+            Ok(None) => "\u{1f310} 선호 언어가 없습니다.\n예: /lang en, /lang ko".to_string(),
+            Err(e) => format!("\u{274c} 조회 실패: {}", e),
+        };
+    }
+    if matches!(input.to_lowercase().as_str(), "off" | "auto") {
+        return match db.set_user_lang(user_id, None) {
+            Ok(()) => "\u{2705} 선호 언어를 해제했습니다.".to_string(),
+            Err(e) => format!("\u{274c} 설정 실패: {}", e),
+        };
+    }
+    let lang = match crate::lang::normalize(input) {
+        Some(lang) => lang,
+        None => {
+            return format!(
+                "\u{274c} '{}' 는 지원하지 않는 언어입니다. ({})",
+                html_escape(input),
+                crate::lang::LANGS.join(", ")
+            )
         }
-        Ok(false) => format!("\u{2139}\u{fe0f} '{}' 이미 구독 중입니다.", source_key),
-        Err(e) => format!("\u{274c} 구독 실패: {}", e),
+    };
+    match db.set_user_lang(user_id, Some(lang)) {
+        Ok(()) => format!(
+            "\u{2705} 선호 언어: {}\n이 언어의 공지를 먼저 보내고, 다른 언어 공지에는 표시를 붙입니다.",
+            crate::lang::label(lang)
+        ),
+        Err(e) => format!("\u{274c} 설정 실패: {}", e),
     }
 }
 
@@ -196,6 +1187,50 @@ fn handle_undept(state: &BotState, user_id: i64, source_key: &str) -> String {
     }
 }
 
+/// `/whoami`: DB가 알고 있는 그대로 (내부 소스 키, 등록 시각 포함) 고정폭으로.
+fn handle_whoami(state: &BotState, user_id: i64) -> String {
+    let db = state.db.lock().unwrap();
+    let user = match db.get_user_debug(user_id) {
+        Ok(Some(user)) => user,
+        Ok(None) => return "\u{1f4ed} 등록된 사용자 정보가 없습니다. /start 를 먼저 보내주세요.".to_string(),
+        Err(e) => return format!("\u{274c} 조회 실패: {}", e),
+    };
+    let or_null = |v: &Option<String>| v.clone().unwrap_or_else(|| "NULL".into());
+
+    let mut text = format!(
+        "telegram_id = {}\nusername    = {}\nfirst_name  = {}\nregistered  = {}\nis_active   = {}\nlang        = {}\n",
+        user.telegram_id,
+        or_null(&user.username),
+        or_null(&user.first_name),
+        user.registered,
+        user.is_active as i32,
+        or_null(&user.lang),
+    );
+    text.push_str(&format!("\nkeyword_subs ({})\n", user.keywords.len()));
+    for (keyword, created_at) in &user.keywords {
+        text.push_str(&format!("  {}  {}\n", created_at, keyword));
+    }
+    text.push_str(&format!("\nsource_subs ({})\n", user.sources.len()));
+    for (key, categories, created_at) in &user.sources {
+        match categories {
+            Some(c) => text.push_str(&format!("  {}  {}  [{}]\n", created_at, key, c)),
+            None => text.push_str(&format!("  {}  {}\n", created_at, key)),
+        }
+    }
+
+    // 구독이 아주 많으면 한 메시지에 들어가는 줄까지만
+    let mut body = String::new();
+    for line in html_escape(text.trim_end()).lines() {
+        if body.chars().count() + line.chars().count() + 20 > MESSAGE_MAX_CHARS {
+            body.push('…');
+            break;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    format!("<pre>{}</pre>", body.trim_end())
+}
+
 fn handle_mysubs(state: &BotState, user_id: i64) -> String {
     let db = state.db.lock().unwrap();
     match db.get_user_subs(user_id) {
@@ -225,10 +1260,25 @@ fn handle_mysubs(state: &BotState, user_id: i64) -> String {
                         .find(|s| s.key == *src)
                         .map(|s| s.display_name.as_str())
                         .unwrap_or(src.as_str());
-                    text.push_str(&format!("  • {} ({})\n", display, src));
+                    let filter = subs
+                        .source_categories
+                        .get(src)
+                        .map(|tags| {
+                            let labels: Vec<String> = tags
+                                .iter()
+                                .map(|t| Category::from_str_tag(t).label().to_string())
+                                .collect();
+                            format!(" — {}", labels.join(", "))
+                        })
+                        .unwrap_or_default();
+                    text.push_str(&format!("  • {} ({}){}\n", display, src, filter));
                 }
             }
 
+            if let Ok(Some(lang)) = db.get_user_lang(user_id) {
+                text.push_str(&format!("\n\u{1f310} 선호 언어: {}\n", crate::lang::label(&lang)));
+            }
+
             text
         }
         Err(e) => format!("\u{274c} 조회 실패: {}", e),
@@ -236,15 +1286,678 @@ fn handle_mysubs(state: &BotState, user_id: i64) -> String {
 }
 
 fn handle_sources(state: &BotState) -> String {
+    let summary = state.db.lock().unwrap().get_sources_summary().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Failed to load sources summary");
+        Vec::new()
+    });
+
     let mut text = "\u{1f4da} <b>사용 가능한 소스 목록</b>\n\n".to_string();
     for src in &state.sources {
         let status = if src.enabled { "\u{2705}" } else { "\u{23f8}\u{fe0f}" };
+        let activity = match summary.iter().find(|s| s.source_key == src.key) {
+            Some(s) => format!(
+                " — {}건, 최근 {}",
+                group_thousands(s.notice_count),
+                s.last_success
+                    .as_deref()
+                    .map(kst::humanize_ago)
+                    .unwrap_or_else(|| "성공 기록 없음".to_string())
+            ),
+            None => String::new(),
+        };
         text.push_str(&format!(
-            "{} <code>{}</code> — {}\n",
-            status, src.key, src.display_name
+            "{} <code>{}</code> — {}{}\n",
+            status,
+            src.key,
+            html_escape(&src.display_name),
+            activity
+        ));
+    }
+    text.push_str("\n\u{1f4a1} 아래 버튼이나 /dept &lt;코드&gt; 로 구독하세요!");
+    text
+}
+
+/// 1204 → "1,204".
+fn group_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn handle_recent(state: &BotState, source_key: &str) -> (String, Option<InlineKeyboardMarkup>) {
+    let source_key = source_key.trim();
+    if source_key.is_empty() {
+        return (
+            "\u{26a0}\u{fe0f} 학과 코드를 입력하세요.\n예: /recent biz".to_string(),
+            None,
+        );
+    }
+
+    let source = match state.sources.iter().find(|s| s.key == source_key) {
+        Some(s) => s,
+        None => {
+            return (
+                format!(
+                    "\u{274c} '{}' 는 유효한 소스가 아닙니다.\n/sources 로 목록을 확인하세요.",
+                    html_escape(source_key)
+                ),
+                None,
+            )
+        }
+    };
+
+    let db = state.db.lock().unwrap();
+    let notices = match db.get_latest_by_source(source_key, RECENT_LIMIT) {
+        Ok(n) => n,
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+
+    if notices.is_empty() {
+        return (
+            format!("\u{1f4ed} {} 에 저장된 공지가 없습니다.", html_escape(&source.display_name)),
+            None,
+        );
+    }
+
+    let header = format!("\u{1f195} <b>{} 최신 공지</b>\n\n", html_escape(&source.display_name));
+    let (text, buttons) = render_notice_list(header, &notices, 0);
+    let keyboard = InlineKeyboardMarkup::new(buttons);
+    (text, Some(keyboard))
+}
+
+/// 인라인 쿼리 핸들러 (`@봇이름 장학금`). 빈 쿼리면 최신 공지를 보여준다.
+pub async fn handle_inline_query(
+    bot: Bot,
+    q: InlineQuery,
+    state: Arc<BotState>,
+) -> ResponseResult<()> {
+    let query = q.query.trim();
+    let result = {
+        let db = state.db.lock().unwrap();
+        if query.is_empty() {
+            db.get_latest(INLINE_LIMIT, 0)
+        } else {
+            db.search_notices(query, INLINE_LIMIT, 0)
+        }
+    };
+    let notices = match result {
+        Ok(n) => n,
+        Err(e) => {
+            tracing::error!(error = %e, "Inline query failed");
+            Vec::new()
+        }
+    };
+
+    let results = build_inline_results(&notices, &state.sources);
+    bot.answer_inline_query(q.id, results)
+        .cache_time(INLINE_CACHE_SECS)
+        .await?;
+    Ok(())
+}
+
+/// 공지 목록 → 인라인 결과 (제목, 본문 미리보기, 원문 링크).
+fn build_inline_results(notices: &[Notice], sources: &[SourceConfig]) -> Vec<InlineQueryResult> {
+    notices
+        .iter()
+        .take(INLINE_LIMIT)
+        .map(|notice| {
+            let category = Category::from_str_tag(&notice.category);
+            let display = sources
+                .iter()
+                .find(|s| s.key == notice.source_key)
+                .map(|s| s.display_name.as_str())
+                .unwrap_or(&notice.source_display_name);
+            let date = notice.published.as_deref().unwrap_or("날짜 미상");
+
+            let text = format!(
+                "{} <b>{}</b>\n\u{1f3db} {} · \u{1f4c5} {}\n\u{1f517} <a href=\"{}\">원문 보기</a>",
+                category.emoji(),
+                html_escape(&notice.title),
+                html_escape(display),
+                html_escape(date),
+                html_escape(&notice.url),
+            );
+            let content = InputMessageContent::Text(
+                InputMessageContentText::new(text).parse_mode(ParseMode::Html),
+            );
+
+            let description = match notice.body.as_deref().filter(|b| !b.is_empty()) {
+                Some(body) => body_snippet(body),
+                None => format!("{} · {}", display, date),
+            };
+            let mut article = InlineQueryResultArticle::new(
+                notice.id.to_string(),
+                format!("{} {}", category.emoji(), notice.title),
+                content,
+            )
+            .description(description);
+            if let Ok(url) = reqwest::Url::parse(&notice.url) {
+                article = article.url(url);
+            }
+            InlineQueryResult::Article(article)
+        })
+        .collect()
+}
+
+/// 공지 목록 본문과 번호별 원문 링크 버튼(5개씩 한 줄) 생성.
+/// `offset`은 페이지 번호 매김 시작값.
+fn render_notice_list(
+    header: String,
+    notices: &[Notice],
+    offset: usize,
+) -> (String, Vec<Vec<InlineKeyboardButton>>) {
+    let mut text = header;
+    let mut buttons = Vec::new();
+    for (i, notice) in notices.iter().enumerate() {
+        let n = offset + i + 1;
+        let category = Category::from_str_tag(&notice.category);
+        text.push_str(&format!(
+            "{}. {} {}\n   \u{1f4c5} {}\n",
+            n,
+            category.emoji(),
+            html_escape(&notice.title),
+            html_escape(notice.published.as_deref().unwrap_or("날짜 미상")),
+        ));
+        if let Ok(url) = reqwest::Url::parse(&notice.url) {
+            buttons.push(InlineKeyboardButton::url(n.to_string(), url));
+        }
+    }
+    let rows = buttons.chunks(5).map(|row| row.to_vec()).collect();
+    (text, rows)
+}
+
+/// `/stats` 집계 기간 (주)과 막대 최대 길이.
+const STATS_WEEKS: u32 = 8;
+const STATS_BAR_WIDTH: u32 = 16;
+
+/// 소스의 최근 8주 주별 공지 수를 막대그래프로.
+fn handle_stats(state: &BotState, source_key: &str) -> String {
+    if source_key.is_empty() {
+        return "\u{26a0}\u{fe0f} 학과 코드를 입력하세요.\n예: /stats biz".to_string();
+    }
+    let Some(source) = state.sources.iter().find(|s| s.key == source_key) else {
+        return format!(
+            "\u{274c} '{}' 는 유효한 소스가 아닙니다.\n/sources 로 목록을 확인하세요.",
+            html_escape(source_key)
+        );
+    };
+    let counts = match state.db.lock().unwrap().weekly_counts(source_key, STATS_WEEKS) {
+        Ok(c) => c,
+        Err(e) => return format!("\u{274c} 조회 실패: {}", e),
+    };
+
+    let mut text = format!(
+        "\u{1f4c8} <b>{} 주별 공지 수</b> (최근 {}주)\n\n<pre>",
+        html_escape(&source.display_name),
+        STATS_WEEKS
+    );
+    text.push_str(&render_week_bars(&counts, kst::today()));
+    text.push_str(&format!(
+        "</pre>\n합계 {}건 · 주 평균 {:.1}건",
+        counts.iter().sum::<u32>(),
+        counts.iter().sum::<u32>() as f64 / counts.len().max(1) as f64
+    ));
+    text
+}
+
+/// 주별 개수 → "02/03~ ████ 4" 줄들. 마지막 칸이 이번 주.
+fn render_week_bars(counts: &[u32], today: chrono::NaiveDate) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    let mut out = String::new();
+    for (i, &n) in counts.iter().enumerate() {
+        let weeks_ago = (counts.len() - 1 - i) as i64;
+        let start = today - chrono::Duration::days(weeks_ago * 7 + 6);
+        let len = (n * STATS_BAR_WIDTH).div_ceil(max) as usize;
+        out.push_str(&format!(
+            "{}~ {}{} {}\n",
+            start.format("%m/%d"),
+            "\u{2588}".repeat(len),
+            if n == 0 { "\u{00b7}" } else { "" },
+            n
+        ));
+    }
+    out
+}
+
+/// `/trending` 기간 (일)과 표시 개수.
+const TRENDING_DAYS: i64 = 7;
+const TRENDING_LIMIT: usize = 10;
+
+/// 최근 7일 조회수 상위 공지.
+fn handle_trending(state: &BotState) -> (String, Option<InlineKeyboardMarkup>) {
+    let trending = match state.db.lock().unwrap().get_trending(TRENDING_DAYS, TRENDING_LIMIT) {
+        Ok(t) => t,
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+    if trending.is_empty() {
+        return ("\u{1f4ed} 최근 7일간 조회수 정보가 있는 공지가 없습니다.".to_string(), None);
+    }
+
+    let mut text = "\u{1f525} <b>최근 7일 인기 공지</b>\n\n".to_string();
+    let mut buttons = Vec::new();
+    for (i, (notice, views)) in trending.iter().enumerate() {
+        let n = i + 1;
+        let category = Category::from_str_tag(&notice.category);
+        let display = state
+            .sources
+            .iter()
+            .find(|s| s.key == notice.source_key)
+            .map(|s| s.display_name.as_str())
+            .unwrap_or(&notice.source_key);
+        text.push_str(&format!(
+            "{}. {} {}\n   \u{1f441} {} · {}\n",
+            n,
+            category.emoji(),
+            html_escape(&notice.title),
+            views,
+            html_escape(display),
+        ));
+        if let Ok(url) = reqwest::Url::parse(&notice.url) {
+            buttons.push(InlineKeyboardButton::url(n.to_string(), url));
+        }
+    }
+    let rows: Vec<Vec<_>> = buttons.chunks(5).map(|row| row.to_vec()).collect();
+    (text, Some(InlineKeyboardMarkup::new(rows)))
+}
+
+/// `/top`에 보여줄 공지 수.
+const TOP_LIMIT: usize = 10;
+
+/// `/top <분류>`: 전체 소스에서 해당 분류의 최신 공지.
+fn handle_top(state: &BotState, input: &str) -> (String, Option<InlineKeyboardMarkup>) {
+    let usage = "예: /top 장학 (학사/장학/채용/모집/행사/국제)";
+    if input.is_empty() {
+        return (format!("\u{26a0}\u{fe0f} 분류를 입력하세요.\n{}", usage), None);
+    }
+    let category = match stored_category(input) {
+        Some(c) => c,
+        None => {
+            return (
+                format!("\u{274c} '{}' 는 알 수 없는 분류입니다.\n{}", html_escape(input), usage),
+                None,
+            )
+        }
+    };
+    let notices = match state.db.lock().unwrap().get_by_category(category.as_str(), TOP_LIMIT) {
+        Ok(n) => n,
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+    if notices.is_empty() {
+        return (format!("\u{1f4ed} {} 공지가 아직 없습니다.", category), None);
+    }
+
+    let mut text = format!("{} <b>{} 최신 공지</b>\n\n", category.emoji(), html_escape(category.label()));
+    let mut buttons = Vec::new();
+    for (i, notice) in notices.iter().enumerate() {
+        let n = i + 1;
+        let display = state
+            .sources
+            .iter()
+            .find(|s| s.key == notice.source_key)
+            .map(|s| s.display_name.as_str())
+            .unwrap_or(&notice.source_key);
+        text.push_str(&format!(
+            "{}. {}\n   {} · {}\n",
+            n,
+            html_escape(&notice.title),
+            html_escape(display),
+            html_escape(notice.published.as_deref().unwrap_or("날짜 미상")),
+        ));
+        if let Ok(url) = reqwest::Url::parse(&notice.url) {
+            buttons.push(InlineKeyboardButton::url(n.to_string(), url));
+        }
+    }
+    let rows: Vec<Vec<_>> = buttons.chunks(5).map(|row| row.to_vec()).collect();
+    (text, Some(InlineKeyboardMarkup::new(rows)))
+}
+
+/// 사용자가 입력한 분류 이름 → DB에 저장되는 분류.
+/// 태그로 되돌렸을 때 같은 분류가 나와야 DB에 저장된 값과 맞는다.
+fn stored_category(input: &str) -> Option<Category> {
+    Category::lookup(input).filter(|c| Category::from_str_tag(c.as_str()) == *c)
+}
+
+/// `/random [분류]`: 아카이브에서 공지 하나를 골라 원문 버튼과 함께.
+fn handle_random(state: &BotState, input: &str) -> (String, Option<InlineKeyboardMarkup>) {
+    let category = if input.is_empty() {
+        None
+    } else {
+        match stored_category(input) {
+            Some(c) => Some(c),
+            None => {
+                return (
+                    format!(
+                        "\u{274c} '{}' 는 알 수 없는 분류입니다.\n예: /random 장학 (학사/장학/채용/모집/행사/국제)",
+                        html_escape(input)
+                    ),
+                    None,
+                )
+            }
+        }
+    };
+    let notice = match state.db.lock().unwrap().get_random(category.as_ref().map(|c| c.as_str())) {
+        Ok(Some(n)) => n,
+        Ok(None) => {
+            let text = match &category {
+                Some(c) => format!("\u{1f4ed} {} 공지가 아직 없습니다.", c),
+                None => "\u{1f4ed} 아직 저장된 공지가 없습니다.".to_string(),
+            };
+            return (text, None);
+        }
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+
+    let display = state
+        .sources
+        .iter()
+        .find(|s| s.key == notice.source_key)
+        .map(|s| s.display_name.as_str())
+        .unwrap_or(&notice.source_key);
+    let text = format!(
+        "\u{1f3b2} <b>지난 공지 하나</b>\n\n{} {}\n{} · {}",
+        Category::from_str_tag(&notice.category).emoji(),
+        html_escape(&notice.title),
+        html_escape(display),
+        html_escape(notice.published.as_deref().unwrap_or("날짜 미상")),
+    );
+    let keyboard = reqwest::Url::parse(&notice.url)
+        .ok()
+        .map(|url| InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url("원문 보기", url)]]));
+    (text, keyboard)
+}
+
+/// `/deadlines`에 보여줄 공지 수.
+const DEADLINE_LIMIT: usize = 10;
+
+/// `/deadlines`가 구독 필터 전에 훑어볼 마감 공지 수.
+const DEADLINE_SCAN_LIMIT: usize = 500;
+
+/// 사용자의 구독(키워드/학과)에 걸리는 공지인지. DM 매칭과 같은 기준.
+fn matches_subs(subs: &UserSubs, notice: &Notice, match_body: bool) -> bool {
+    if subs.keywords.iter().any(|k| keyword_matches(notice, k, match_body)) {
+        return true;
+    }
+    subs.sources.contains(&notice.source_key)
+        && subs
+            .source_categories
+            .get(&notice.source_key)
+            .is_none_or(|allowed| allowed.contains(&notice.category))
+}
+
+/// 구독 중인 공지 중 마감이 남은 것을 임박순으로 D-day와 함께 보여준다.
+fn handle_deadlines(state: &BotState, user_id: i64) -> (String, Option<InlineKeyboardMarkup>) {
+    let result = {
+        let db = state.db.lock().unwrap();
+        db.get_user_subs(user_id)
+            .and_then(|subs| Ok((db.get_deadline_notices(DEADLINE_SCAN_LIMIT, Some(user_id))?, subs)))
+    };
+    let (notices, subs) = match result {
+        Ok(r) => r,
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+    if subs.keywords.is_empty() && subs.sources.is_empty() {
+        return (
+            "\u{1f4ed} 구독이 없습니다.\n/sub 또는 /dept로 먼저 구독해주세요.".to_string(),
+            None,
+        );
+    }
+    let notices: Vec<_> = notices
+        .into_iter()
+        .filter(|(n, _)| matches_subs(&subs, n, state.match_body))
+        .take(DEADLINE_LIMIT)
+        .collect();
+    if notices.is_empty() {
+        return ("\u{1f4ed} 구독 중인 공지 중 마감이 남은 공지가 없습니다.".to_string(), None);
+    }
+
+    let today = kst::today();
+    let mut text = "\u{23f0} <b>마감 임박 공지</b>\n\n".to_string();
+    let mut buttons = Vec::new();
+    for (i, (notice, deadline)) in notices.iter().enumerate() {
+        let n = i + 1;
+        let d_day = parse_db_date(deadline)
+            .map(|d| format!("<b>{}</b> ", d_day(d, today)))
+            .unwrap_or_default();
+        text.push_str(&format!(
+            "{}. {}{}\n   마감 {}\n",
+            n,
+            d_day,
+            html_escape(&notice.title),
+            html_escape(deadline),
+        ));
+        if let Ok(url) = reqwest::Url::parse(&notice.url) {
+            buttons.push(InlineKeyboardButton::url(n.to_string(), url));
+        }
+    }
+    let rows: Vec<Vec<_>> = buttons.chunks(5).map(|row| row.to_vec()).collect();
+    (text, Some(InlineKeyboardMarkup::new(rows)))
+}
+
+/// 페이지 단위로 넘겨보는 목록 종류.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PageKind {
+    Latest,
+    Search,
+}
+
+impl PageKind {
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Latest => "l",
+            Self::Search => "s",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "l" => Some(Self::Latest),
+            "s" => Some(Self::Search),
+            _ => None,
+        }
+    }
+}
+
+/// 페이지 이동 요청 (callback_data로 왕복).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageRequest {
+    kind: PageKind,
+    offset: usize,
+    query: String,
+}
+
+/// 페이지 요청을 `pg|<종류>|<offset>|<검색어>` 형태로 인코딩.
+/// 64바이트를 넘거나 `#`으로 시작하는 검색어는 짧은 토큰(`#xxxxxxxx`)으로
+/// 바꾸고 원문은 `tokens`에 보관한다.
+fn encode_page_callback(page: &PageRequest, tokens: &Mutex<HashMap<String, String>>) -> String {
+    let prefix = format!("pg|{}|{}|", page.kind.tag(), page.offset);
+    if prefix.len() + page.query.len() <= CALLBACK_DATA_MAX && !page.query.starts_with('#') {
+        return format!("{}{}", prefix, page.query);
+    }
+
+    let mut hasher = DefaultHasher::new();
+    page.query.hash(&mut hasher);
+    let token = format!("#{:08x}", hasher.finish() as u32);
+    tokens
+        .lock()
+        .unwrap()
+        .insert(token.clone(), page.query.clone());
+    format!("{}{}", prefix, token)
+}
+
+/// `encode_page_callback`의 역. 모르는 형식이나 만료된 토큰이면 `None`.
+fn decode_page_callback(data: &str, tokens: &Mutex<HashMap<String, String>>) -> Option<PageRequest> {
+    let mut parts = data.splitn(4, '|');
+    if parts.next()? != "pg" {
+        return None;
+    }
+    let kind = PageKind::from_tag(parts.next()?)?;
+    let offset = parts.next()?.parse().ok()?;
+    let raw_query = parts.next()?;
+    let query = if raw_query.starts_with('#') {
+        tokens.lock().unwrap().get(raw_query)?.clone()
+    } else {
+        raw_query.to_string()
+    };
+    Some(PageRequest { kind, offset, query })
+}
+
+/// `/latest`, `/search` 한 페이지 렌더링 (이전/다음 버튼 포함).
+fn render_page(state: &BotState, page: &PageRequest) -> (String, Option<InlineKeyboardMarkup>) {
+    // 다음 페이지 존재 여부 확인을 위해 1개 더 조회
+    let result = {
+        let db = state.db.lock().unwrap();
+        match page.kind {
+            PageKind::Latest => db.get_latest(PAGE_SIZE + 1, page.offset),
+            PageKind::Search => db.search_notices(&page.query, PAGE_SIZE + 1, page.offset),
+        }
+    };
+    let mut notices = match result {
+        Ok(n) => n,
+        Err(e) => return (format!("\u{274c} 조회 실패: {}", e), None),
+    };
+
+    if notices.is_empty() {
+        let text = match page.kind {
+            PageKind::Latest => "\u{1f4ed} 저장된 공지가 없습니다.".to_string(),
+            PageKind::Search => format!("\u{1f50d} '{}' 검색 결과가 없습니다.", html_escape(&page.query)),
+        };
+        return (text, None);
+    }
+
+    let has_next = notices.len() > PAGE_SIZE;
+    notices.truncate(PAGE_SIZE);
+
+    let page_no = page.offset / PAGE_SIZE + 1;
+    let header = match page.kind {
+        PageKind::Latest => format!("\u{1f195} <b>최신 공지</b> ({}쪽)\n\n", page_no),
+        PageKind::Search => format!(
+            "\u{1f50d} <b>'{}' 검색 결과</b> ({}쪽)\n\n",
+            html_escape(&page.query),
+            page_no
+        ),
+    };
+    let (text, mut rows) = render_notice_list(header, &notices, page.offset);
+
+    let mut nav = Vec::new();
+    if page.offset > 0 {
+        let prev = PageRequest { offset: page.offset.saturating_sub(PAGE_SIZE), ..page.clone() };
+        nav.push(InlineKeyboardButton::callback(
+            "\u{25c0} 이전",
+            encode_page_callback(&prev, &state.query_tokens),
+        ));
+    }
+    if has_next {
+        let next = PageRequest { offset: page.offset + PAGE_SIZE, ..page.clone() };
+        nav.push(InlineKeyboardButton::callback(
+            "다음 \u{25b6}",
+            encode_page_callback(&next, &state.query_tokens),
+        ));
+    }
+    if !nav.is_empty() {
+        rows.push(nav);
+    }
+
+    (text, Some(InlineKeyboardMarkup::new(rows)))
+}
+
+fn handle_about(state: &BotState) -> String {
+    format!(
+        "\u{1f916} <b>충북대 공지 봇</b>\n\n\
+         버전: {} ({})\n\
+         가동 시간: {}\n\
+         활성 소스: {}개",
+        env!("CARGO_PKG_VERSION"),
+        env!("GIT_HASH"),
+        format_uptime(state.started.elapsed()),
+        state.sources.iter().filter(|s| s.enabled).count(),
+    )
+}
+
+/// 가동 시간 표시: "3일 4시간 5분" (0인 단위는 생략), 1분 미만은 "1분 미만".
+fn format_uptime(uptime: Duration) -> String {
+    let mins = uptime.as_secs() / 60;
+    let (days, hours, mins) = (mins / 1440, mins / 60 % 24, mins % 60);
+    let parts: Vec<String> = [(days, "일"), (hours, "시간"), (mins, "분")]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{}{}", n, unit))
+        .collect();
+    if parts.is_empty() {
+        "1분 미만".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// `/recat <URL> <분류>`: 잘못 분류된 공지를 고치고, 같은 제목이 다시 올라오면
+/// 고친 분류를 쓰도록 기록한다.
+fn handle_recat(state: &BotState, user_id: i64, args: &str) -> String {
+    let usage = "예: /recat https://www.chungbuk.ac.kr/... 장학";
+    let mut parts = args.split_whitespace();
+    let (Some(url), Some(name), None) = (parts.next(), parts.next(), parts.next()) else {
+        return format!("\u{26a0}\u{fe0f} 공지 URL과 분류를 입력하세요.\n{}", usage);
+    };
+    let category = match Category::lookup(name) {
+        Some(c) if Category::from_str_tag(c.as_str()) == c => c,
+        _ => return format!("\u{274c} '{}' 는 알 수 없는 분류입니다.\n{}", html_escape(name), usage),
+    };
+    match state.db.lock().unwrap().set_category_override(url, category.as_str(), user_id) {
+        Ok(Some(title)) => format!(
+            "\u{2705} 분류를 {} {}(으)로 고쳤습니다.\n{}\n같은 제목의 공지는 앞으로 이 분류로 저장됩니다.",
+            category.emoji(),
+            html_escape(category.label()),
+            html_escape(&title)
+        ),
+        Ok(None) => "\u{274c} 해당 URL의 공지를 찾을 수 없습니다.".to_string(),
+        Err(e) => format!("\u{274c} 저장 실패: {}", e),
+    }
+}
+
+/// `/errors`에 보여줄 에러 메시지 최대 길이 (문자).
+const ERROR_MESSAGE_CHARS: usize = 300;
+
+/// 실패 중인 소스 목록: 연속 실패 횟수, 마지막 크롤 시각, 마지막 에러.
+fn handle_errors(state: &BotState) -> String {
+    let stats = match state.db.lock().unwrap().get_crawl_stats() {
+        Ok(s) => s,
+        Err(e) => return format!("\u{274c} 조회 실패: {}", e),
+    };
+    let failing: Vec<_> = stats.iter().filter(|s| s.error_count > 0).collect();
+    if failing.is_empty() {
+        return "\u{2705} 실패 중인 소스가 없습니다.".to_string();
+    }
+
+    let mut text = format!("\u{26a0}\u{fe0f} <b>실패 중인 소스 {}개</b>\n", failing.len());
+    for stat in failing {
+        let display = state
+            .sources
+            .iter()
+            .find(|s| s.key == stat.source_key)
+            .map(|s| s.display_name.as_str())
+            .unwrap_or(&stat.source_key);
+        let error: String = stat
+            .last_error
+            .as_deref()
+            .unwrap_or("(기록 없음)")
+            .chars()
+            .take(ERROR_MESSAGE_CHARS)
+            .collect();
+        text.push_str(&format!(
+            "\n• <b>{}</b> (<code>{}</code>) — 연속 {}회{}\n   최근 시도: {}\n   <code>{}</code>\n",
+            html_escape(display),
+            html_escape(&stat.source_key),
+            stat.error_count,
+            if stat.is_cooling_down() { ", \u{23f8} 비활성" } else { "" },
+            stat.last_crawled.as_deref().unwrap_or("없음"),
+            html_escape(&error),
         ));
     }
-    text.push_str("\n\u{1f4a1} /dept &lt;코드&gt; 로 구독하세요!");
     text
 }
 
@@ -257,6 +1970,24 @@ fn handle_status(state: &BotState) -> String {
             }
 
             let mut text = "\u{1f4ca} <b>봇 상태</b>\n\n".to_string();
+            let usage = (|| -> anyhow::Result<_> {
+                Ok((
+                    db.get_user_count()?,
+                    db.get_inactive_user_count()?,
+                    db.get_keyword_sub_count()?,
+                    db.get_source_sub_count()?,
+                    db.get_sent_count_since(24)?,
+                ))
+            })();
+            match usage {
+                Ok((users, inactive, keywords, sources, sent)) => text.push_str(&format!(
+                    "\u{1f465} 사용자 {}명 (차단·비활성 {}명) · 키워드 구독 {}개 · 학과 구독 {}개\n\
+                     \u{1f4e8} 최근 24시간 발송 {}건\n\n",
+                    users, inactive, keywords, sources, sent
+                )),
+                Err(e) => tracing::warn!(error = %e, "Failed to load usage stats"),
+            }
+
             for stat in &stats {
                 let display = state
                     .sources
@@ -268,14 +1999,20 @@ fn handle_status(state: &BotState) -> String {
                     .last_crawled
                     .as_deref()
                     .unwrap_or("없음");
-                let err_icon = if stat.error_count > 0 {
+                let avg = stat
+                    .avg_duration_ms
+                    .map(|ms| format!(" · 평균 {:.1}s", ms / 1000.0))
+                    .unwrap_or_default();
+                let err_icon = if stat.is_cooling_down() {
+                    format!(" \u{23f8} 비활성(자동, {}회 실패)", stat.error_count)
+                } else if stat.error_count > 0 {
                     format!(" \u{26a0}\u{fe0f}({})", stat.error_count)
                 } else {
                     String::new()
                 };
                 text.push_str(&format!(
-                    "• {} — 최근: {}{}\n",
-                    display, last, err_icon
+                    "• {} — 최근: {}{}{}\n",
+                    display, last, avg, err_icon
                 ));
             }
             text
@@ -288,6 +2025,133 @@ fn handle_status(state: &BotState) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::from_secs(30)), "1분 미만");
+        assert_eq!(format_uptime(Duration::from_secs(5 * 60 + 59)), "5분");
+        assert_eq!(format_uptime(Duration::from_secs(2 * 3600)), "2시간");
+        assert_eq!(format_uptime(Duration::from_secs(3 * 86_400 + 4 * 3600 + 5 * 60)), "3일 4시간 5분");
+        assert_eq!(format_uptime(Duration::from_secs(86_400 + 60)), "1일 1분");
+    }
+
+    fn test_state() -> BotState {
+        BotState {
+            db: Arc::new(Mutex::new(Database::init(":memory:").unwrap())),
+            sources: Vec::new(),
+            query_tokens: Arc::new(Mutex::new(HashMap::new())),
+            admin: AdminConfig::default(),
+            message_delay_ms: 0,
+            log_channel: None,
+            channel: "@cbnu_notice".into(),
+            match_body: false,
+            crawl: None,
+            started: Instant::now(),
+            limiter: Arc::new(RateLimiter::new(0)),
+        }
+    }
+
+    #[test]
+    fn test_find_channel_post() {
+        let state = test_state();
+        {
+            let db = state.db.lock().unwrap();
+            for no in ["1", "2"] {
+                let raw = crate::parser::RawNotice {
+                    notice_id: no.into(),
+                    title: format!("공지{}", no),
+                    url: format!("https://biz.chungbuk.ac.kr/view?no={}", no),
+                    author: None,
+                    date: None,
+                    original_date: None,
+                    category: None,
+                    is_pinned: false,
+                    views: None,
+                };
+                db.insert_if_new("biz", &raw, "경영학부", false).unwrap();
+            }
+            db.set_channel_message_id(1, "@cbnu_notice", 501).unwrap();
+        }
+
+        // 공지 번호나 URL로 찾는다
+        let (id, post) = find_channel_post(&state, " 1 ").unwrap();
+        assert_eq!((id, post.message_id), (1, 501));
+        let (id, _) = find_channel_post(&state, "https://biz.chungbuk.ac.kr/view?no=1").unwrap();
+        assert_eq!(id, 1);
+
+        // 게시 기록이 없거나 없는 공지
+        let not_found = find_channel_post(&state, "2").unwrap_err();
+        assert!(not_found.contains("찾을 수 없습니다"), "{}", not_found);
+        assert_eq!(find_channel_post(&state, "99").unwrap_err(), not_found);
+        assert_eq!(find_channel_post(&state, "https://example.com/x").unwrap_err(), not_found);
+        assert!(find_channel_post(&state, "  ").unwrap_err().contains("입력하세요"));
+
+        // 이미 지운 게시물 (48시간 경과는 DB 테스트에서)
+        state.db.lock().unwrap().mark_deleted(1).unwrap();
+        assert!(find_channel_post(&state, "1").unwrap_err().contains("이미 삭제한"));
+    }
+
+    #[test]
+    fn test_bulk_keyword_sub() {
+        let state = test_state();
+        state.db.lock().unwrap().register_user(1, None, None).unwrap();
+
+        // 한 개는 기존 문구 그대로
+        assert_eq!(handle_sub(&state, 1, " 채용 "), "\u{2705} '채용' 키워드 구독 완료!");
+        assert_eq!(handle_sub(&state, 1, "채용"), "\u{2139}\u{fe0f} '채용' 이미 구독 중입니다.");
+        assert_eq!(handle_sub(&state, 1, " , "), handle_sub(&state, 1, ""));
+
+        let long = "가".repeat(20);
+        let reply = handle_sub(&state, 1, &format!("장학금, 채용,, author:,{}, 장학금", long));
+        assert_eq!(
+            reply,
+            format!(
+                "\u{2705} 장학금, \u{2139}\u{fe0f} 채용 이미 구독, \u{274c} author: (작성자 없음), \u{274c} {} (너무 김 (최대 50자))",
+                long
+            )
+        );
+        let subs = state.db.lock().unwrap().get_user_subs(1).unwrap();
+        assert_eq!(subs.keywords, vec!["장학금".to_string(), "채용".to_string()]);
+
+        let reply = handle_unsub(&state, 1, "장학금, 교환학생");
+        assert_eq!(reply, "\u{2705} 장학금, \u{2139}\u{fe0f} 교환학생 구독 중 아님");
+        assert_eq!(handle_unsub(&state, 1, "채용"), "\u{2705} '채용' 구독 해제 완료!");
+        assert!(state.db.lock().unwrap().get_user_subs(1).unwrap().keywords.is_empty());
+    }
+
+    #[test]
+    fn test_source_toggle() {
+        let mut state = test_state();
+        for (key, name) in [("biz", "경영학부"), ("math", "수학과")] {
+            state.sources.push(SourceConfig {
+                key: key.into(),
+                display_name: name.into(),
+                parser: "php_master".into(),
+                url: format!("https://{}.chungbuk.ac.kr", key),
+                ..Default::default()
+            });
+        }
+        state.db.lock().unwrap().register_user(1, None, None).unwrap();
+        let labels = |state: &BotState| -> Vec<String> {
+            sources_keyboard(state, 1, 0)
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .map(|b| b.text.clone())
+                .collect()
+        };
+        assert_eq!(labels(&state), vec!["\u{2795} 경영학부", "\u{2795} 수학과"]);
+
+        assert_eq!(toggle_source_sub(&state, 1, "biz"), Ok(true));
+        assert_eq!(state.db.lock().unwrap().get_user_subs(1).unwrap().sources, vec!["biz".to_string()]);
+        assert_eq!(labels(&state), vec!["\u{2705} 경영학부", "\u{2795} 수학과"]);
+
+        assert_eq!(toggle_source_sub(&state, 1, "biz"), Ok(false));
+        assert!(state.db.lock().unwrap().get_user_subs(1).unwrap().sources.is_empty());
+        // callback_data는 위조될 수 있으니 설정에 없는 키는 거절
+        assert!(toggle_source_sub(&state, 1, "nope").is_err());
+        assert!(state.db.lock().unwrap().get_user_subs(1).unwrap().sources.is_empty());
+    }
+
     #[test]
     fn test_commands_parse() {
         // Verify BotCommands derive works
@@ -296,4 +2160,135 @@ mod tests {
         assert!(text.contains("도움말"));
         assert!(text.contains("키워드 구독"));
     }
+
+    #[test]
+    fn test_guess_command() {
+        assert_eq!(edit_distance("serach", "search"), 2);
+        assert_eq!(edit_distance("", "sub"), 3);
+
+        // 띄어쓰기 빠진 명령어는 고쳐서 실행
+        assert_eq!(guess_command("/sub장학금"), CommandGuess::Split("/sub 장학금".into()));
+        assert_eq!(guess_command("/unsub장학금, 채용"), CommandGuess::Split("/unsub 장학금, 채용".into()));
+        assert_eq!(guess_command("/search기숙사"), CommandGuess::Split("/search 기숙사".into()));
+
+        let suggested = |text: &str| match guess_command(text) {
+            CommandGuess::Suggest(name, _) => Some(name),
+            _ => None,
+        };
+        assert_eq!(suggested("/serach 장학"), Some("search".into()));
+        assert_eq!(suggested("/HELP"), Some("help".into()));
+        assert_eq!(suggested("/subscribe 장학금"), Some("sub".into()));
+        assert_eq!(suggested("/unsubscribe"), Some("unsub".into()));
+        assert_eq!(suggested("/stat@cbnu_notice_bot"), Some("stats".into()));
+        assert_eq!(guess_command("/xyzzy"), CommandGuess::Unknown);
+        assert_eq!(guess_command("장학금"), CommandGuess::Unknown);
+        // 숨은 관리자 명령어는 제안하지 않는다
+        assert_ne!(suggested("/broadcst"), Some("broadcast".into()));
+    }
+
+    #[test]
+    fn test_render_week_bars() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let chart = render_week_bars(&[0, 2, 4], today);
+        let lines: Vec<_> = chart.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "02/18~ \u{00b7} 0");
+        assert_eq!(lines[1], format!("02/25~ {} 2", "\u{2588}".repeat(8)));
+        assert_eq!(lines[2], format!("03/04~ {} 4", "\u{2588}".repeat(16)));
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(1204), "1,204");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn test_without_dismiss_button() {
+        let markup = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::url("원문", reqwest::Url::parse("https://example.com").unwrap()),
+            InlineKeyboardButton::callback("확인", format!("{}42", DISMISS_PREFIX)),
+        ]]);
+        let stripped = without_dismiss_button(&markup);
+        assert_eq!(stripped.inline_keyboard.len(), 1);
+        assert_eq!(stripped.inline_keyboard[0].len(), 1);
+        assert_eq!(stripped.inline_keyboard[0][0].text, "원문");
+    }
+
+    #[test]
+    fn test_page_callback_round_trip() {
+        let tokens = Mutex::new(HashMap::new());
+
+        let latest = PageRequest { kind: PageKind::Latest, offset: 20, query: String::new() };
+        let data = encode_page_callback(&latest, &tokens);
+        assert_eq!(data, "pg|l|20|");
+        assert_eq!(decode_page_callback(&data, &tokens), Some(latest));
+
+        let search = PageRequest { kind: PageKind::Search, offset: 10, query: "장학금".into() };
+        let data = encode_page_callback(&search, &tokens);
+        assert!(data.len() <= CALLBACK_DATA_MAX);
+        assert_eq!(decode_page_callback(&data, &tokens), Some(search));
+        assert!(tokens.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_page_callback_long_query_uses_token() {
+        let tokens = Mutex::new(HashMap::new());
+        let long = PageRequest {
+            kind: PageKind::Search,
+            offset: 10,
+            query: "국가장학금 2차 신청 안내 및 가구원 정보제공 동의 방법".into(),
+        };
+        let data = encode_page_callback(&long, &tokens);
+        assert!(data.len() <= CALLBACK_DATA_MAX, "len = {}", data.len());
+        assert_eq!(decode_page_callback(&data, &tokens), Some(long));
+
+        // 토큰이 만료(재시작 등)되면 None
+        assert_eq!(decode_page_callback(&data, &Mutex::new(HashMap::new())), None);
+        assert_eq!(decode_page_callback("garbage", &tokens), None);
+    }
+
+    fn sample_notice(id: i64, body: Option<&str>) -> Notice {
+        Notice {
+            id,
+            source_key: "main".into(),
+            notice_id: id.to_string(),
+            title: format!("장학금 공지 {}", id),
+            url: format!("https://www.chungbuk.ac.kr/view?nttNo={}", id),
+            author: None,
+            category: "scholarship".into(),
+            published: Some("2026-02-06".into()),
+            source_display_name: "main".into(),
+            body: body.map(String::from),
+            edited: false,
+            urgent: false,
+        }
+    }
+
+    #[test]
+    fn test_build_inline_results() {
+        let mut notices: Vec<_> = (1..=25).map(|i| sample_notice(i, None)).collect();
+        notices[0].body = Some("신청 기간은 2월 10일까지입니다.".into());
+        let results = build_inline_results(&notices, &[]);
+        assert_eq!(results.len(), INLINE_LIMIT);
+
+        let InlineQueryResult::Article(first) = &results[0] else {
+            panic!("expected article");
+        };
+        assert_eq!(first.id, "1");
+        assert!(first.title.contains("장학금 공지 1"));
+        assert_eq!(first.description.as_deref(), Some("신청 기간은 2월 10일까지입니다."));
+        assert_eq!(
+            first.url.as_ref().map(|u| u.as_str()),
+            Some("https://www.chungbuk.ac.kr/view?nttNo=1")
+        );
+
+        // 본문이 없으면 소스/날짜로 설명
+        let InlineQueryResult::Article(second) = &results[1] else {
+            panic!("expected article");
+        };
+        assert_eq!(second.description.as_deref(), Some("main · 2026-02-06"));
+    }
 }
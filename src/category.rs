@@ -1,4 +1,8 @@
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Category {
@@ -7,15 +11,87 @@ pub enum Category {
     Recruit,
     Contest,
     Event,
+    /// 교환학생·국제교류·유학생 비자 등 국제처 공지.
+    International,
     General,
+    /// config `[[categories]]`로 정의한 사용자 카테고리.
+    Custom {
+        tag: String,
+        emoji: String,
+        label: String,
+    },
 }
 
-impl Category {
-    /// Classify a notice by title keywords. Priority order matters.
-    pub fn classify(title: &str) -> Self {
-        let t = title.to_lowercase();
+/// config의 `[[categories]]` 항목 하나. 순서대로 검사하며 먼저 맞는 규칙이 이긴다.
+#[derive(Deserialize, Clone, Debug)]
+pub struct CategoryRuleConfig {
+    pub tag: String,
+    pub emoji: Option<String>,
+    pub label: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+/// 제목 키워드 → 카테고리 분류 규칙표.
+#[derive(Debug, Clone)]
+pub struct CategoryRules {
+    rules: Vec<(Vec<String>, Category)>,
+    /// 게시판 자체 분류명(학사/장학 등) → 카테고리 태그.
+    aliases: HashMap<String, String>,
+}
 
-        let rules: &[(&[&str], Category)] = &[
+/// 내장 분류명 alias. eGov 게시판의 분류 컬럼 값 기준.
+const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("학사", "academic"),
+    ("수업", "academic"),
+    ("장학", "scholarship"),
+    ("채용", "recruit"),
+    ("인사", "recruit"),
+    ("모집", "contest"),
+    ("공모", "contest"),
+    ("행사", "event"),
+    ("국제", "international"),
+    ("국제교류", "international"),
+    ("일반", "general"),
+];
+
+/// `install_rules`로 설치된 전역 규칙표. 없으면 내장 규칙 사용.
+static RULES: OnceLock<CategoryRules> = OnceLock::new();
+
+/// 내장 규칙표 (config 미지정 시 fallback).
+fn builtin_rules() -> &'static CategoryRules {
+    static BUILTIN: OnceLock<CategoryRules> = OnceLock::new();
+    BUILTIN.get_or_init(CategoryRules::builtin)
+}
+
+/// 현재 적용 중인 규칙표.
+fn rules() -> &'static CategoryRules {
+    RULES.get().unwrap_or_else(|| builtin_rules())
+}
+
+/// 시작 시 config의 카테고리 규칙/alias를 전역으로 설치한다. 둘 다 비어 있으면 내장 규칙 유지.
+pub fn install_rules(configs: &[CategoryRuleConfig], aliases: &HashMap<String, String>) {
+    if configs.is_empty() && aliases.is_empty() {
+        return;
+    }
+    if RULES.set(CategoryRules::from_config(configs, aliases)).is_err() {
+        tracing::warn!("Category rules already installed; ignoring");
+    }
+}
+
+impl CategoryRules {
+    /// 하드코딩된 기본 규칙. Priority order matters.
+    pub fn builtin() -> Self {
+        // 국제 공지는 "2학기 교환학생 선발"처럼 학사/모집 키워드를 같이 달고 나오므로 맨 앞.
+        // 제목을 소문자로 바꿔 비교하므로 영문 키워드는 소문자로 적는다.
+        // "비자"만 쓰면 "소비자"에 걸리므로 뒤에 붙는 말까지 적는다.
+        let table: &[(&[&str], Category)] = &[
+            (
+                &[
+                    "교환학생", "국제교류", "topik", "비자(", "비자 발급", "비자 연장",
+                    "visa", "외국인 유학생", "유학생", "해외파견", "방문학생", "출입국",
+                ],
+                Category::International,
+            ),
             (
                 &[
                     "수강", "학점", "성적", "졸업", "휴학", "복학", "전과", "재입학", "수업",
@@ -52,14 +128,93 @@ impl Category {
             ),
         ];
 
-        for (keywords, category) in rules {
-            if keywords.iter().any(|k| t.contains(k)) {
+        Self {
+            rules: table
+                .iter()
+                .map(|(kws, cat)| (kws.iter().map(|k| k.to_string()).collect(), cat.clone()))
+                .collect(),
+            aliases: BUILTIN_ALIASES
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// config 규칙표 생성. 내장 태그(academic 등)에 emoji/label을 지정하지 않으면
+    /// 내장 카테고리를 그대로 쓴다. `configs`가 비어 있으면 내장 규칙을 쓰고,
+    /// `aliases`는 내장 alias에 덮어쓴다.
+    pub fn from_config(configs: &[CategoryRuleConfig], aliases: &HashMap<String, String>) -> Self {
+        let mut table = Self::builtin();
+        table
+            .aliases
+            .extend(aliases.iter().map(|(k, v)| (k.trim().to_string(), v.clone())));
+        if configs.is_empty() {
+            return table;
+        }
+
+        table.rules = configs
+            .iter()
+            .map(|c| {
+                let builtin = Category::builtin_from_tag(&c.tag);
+                let category = match (builtin, &c.emoji, &c.label) {
+                    (Some(b), None, None) => b,
+                    (b, emoji, label) => Category::Custom {
+                        tag: c.tag.clone(),
+                        emoji: emoji
+                            .clone()
+                            .or_else(|| b.as_ref().map(|b| b.emoji().to_string()))
+                            .unwrap_or_else(|| "\u{1f4e2}".to_string()),
+                        label: label
+                            .clone()
+                            .or_else(|| b.as_ref().map(|b| b.label().to_string()))
+                            .unwrap_or_else(|| c.tag.clone()),
+                    },
+                };
+                let keywords = c.keywords.iter().map(|k| k.to_lowercase()).collect();
+                (keywords, category)
+            })
+            .collect();
+        table
+    }
+
+    /// Classify a notice by title keywords.
+    pub fn classify(&self, title: &str) -> Category {
+        let t = title.to_lowercase();
+        for (keywords, category) in &self.rules {
+            if keywords.iter().any(|k| t.contains(k.as_str())) {
                 return category.clone();
             }
         }
         Category::General
     }
 
+    /// 게시판이 준 분류명을 alias로 먼저 매핑하고, 없거나 모르는 값이면 제목으로 분류.
+    pub fn classify_with_source(&self, raw_category: Option<&str>, title: &str) -> Category {
+        raw_category
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .and_then(|c| self.aliases.get(c))
+            .and_then(|tag| {
+                self.find_tag(tag)
+                    .cloned()
+                    .or_else(|| Category::builtin_from_tag(tag))
+            })
+            .unwrap_or_else(|| self.classify(title))
+    }
+
+    /// 태그로 규칙표의 카테고리 조회 (사용자 카테고리 복원용).
+    fn find_tag(&self, tag: &str) -> Option<&Category> {
+        self.rules.iter().map(|(_, c)| c).find(|c| c.as_str() == tag)
+    }
+}
+
+impl Category {
+    /// Classify a notice using the active rule table. 게시판 분류 컬럼이 있으면
+    /// 그것을 제목 키워드보다 우선한다.
+    pub fn classify_notice(raw_category: Option<&str>, title: &str) -> Self {
+        rules().classify_with_source(raw_category, title)
+    }
+
     pub fn emoji(&self) -> &str {
         match self {
             Self::Academic => "\u{1f4da}",     // 📚
@@ -67,7 +222,9 @@ impl Category {
             Self::Recruit => "\u{1f4bc}",      // 💼
             Self::Contest => "\u{1f4cb}",      // 📋
             Self::Event => "\u{1f3a4}",        // 🎤
+            Self::International => "\u{1f30f}", // 🌏
             Self::General => "\u{1f4e2}",      // 📢
+            Self::Custom { emoji, .. } => emoji,
         }
     }
 
@@ -78,7 +235,9 @@ impl Category {
             Self::Recruit => "채용",
             Self::Contest => "모집",
             Self::Event => "행사",
+            Self::International => "국제",
             Self::General => "일반",
+            Self::Custom { label, .. } => label,
         }
     }
 
@@ -89,18 +248,50 @@ impl Category {
             Self::Recruit => "recruit",
             Self::Contest => "contest",
             Self::Event => "event",
+            Self::International => "international",
             Self::General => "general",
+            Self::Custom { tag, .. } => tag,
         }
     }
 
     pub fn from_str_tag(s: &str) -> Self {
+        if let Some(c) = rules().find_tag(s) {
+            return c.clone();
+        }
+        Self::builtin_from_tag(s).unwrap_or(Self::General)
+    }
+
+    /// 사용자 입력(태그 또는 한글 라벨)으로 카테고리 찾기. 예: "장학", "scholarship".
+    pub fn lookup(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let builtin = [
+            Self::Academic,
+            Self::Scholarship,
+            Self::Recruit,
+            Self::Contest,
+            Self::Event,
+            Self::International,
+            Self::General,
+        ];
+        rules()
+            .rules
+            .iter()
+            .map(|(_, c)| c)
+            .chain(builtin.iter())
+            .find(|c| c.as_str() == s || c.label() == s)
+            .cloned()
+    }
+
+    fn builtin_from_tag(s: &str) -> Option<Self> {
         match s {
-            "academic" => Self::Academic,
-            "scholarship" => Self::Scholarship,
-            "recruit" => Self::Recruit,
-            "contest" => Self::Contest,
-            "event" => Self::Event,
-            _ => Self::General,
+            "academic" => Some(Self::Academic),
+            "scholarship" => Some(Self::Scholarship),
+            "recruit" => Some(Self::Recruit),
+            "contest" => Some(Self::Contest),
+            "event" => Some(Self::Event),
+            "international" => Some(Self::International),
+            "general" => Some(Self::General),
+            _ => None,
         }
     }
 }
@@ -118,33 +309,142 @@ mod tests {
     #[test]
     fn test_classify() {
         assert_eq!(
-            Category::classify("2026학년도 1학기 수강신청 일정 안내"),
+            CategoryRules::builtin().classify("2026학년도 1학기 수강신청 일정 안내"),
             Category::Academic
         );
         assert_eq!(
-            Category::classify("2026학년도 국가장학금 신청 안내"),
+            CategoryRules::builtin().classify("2026학년도 국가장학금 신청 안내"),
             Category::Scholarship
         );
         assert_eq!(
-            Category::classify("2026년도 제1차 직원(공무직) 채용 공고"),
+            CategoryRules::builtin().classify("2026년도 제1차 직원(공무직) 채용 공고"),
             Category::Recruit
         );
         assert_eq!(
-            Category::classify("해외 어학연수 참가자 모집"),
+            CategoryRules::builtin().classify("해외 어학연수 참가자 모집"),
             Category::Contest
         );
         assert_eq!(
-            Category::classify("AI 특강 및 세미나 안내"),
+            CategoryRules::builtin().classify("AI 특강 및 세미나 안내"),
             Category::Event
         );
         assert_eq!(
-            Category::classify("캠퍼스 도로 보수공사 안내"),
+            CategoryRules::builtin().classify("캠퍼스 도로 보수공사 안내"),
             Category::General
         );
+        assert_eq!(
+            CategoryRules::builtin().classify("2026-2학기 교환학생 선발 안내"),
+            Category::International
+        );
+        assert_eq!(
+            CategoryRules::builtin().classify("외국인 유학생 비자(D-2) 연장 신청 안내"),
+            Category::International
+        );
+        assert_eq!(
+            CategoryRules::builtin().classify("제98회 TOPIK 시험 접수 안내"),
+            Category::International
+        );
+        assert_eq!(
+            CategoryRules::builtin().classify("비자 연장 신청 안내"),
+            Category::International
+        );
+        // "소비자"는 비자가 아니다
+        assert_eq!(
+            CategoryRules::builtin().classify("소비자학과 수강신청 안내"),
+            Category::Academic
+        );
+        assert_eq!(
+            CategoryRules::builtin().classify("소비자 교육 세미나"),
+            Category::Event
+        );
         // Priority test: "장학금 모집" should be Scholarship (higher priority)
         assert_eq!(
-            Category::classify("교내장학금 신청 모집"),
+            CategoryRules::builtin().classify("교내장학금 신청 모집"),
+            Category::Scholarship
+        );
+    }
+
+    #[test]
+    fn test_custom_rules_from_config() {
+        let toml_str = r#"
+[[categories]]
+tag = "international"
+emoji = "🌏"
+label = "국제"
+keywords = ["교환학생", "국제", "어학연수"]
+
+[[categories]]
+tag = "scholarship"
+keywords = ["장학"]
+"#;
+        #[derive(Deserialize)]
+        struct Wrapper {
+            categories: Vec<CategoryRuleConfig>,
+        }
+        let w: Wrapper = toml::from_str(toml_str).unwrap();
+        let rules = CategoryRules::from_config(&w.categories, &HashMap::new());
+
+        let intl = rules.classify("2026-2학기 교환학생 선발 안내");
+        assert_eq!(intl.as_str(), "international");
+        assert_eq!(intl.label(), "국제");
+        assert_eq!(intl.emoji(), "🌏");
+
+        // 내장 태그는 내장 카테고리로
+        assert_eq!(rules.classify("교내장학금 신청"), Category::Scholarship);
+        // config가 있으면 내장 규칙은 쓰지 않는다
+        assert_eq!(rules.classify("수강신청 일정 안내"), Category::General);
+        assert_eq!(rules.find_tag("international"), Some(&intl));
+    }
+
+    #[test]
+    fn test_source_category_alias() {
+        let rules = CategoryRules::builtin();
+        // 게시판 분류가 제목 키워드보다 우선
+        assert_eq!(
+            rules.classify_with_source(Some("장학"), "수강신청 기간 안내"),
             Category::Scholarship
         );
+        // 모르는 분류명이나 빈 값은 제목으로 분류
+        assert_eq!(
+            rules.classify_with_source(Some("기타"), "수강신청 기간 안내"),
+            Category::Academic
+        );
+        assert_eq!(rules.classify_with_source(Some(" "), "특강 안내"), Category::Event);
+
+        let mut aliases = HashMap::new();
+        aliases.insert("취업".to_string(), "recruit".to_string());
+        let rules = CategoryRules::from_config(&[], &aliases);
+        assert_eq!(rules.classify_with_source(Some("취업"), "설명회"), Category::Recruit);
+        assert_eq!(rules.classify_with_source(Some("장학"), "설명회"), Category::Scholarship);
+    }
+
+    #[test]
+    fn test_builtin_tags_round_trip() {
+        // 내장 카테고리를 추가하면 태그/라벨 왕복이 모두 맞아야 한다
+        let all = [
+            Category::Academic,
+            Category::Scholarship,
+            Category::Recruit,
+            Category::Contest,
+            Category::Event,
+            Category::International,
+            Category::General,
+        ];
+        for c in &all {
+            assert_eq!(&Category::from_str_tag(c.as_str()), c);
+            assert_eq!(Category::lookup(c.label()).as_ref(), Some(c));
+            assert!(!c.emoji().is_empty());
+        }
+        // 예전에 저장된 태그와 모르는 태그는 일반으로
+        assert_eq!(Category::from_str_tag("general"), Category::General);
+        assert_eq!(Category::from_str_tag("safety"), Category::General);
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(Category::lookup("장학"), Some(Category::Scholarship));
+        assert_eq!(Category::lookup("recruit"), Some(Category::Recruit));
+        assert_eq!(Category::lookup(" 일반 "), Some(Category::General));
+        assert_eq!(Category::lookup("없는분류"), None);
     }
 }
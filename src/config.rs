@@ -2,12 +2,121 @@ use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::category::CategoryRuleConfig;
+use crate::parser::RawNotice;
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Config {
     pub bot: BotConfig,
     pub database: DbConfig,
     #[serde(rename = "source")]
     pub sources: Vec<SourceConfig>,
+    /// serve 모드를 webhook으로 띄울 때의 설정. 없으면 long polling.
+    pub webhook: Option<WebhookConfig>,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    /// 사용자 정의 카테고리 규칙 (순서대로 검사). 없으면 내장 규칙.
+    #[serde(default)]
+    pub categories: Vec<CategoryRuleConfig>,
+    /// 게시판 분류 컬럼 값 → 카테고리 태그 (내장 alias에 추가/덮어쓰기).
+    #[serde(default)]
+    pub category_aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub admin: AdminConfig,
+    #[serde(default)]
+    pub templates: TemplatesConfig,
+    #[serde(default)]
+    pub weekly_report: WeeklyReportConfig,
+    #[serde(default)]
+    pub discord: DiscordConfig,
+    #[serde(default)]
+    pub slack: SlackConfig,
+}
+
+/// 채널에 올라간 공지를 디스코드 webhook으로도 게시.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct DiscordConfig {
+    /// 비어 있으면 끔. 환경변수 DISCORD_WEBHOOK_URL이 우선.
+    #[serde(default)]
+    pub webhook_url: String,
+}
+
+impl DiscordConfig {
+    /// 사용할 webhook URL (환경변수 > 설정). 없으면 `None`.
+    pub fn webhook_url(&self) -> Option<String> {
+        std::env::var("DISCORD_WEBHOOK_URL")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| Some(self.webhook_url.clone()).filter(|s| !s.is_empty()))
+    }
+}
+
+/// 연구실/교직원 슬랙으로 일부 소스의 공지를 게시 (incoming webhook).
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct SlackConfig {
+    /// 비어 있으면 끔. 환경변수 SLACK_WEBHOOK_URL이 우선.
+    #[serde(default)]
+    pub webhook_url: String,
+    /// 슬랙에도 올릴 소스 키. 목록에 있는 소스만 게시한다.
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+impl SlackConfig {
+    /// 사용할 webhook URL (환경변수 > 설정). 없거나 게시할 소스가 없으면 `None`.
+    pub fn webhook_url(&self) -> Option<String> {
+        if self.sources.is_empty() {
+            return None;
+        }
+        std::env::var("SLACK_WEBHOOK_URL")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| Some(self.webhook_url.clone()).filter(|s| !s.is_empty()))
+    }
+}
+
+/// 로그 채널 주간 리포트 (serve 모드 전용). 요일/시각은 KST.
+#[derive(Deserialize, Clone, Debug)]
+pub struct WeeklyReportConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// "mon" ~ "sun" (또는 "monday" 등).
+    #[serde(default = "default_report_weekday")]
+    pub weekday: String,
+    #[serde(default = "default_report_hour")]
+    pub hour: u32,
+}
+
+impl Default for WeeklyReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: default_report_weekday(),
+            hour: default_report_hour(),
+        }
+    }
+}
+
+impl WeeklyReportConfig {
+    /// 설정된 요일. 해석할 수 없으면 `None` (`validate`가 걸러낸다).
+    pub fn weekday(&self) -> Option<chrono::Weekday> {
+        self.weekday.trim().parse().ok()
+    }
+}
+
+/// 메시지 템플릿. `{title}`, `{source}`, `{date}`, `{author}`, `{category}`, `{url}`
+/// 자리표시자를 쓰는 평문이며, 없으면 기본 레이아웃을 쓴다.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TemplatesConfig {
+    pub channel_template: Option<String>,
+    pub dm_template: Option<String>,
+}
+
+/// 관리자 설정 (`/broadcast` 등 관리자 전용 명령어).
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct AdminConfig {
+    #[serde(default)]
+    pub telegram_ids: Vec<i64>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -20,6 +129,193 @@ pub struct BotConfig {
     pub message_delay_ms: u64,
     #[serde(default = "default_crawl_interval")]
     pub crawl_interval_secs: u64,
+    /// 업데이트 수신 방식: "polling" (기본) 또는 "webhook".
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// TLS 인증서 검증 생략 (전체 기본값). 소스별 `tls_insecure`가 우선.
+    #[serde(default)]
+    pub tls_insecure: bool,
+    /// 채널 발송 시 링크 미리보기 끄기.
+    #[serde(default)]
+    pub disable_link_preview: bool,
+    /// 채널 게시물에 "원문 보기" 옆 "공유" 버튼 (t.me/share 링크) 달기.
+    #[serde(default)]
+    pub show_share_button: bool,
+    /// 기본 채널이 포럼 슈퍼그룹일 때 카테고리 태그 → 토픽(message_thread_id).
+    #[serde(default)]
+    pub category_topics: HashMap<String, i32>,
+    /// 한 번에 올라온 여러 공지를 채널별로 묶어 한 메시지로 발송.
+    #[serde(default)]
+    pub group_channel_posts: bool,
+    /// 상세 페이지(`fetch_detail`)에서 찾은 이미지를 사진/앨범으로 게시.
+    #[serde(default)]
+    pub send_images: bool,
+    /// 같은 호스트로 보내는 요청 사이 최소 간격 (ms). 0이면 제한 없음.
+    #[serde(default = "default_host_delay")]
+    pub host_delay_ms: u64,
+    /// 이미 알린 공지의 제목이 수정되면 "수정됨"으로 다시 알림.
+    #[serde(default)]
+    pub notify_on_edit: bool,
+    /// 수정 알림을 새 게시물 대신 원래 채널 메시지를 고쳐서 보냄 (48시간 이내만).
+    #[serde(default)]
+    pub edit_on_update: bool,
+    /// 연속 N회 실패한 소스는 쿨다운(30분부터 두 배씩, 최대 24시간) 동안 건너뛴다. 0이면 끔.
+    #[serde(default = "default_max_consecutive_errors")]
+    pub max_consecutive_errors: u32,
+    /// 채널+DM+`/broadcast` 전체 초당 발송 한도 (텔레그램 flood 제한 30/s 아래로). 0이면 제한 없음.
+    #[serde(default = "default_send_rate")]
+    pub send_rate_per_sec: u32,
+    /// 개인 DM을 보낼 별도 봇 토큰이 든 환경변수 이름. 없으면 채널 봇으로 보낸다.
+    /// 이 봇은 명령/버튼을 받지 않아서 DM의 "확인" 버튼은 빠진다.
+    #[serde(default)]
+    pub dm_token_env: Option<String>,
+    /// 목록 요청 실패 시 재시도 횟수.
+    #[serde(default = "default_fetch_retries")]
+    pub fetch_retries: u32,
+    /// 첫 재시도 대기 (ms). 이후 두 배씩 늘고 ±50% 무작위 지터를 섞는다.
+    #[serde(default = "default_fetch_backoff")]
+    pub fetch_backoff_ms: u64,
+    /// 목록이 0건으로 파싱되면 파서가 읽은 응답 원문(HTML/JSON)을 `debug_snapshots/`에 저장하고 로그 채널에 알림
+    /// (계속 0건이면 처음 한 번만 알린다).
+    #[serde(default)]
+    pub save_empty_snapshots: bool,
+    /// 소스별로 남길 스냅샷 수.
+    #[serde(default = "default_snapshot_keep")]
+    pub snapshot_keep: usize,
+    /// 같은 소스 재공지(`[재공지] ...`) 억제 기준 제목 유사도 (0.0~1.0). 0이면 끔.
+    #[serde(default)]
+    pub near_dup_threshold: f64,
+    /// 재공지 비교 대상 기간 (일).
+    #[serde(default = "default_near_dup_window")]
+    pub near_dup_window_days: u32,
+    /// 채널 게시. 끄면 새 공지를 게시하지 않고 처리 완료로만 기록한다 (DM은 그대로).
+    #[serde(default = "default_true")]
+    pub enable_channel: bool,
+    /// 개인 DM. 끄면 그동안의 공지는 나중에 다시 켜도 DM으로 보내지 않는다.
+    #[serde(default = "default_true")]
+    pub enable_dm: bool,
+    /// DM 매칭 대상 공지 기간 (시간). 봇이 이보다 오래 멈춰 있었으면 그 전 공지는 DM하지 않는다.
+    #[serde(default = "default_dm_lookback_hours")]
+    pub dm_lookback_hours: u32,
+    /// 한 번에 DM 매칭할 최대 공지 수 (최신순).
+    #[serde(default = "default_dm_scan_limit")]
+    pub dm_scan_limit: usize,
+    /// 사용자별 하루(KST) 최대 개별 DM 수. 넘는 매칭은 저녁 9시에 요약 한 통으로. 0이면 제한 없음.
+    #[serde(default)]
+    pub max_dms_per_user_per_day: u32,
+    /// 제목 키워드 구독이 상세 본문까지 검사 (`fetch_detail` 소스만 본문이 있다).
+    #[serde(default)]
+    pub match_body: bool,
+    /// 크롤링 User-Agent. 문자열 하나 또는 목록 (목록이면 요청마다 돌려 쓴다).
+    #[serde(default)]
+    pub user_agent: UserAgent,
+    /// 크롤링 접속(TCP/TLS) 제한 시간 (초). 죽은 호스트는 빨리 포기한다.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// 크롤링 요청 한 건 전체 제한 시간 (초). 느린 상세 페이지용으로 늘릴 수 있다.
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// 채널 게시 순서: `latest` (기본, 최근 수집 먼저), `chronological`, `pinned_first`.
+    #[serde(default)]
+    pub post_order: PostOrder,
+    /// 미리보기 채널. 지정하면 모든 채널 게시가 소스/카테고리 라우팅과 관계없이 이 채널로
+    /// "[PREVIEW]" 표시를 달고 나가고, DM과 디스코드/슬랙 미러링은 하지 않는다.
+    /// `--preview-channel` 플래그가 우선.
+    #[serde(default)]
+    pub preview_channel: Option<String>,
+    /// 제목에 들어 있으면 긴급 공지로 표시하는 키워드. 긴급 공지는 묶음/발송 간격 없이
+    /// 먼저 게시되고 🚨 머리말이 붙는다. 기본은 빈 목록 (끔).
+    #[serde(default)]
+    pub urgent_keywords: Vec<String>,
+}
+
+impl BotConfig {
+    /// 제목이 긴급 키워드를 포함하는지 (대소문자 무시).
+    pub fn is_urgent(&self, title: &str) -> bool {
+        let title = title.to_lowercase();
+        self.urgent_keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .any(|k| !k.is_empty() && title.contains(&k))
+    }
+
+    /// 크롤링 HTTP 제한 시간 (`connect_timeout_secs`, `request_timeout_secs`).
+    pub fn http_timeouts(&self) -> HttpTimeouts {
+        HttpTimeouts {
+            connect: std::time::Duration::from_secs(self.connect_timeout_secs),
+            request: std::time::Duration::from_secs(self.request_timeout_secs),
+        }
+    }
+}
+
+/// 크롤링 HTTP 클라이언트의 접속/전체 제한 시간.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    pub connect: std::time::Duration,
+    pub request: std::time::Duration,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: std::time::Duration::from_secs(default_connect_timeout_secs()),
+            request: std::time::Duration::from_secs(default_request_timeout_secs()),
+        }
+    }
+}
+
+/// `bot.post_order`: 한 사이클에 올릴 공지들의 게시 순서.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PostOrder {
+    /// 최근 게시된 공지부터 (게시일이 없거나 이상하면 발견 시각), 소스별로 돌아가며.
+    #[default]
+    Latest,
+    /// 오래된 게시글부터. 채널을 위에서 아래로 읽으면 시간순.
+    Chronological,
+    /// 고정(공지) 글 먼저, 나머지는 최근 게시 순.
+    PinnedFirst,
+}
+
+/// 크롤링 기본 User-Agent.
+pub const DEFAULT_USER_AGENT: &str = "CBNU-Notice-Bot/1.0 (student project)";
+
+/// `bot.user_agent`: `"..."` 또는 `["...", "..."]`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum UserAgent {
+    Fixed(String),
+    Rotate(Vec<String>),
+}
+
+impl Default for UserAgent {
+    fn default() -> Self {
+        Self::Fixed(DEFAULT_USER_AGENT.to_string())
+    }
+}
+
+impl UserAgent {
+    /// 사용할 UA 목록 (빈 값은 제외).
+    pub fn list(&self) -> Vec<String> {
+        let all = match self {
+            Self::Fixed(ua) => std::slice::from_ref(ua),
+            Self::Rotate(uas) => uas.as_slice(),
+        };
+        all.iter().map(|ua| ua.trim()).filter(|ua| !ua.is_empty()).map(String::from).collect()
+    }
+}
+
+/// Webhook 수신 설정 (리버스 프록시 뒤에서 serve 할 때).
+#[derive(Deserialize, Clone, Debug)]
+pub struct WebhookConfig {
+    /// 텔레그램이 호출할 공개 URL (예: https://bot.example.com/webhook).
+    #[serde(default)]
+    pub url: String,
+    /// 로컬에서 listen 할 포트. 프록시가 이 포트로 전달한다.
+    #[serde(default = "default_webhook_port")]
+    pub port: u16,
+    /// X-Telegram-Bot-Api-Secret-Token 검증값. 환경변수 WEBHOOK_SECRET이 우선.
+    pub secret_token: Option<String>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -40,6 +336,70 @@ pub struct SourceConfig {
     pub enabled: bool,
     /// 이 소스의 공지를 보낼 채널. 미지정 시 bot.telegram_channel 사용.
     pub channel: Option<String>,
+    /// 신규 공지마다 상세 페이지를 받아 본문/정확한 게시 시각을 채운다.
+    #[serde(default)]
+    pub fetch_detail: bool,
+    /// 다른 소스에 최근 올라온 같은 공지(제목과 링크가 같은 글)는 다시 알리지 않는다.
+    #[serde(default)]
+    pub dedup_global: bool,
+    /// 인증서가 깨진 사이트만 TLS 검증 생략. 미지정 시 bot.tls_insecure 사용.
+    pub tls_insecure: Option<bool>,
+    /// 게시판 언어 (`ko`, `en`). 미지정 시 공지 제목으로 추정한다.
+    pub lang: Option<String>,
+    /// 이 날짜(`"2025-01-01"`)보다 오래된 게시일의 공지는 저장만 하고 알리지 않는다.
+    /// 게시판 개편 등으로 옛 글이 목록에 다시 떠오를 때 대비.
+    pub min_date: Option<chrono::NaiveDate>,
+    /// 한 사이클에 이 소스에서 채널로 보낼 최대 공지 수. 전체 한도는 `bot.max_notices_per_run`.
+    pub max_per_run: Option<usize>,
+    /// 고정(공지) 글은 저장만 하고 알리지 않는다. 규정·양식 글이 늘 걸려 있는 게시판용.
+    #[serde(default)]
+    pub skip_pinned: bool,
+    /// 고정 글만 처리 (파서 점검용). 일반 글은 저장하지 않는다.
+    #[serde(default)]
+    pub pinned_only: bool,
+    /// 포럼 채널에서 이 소스를 올릴 토픽(`message_thread_id`). 미지정 시 카테고리 토픽/일반 스레드.
+    pub topic_id: Option<i32>,
+}
+
+// 테스트 픽스처에서 필요한 필드만 채울 때 쓴다. 값은 설정에서 생략했을 때와 같다.
+impl Default for SourceConfig {
+    fn default() -> Self {
+        Self {
+            key: String::new(),
+            display_name: String::new(),
+            parser: String::new(),
+            url: String::new(),
+            params: HashMap::new(),
+            enabled: default_true(),
+            channel: None,
+            fetch_detail: false,
+            dedup_global: false,
+            tls_insecure: None,
+            lang: None,
+            min_date: None,
+            max_per_run: None,
+            skip_pinned: false,
+            pinned_only: false,
+            topic_id: None,
+        }
+    }
+}
+
+impl SourceConfig {
+    /// 이 소스에 TLS 검증을 생략할지 (소스 설정 > 전체 기본값).
+    pub fn tls_insecure(&self, global: bool) -> bool {
+        self.tls_insecure.unwrap_or(global)
+    }
+
+    /// 크롤링 결과에서 처리할 글인지 (`pinned_only`면 고정 글만).
+    pub fn keeps(&self, notice: &RawNotice) -> bool {
+        !self.pinned_only || notice.is_pinned
+    }
+
+    /// 새로 저장한 글을 알리지 않고 넘길지 (`skip_pinned`인 소스의 고정 글).
+    pub fn mutes_pinned(&self, notice: &RawNotice) -> bool {
+        self.skip_pinned && notice.is_pinned
+    }
 }
 
 fn default_max_notices() -> usize {
@@ -48,6 +408,39 @@ fn default_max_notices() -> usize {
 fn default_delay() -> u64 {
     150
 }
+fn default_host_delay() -> u64 {
+    500
+}
+fn default_max_consecutive_errors() -> u32 {
+    10
+}
+fn default_fetch_retries() -> u32 {
+    3
+}
+fn default_fetch_backoff() -> u64 {
+    2000
+}
+fn default_send_rate() -> u32 {
+    25
+}
+fn default_snapshot_keep() -> usize {
+    5
+}
+fn default_near_dup_window() -> u32 {
+    14
+}
+fn default_dm_lookback_hours() -> u32 {
+    48
+}
+fn default_dm_scan_limit() -> usize {
+    200
+}
+fn default_connect_timeout_secs() -> u64 {
+    5
+}
+fn default_request_timeout_secs() -> u64 {
+    15
+}
 fn default_db_path() -> String {
     "notices.db".to_string()
 }
@@ -57,6 +450,39 @@ fn default_crawl_interval() -> u64 {
 fn default_true() -> bool {
     true
 }
+fn default_mode() -> String {
+    "polling".to_string()
+}
+fn default_webhook_port() -> u16 {
+    8443
+}
+fn default_metrics_port() -> u16 {
+    9898
+}
+fn default_report_weekday() -> String {
+    "mon".to_string()
+}
+fn default_report_hour() -> u32 {
+    9
+}
+
+/// Prometheus `/metrics` 엔드포인트 설정 (serve 모드 전용).
+#[derive(Deserialize, Clone, Debug)]
+pub struct MetricsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_metrics_port")]
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_metrics_port(),
+        }
+    }
+}
 
 impl Config {
     pub fn load(path: &Path) -> anyhow::Result<Self> {
@@ -67,9 +493,209 @@ impl Config {
         Ok(config)
     }
 
+    /// 설정 검증. 문제를 모두 모아 한 번에 보고한다.
+    /// (알 수 없는 파서, 파서별 필수 params 누락, 중복 key, 잘못된 채널 형식)
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+
+        for (name, channel) in [
+            ("bot.telegram_channel", Some(&self.bot.telegram_channel)),
+            ("bot.log_channel", self.bot.log_channel.as_ref()),
+        ] {
+            if let Some(ch) = channel.filter(|c| !c.is_empty()) {
+                if !is_valid_channel(ch) {
+                    problems.push(format!("{}: '{}' is not an @name or numeric chat id", name, ch));
+                }
+            }
+        }
+
+        for (name, template) in [
+            ("templates.channel_template", &self.templates.channel_template),
+            ("templates.dm_template", &self.templates.dm_template),
+        ] {
+            if let Some(t) = template {
+                for unknown in crate::format::unknown_placeholders(t) {
+                    problems.push(format!(
+                        "{}: unknown placeholder {{{}}} (expected one of: {})",
+                        name,
+                        unknown,
+                        crate::format::TEMPLATE_PLACEHOLDERS.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.bot.near_dup_threshold) {
+            problems.push(format!(
+                "bot.near_dup_threshold: {} is outside 0.0..=1.0",
+                self.bot.near_dup_threshold
+            ));
+        }
+
+        if self.bot.dm_lookback_hours == 0 || self.bot.dm_scan_limit == 0 {
+            problems.push("bot.dm_lookback_hours / bot.dm_scan_limit: must be at least 1".to_string());
+        }
+        if self.bot.connect_timeout_secs == 0 || self.bot.request_timeout_secs == 0 {
+            problems.push("bot.connect_timeout_secs / bot.request_timeout_secs: must be at least 1".to_string());
+        }
+        if !self.discord.webhook_url.is_empty() && reqwest::Url::parse(&self.discord.webhook_url).is_err() {
+            problems.push(format!("discord.webhook_url: invalid url '{}'", self.discord.webhook_url));
+        }
+        if !self.slack.webhook_url.is_empty() && reqwest::Url::parse(&self.slack.webhook_url).is_err() {
+            problems.push(format!("slack.webhook_url: invalid url '{}'", self.slack.webhook_url));
+        }
+        for key in &self.slack.sources {
+            if !self.sources.iter().any(|s| &s.key == key) {
+                problems.push(format!("slack.sources: unknown source '{}'", key));
+            }
+        }
+
+        if self.weekly_report.weekday().is_none() {
+            problems.push(format!("weekly_report.weekday: unknown day '{}'", self.weekly_report.weekday));
+        }
+        if self.weekly_report.hour > 23 {
+            problems.push(format!("weekly_report.hour: {} is outside 0..=23", self.weekly_report.hour));
+        }
+
+        if self.bot.user_agent.list().is_empty() {
+            problems.push("bot.user_agent: no non-empty user agent".to_string());
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for source in &self.sources {
+            let key = &source.key;
+            if key.trim().is_empty() {
+                problems.push(format!("source '{}': key is empty", source.display_name));
+            } else if !seen.insert(key.as_str()) {
+                problems.push(format!("source '{}': duplicate key", key));
+            }
+
+            if let Some(lang) = &source.lang {
+                if crate::lang::normalize(lang) != Some(lang.as_str()) {
+                    problems.push(format!(
+                        "source '{}': unknown lang '{}' (expected one of: {})",
+                        key,
+                        lang,
+                        crate::lang::LANGS.join(", ")
+                    ));
+                }
+            }
+
+            match crate::parser::PARSER_TYPES.iter().find(|(name, _)| *name == source.parser) {
+                Some((_, required)) => {
+                    for param in *required {
+                        if source.params.get(*param).is_none_or(|v| v.trim().is_empty()) {
+                            problems.push(format!(
+                                "source '{}': parser '{}' requires params.{}",
+                                key, source.parser, param
+                            ));
+                        }
+                    }
+                }
+                None => {
+                    let known: Vec<&str> = crate::parser::PARSER_TYPES.iter().map(|(n, _)| *n).collect();
+                    problems.push(format!(
+                        "source '{}': unknown parser '{}' (expected one of: {})",
+                        key,
+                        source.parser,
+                        known.join(", ")
+                    ));
+                }
+            }
+
+            if source.max_per_run == Some(0) {
+                problems.push(format!("source '{}': max_per_run must be at least 1", key));
+            }
+            if source.skip_pinned && source.pinned_only {
+                problems.push(format!("source '{}': skip_pinned and pinned_only cannot both be set", key));
+            }
+            if reqwest::Url::parse(&source.url).is_err() {
+                problems.push(format!("source '{}': invalid url '{}'", key, source.url));
+            }
+            if let Some(ch) = &source.channel {
+                if !is_valid_channel(ch) {
+                    problems.push(format!("source '{}': channel '{}' is not an @name or numeric chat id", key, ch));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "Invalid config ({} problem(s)):\n  - {}",
+                problems.len(),
+                problems.join("\n  - ")
+            )
+        }
+    }
+
     pub fn enabled_sources(&self) -> Vec<&SourceConfig> {
         self.sources.iter().filter(|s| s.enabled).collect()
     }
+
+    /// 한 소스만 수집하도록 나머지를 끈 사본 (`/crawl <source>`). 활성 소스가 아니면 `None`.
+    pub fn only_source(&self, key: &str) -> Option<Self> {
+        if !self.sources.iter().any(|s| s.key == key && s.enabled) {
+            return None;
+        }
+        let mut cfg = self.clone();
+        for source in &mut cfg.sources {
+            source.enabled = source.key == key;
+        }
+        Some(cfg)
+    }
+
+    /// 소스 키 → 사이클당 발송 한도 (`max_per_run`을 지정한 소스만).
+    pub fn source_caps(&self) -> HashMap<String, usize> {
+        self.sources
+            .iter()
+            .filter_map(|s| s.max_per_run.map(|cap| (s.key.clone(), cap)))
+            .collect()
+    }
+
+    /// 소스 키 → 포럼 토픽 (`topic_id`를 지정한 소스만).
+    pub fn source_topics(&self) -> HashMap<String, i32> {
+        self.sources
+            .iter()
+            .filter_map(|s| s.topic_id.map(|topic| (s.key.clone(), topic)))
+            .collect()
+    }
+
+    /// webhook 모드로 실행할지 결정. `--webhook` 플래그나 `bot.mode = "webhook"`이
+    /// 켜져 있어도 URL이 비어 있으면 `None` (polling으로 fallback).
+    pub fn webhook_settings(&self, force: bool) -> Option<&WebhookConfig> {
+        if !force && self.bot.mode != "webhook" {
+            return None;
+        }
+        self.webhook.as_ref().filter(|w| !w.url.trim().is_empty())
+    }
+}
+
+/// 텔레그램 채팅 지정 형식: `@채널이름` 또는 숫자 ID (`-100...`).
+fn is_valid_channel(ch: &str) -> bool {
+    match ch.strip_prefix('@') {
+        Some(name) => {
+            name.len() >= 4 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => ch.strip_prefix('-').unwrap_or(ch).parse::<u64>().is_ok(),
+    }
+}
+
+impl AdminConfig {
+    pub fn is_admin(&self, telegram_id: i64) -> bool {
+        self.telegram_ids.contains(&telegram_id)
+    }
+}
+
+impl WebhookConfig {
+    /// 텔레그램 secret_token 규칙: 1~256자, `A-Z a-z 0-9 _ -`만 허용.
+    pub fn is_valid_secret(token: &str) -> bool {
+        (1..=256).contains(&token.len())
+            && token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
 }
 
 #[cfg(test)]
@@ -114,4 +740,249 @@ pg_idx = "7"
         assert_eq!(config.enabled_sources().len(), 1);
         assert_eq!(config.sources[0].params.get("bbsNo").unwrap(), "8");
     }
+
+    #[test]
+    fn test_urgent_keywords_opt_in() {
+        // 지정하지 않으면 긴급 표시 없음 (게시 순서/간격 그대로)
+        let bot: BotConfig = toml::from_str(r#"telegram_channel = "@cbnu_notice""#).unwrap();
+        assert!(bot.urgent_keywords.is_empty());
+        assert!(!bot.is_urgent("[긴급] 오늘 휴강 안내"));
+
+        let bot: BotConfig = toml::from_str(
+            r#"
+telegram_channel = "@cbnu_notice"
+urgent_keywords = ["휴강", " "]
+"#,
+        )
+        .unwrap();
+        assert!(bot.is_urgent("[긴급] 오늘 휴강 안내"));
+        assert!(!bot.is_urgent("수강신청 안내"));
+    }
+
+    #[test]
+    fn test_webhook_settings() {
+        let base = r#"
+[bot]
+telegram_channel = "@cbnu_notice"
+
+[database]
+
+[[source]]
+key = "biz"
+display_name = "경영학부"
+parser = "php_master"
+url = "https://biz.chungbuk.ac.kr"
+"#;
+        // 설정 없음 → polling
+        let config: Config = toml::from_str(base).unwrap();
+        assert_eq!(config.bot.mode, "polling");
+        assert!(config.webhook_settings(false).is_none());
+        assert!(config.webhook_settings(true).is_none());
+
+        let with_hook = format!(
+            "{}\n[webhook]\nurl = \"https://bot.example.com/webhook\"\nsecret_token = \"abc_123\"\n",
+            base
+        );
+        let config: Config = toml::from_str(&with_hook).unwrap();
+        // mode가 polling이면 --webhook 플래그가 있어야 사용
+        assert!(config.webhook_settings(false).is_none());
+        let hook = config.webhook_settings(true).unwrap();
+        assert_eq!(hook.port, 8443);
+        assert_eq!(hook.secret_token.as_deref(), Some("abc_123"));
+
+        // URL이 비어 있으면 fallback
+        let empty_url = base.replace("[bot]\n", "[bot]\nmode = \"webhook\"\n") + "\n[webhook]\nurl = \"\"\n";
+        let config: Config = toml::from_str(&empty_url).unwrap();
+        assert_eq!(config.bot.mode, "webhook");
+        assert!(config.webhook_settings(false).is_none());
+    }
+
+    #[test]
+    fn test_webhook_secret_validation() {
+        assert!(WebhookConfig::is_valid_secret("abc-DEF_123"));
+        assert!(!WebhookConfig::is_valid_secret(""));
+        assert!(!WebhookConfig::is_valid_secret("has space"));
+        assert!(!WebhookConfig::is_valid_secret(&"a".repeat(257)));
+    }
+
+    #[test]
+    fn test_tls_insecure_override() {
+        let toml_str = r#"
+[bot]
+telegram_channel = "@cbnu_notice"
+
+[database]
+
+[[source]]
+key = "strict"
+display_name = "정상"
+parser = "egov"
+url = "https://www.chungbuk.ac.kr/www/selectBbsNttList.do"
+
+[[source]]
+key = "broken"
+display_name = "인증서 오류"
+parser = "php_master"
+url = "https://biz.chungbuk.ac.kr"
+tls_insecure = true
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(!config.bot.tls_insecure);
+        assert!(!config.sources[0].tls_insecure(config.bot.tls_insecure));
+        assert!(config.sources[1].tls_insecure(config.bot.tls_insecure));
+
+        // 전체를 켜도 소스에서 명시적으로 끌 수 있다
+        let mut src = config.sources[1].clone();
+        src.tls_insecure = Some(false);
+        assert!(!src.tls_insecure(true));
+        assert!(config.sources[0].tls_insecure(true));
+    }
+
+    #[test]
+    fn test_pinned_modes() {
+        let config: Config = toml::from_str(
+            r#"
+[bot]
+telegram_channel = "@cbnu_notice"
+
+[database]
+
+[[source]]
+key = "plain"
+display_name = "기본"
+parser = "php_master"
+url = "https://biz.chungbuk.ac.kr"
+params = { pg_idx = "1" }
+
+[[source]]
+key = "noisy"
+display_name = "고정 글 많음"
+parser = "php_master"
+url = "https://physics.chungbuk.ac.kr"
+skip_pinned = true
+params = { pg_idx = "1" }
+
+[[source]]
+key = "debug"
+display_name = "고정 글만"
+parser = "php_master"
+url = "https://math.chungbuk.ac.kr"
+pinned_only = true
+params = { pg_idx = "1" }
+"#,
+        )
+        .unwrap();
+        let notice = |is_pinned| RawNotice {
+            notice_id: "1".into(),
+            title: "학과 규정".into(),
+            url: "https://biz.chungbuk.ac.kr/1".into(),
+            author: None,
+            date: None,
+            original_date: None,
+            category: None,
+            is_pinned,
+            views: None,
+        };
+        let (pinned, normal) = (notice(true), notice(false));
+        let [plain, noisy, debug] = &config.sources[..] else { panic!() };
+
+        // 기본: 고정 여부와 상관없이 처리하고 알린다
+        assert!(plain.keeps(&pinned) && plain.keeps(&normal));
+        assert!(!plain.mutes_pinned(&pinned));
+
+        // skip_pinned: 고정 글도 저장하지만 알리지 않는다
+        assert!(noisy.keeps(&pinned) && noisy.keeps(&normal));
+        assert!(noisy.mutes_pinned(&pinned));
+        assert!(!noisy.mutes_pinned(&normal));
+
+        // pinned_only: 일반 글은 처리하지 않는다
+        assert!(debug.keeps(&pinned));
+        assert!(!debug.keeps(&normal));
+        assert!(!debug.mutes_pinned(&pinned));
+        assert!(config.validate().is_ok());
+
+        let mut both = config.clone();
+        both.sources[1].pinned_only = true;
+        assert!(both.validate().unwrap_err().to_string().contains("cannot both be set"));
+    }
+
+    #[test]
+    fn test_admin_check() {
+        let toml_str = r#"
+source = []
+[bot]
+telegram_channel = "@cbnu_notice"
+[database]
+[admin]
+telegram_ids = [1001, 1002]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.admin.is_admin(1001));
+        assert!(config.admin.is_admin(1002));
+        assert!(!config.admin.is_admin(9999));
+
+        // [admin]이 없으면 아무도 관리자가 아니다
+        let config: Config =
+            toml::from_str("source = []\n[bot]\ntelegram_channel = \"@c\"\n[database]\n").unwrap();
+        assert!(!config.admin.is_admin(1001));
+    }
+
+    #[test]
+    fn test_user_agent_forms() {
+        let parse = |extra: &str| -> Config {
+            toml::from_str(&format!("source = []\n[bot]\ntelegram_channel = \"@c\"\n{}\n[database]\n", extra))
+                .unwrap()
+        };
+        assert_eq!(parse("").bot.user_agent.list(), vec![DEFAULT_USER_AGENT]);
+        assert_eq!(parse("user_agent = \"Mozilla/5.0\"").bot.user_agent.list(), vec!["Mozilla/5.0"]);
+        assert_eq!(parse("user_agent = [\"a\", \"\", \"b\"]").bot.user_agent.list(), vec!["a", "b"]);
+        assert!(parse("user_agent = []").validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let toml_str = r#"
+[bot]
+telegram_channel = "@cbnu_notice"
+[database]
+
+[[source]]
+key = "main"
+display_name = "본부"
+parser = "egov"
+url = "https://www.chungbuk.ac.kr/www/selectBbsNttList.do"
+[source.params]
+bbsNo = "8"
+
+[[source]]
+key = "main"
+display_name = "중복"
+parser = "xe_board"
+url = "https://civil.chungbuk.ac.kr"
+channel = "cbnu_dept"
+[source.params]
+mid = "board_jIDW98"
+
+[[source]]
+key = "lib"
+display_name = "도서관"
+parser = "rss"
+url = "https://library.chungbuk.ac.kr"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("4 problem(s)"), "{}", err);
+        assert!(err.contains("source 'main': parser 'egov' requires params.key"));
+        assert!(err.contains("source 'main': duplicate key"));
+        assert!(err.contains("channel 'cbnu_dept'"));
+        assert!(err.contains("unknown parser 'rss'"));
+    }
+
+    #[test]
+    fn test_validate_shipped_config() {
+        let config = Config::load(Path::new("config.toml")).unwrap();
+        config.validate().unwrap();
+        assert!(is_valid_channel("-1001234567890"));
+        assert!(!is_valid_channel("@ab"));
+    }
 }
@@ -1,14 +1,126 @@
-use chrono::Utc;
-use rusqlite::{params, Connection};
+use chrono::Duration;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
 
 use crate::category::Category;
+use crate::config::PostOrder;
+use crate::kst::{self, now_sqlite};
 use crate::parser::RawNotice;
 
-/// SQLite datetime() 호환 포맷으로 현재 시간 반환.
-/// RFC3339 대신 "YYYY-MM-DD HH:MM:SS" 형식을 사용해야
-/// SQLite의 datetime('now', '-1 day') 등과 올바르게 비교된다.
-fn now_sqlite() -> String {
-    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+/// 쉼표로 저장된 카테고리 필터 분리.
+fn split_categories(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// 교차 게시 중복 판정 기간 (일).
+const DEDUP_WINDOW_DAYS: u32 = 14;
+
+/// 정규화한 제목의 안정적인 해시 (FNV-1a 64bit, hex).
+/// 공백/기호/대소문자 차이는 무시한다. 실행 환경이 바뀌어도 값이 같아야
+/// DB에 저장할 수 있으므로 std의 `DefaultHasher`는 쓰지 않는다.
+pub fn content_hash(title: &str) -> String {
+    fnv1a_hex(normalized_title(title))
+}
+
+fn normalized_title(title: &str) -> impl Iterator<Item = char> + '_ {
+    title.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase)
+}
+
+/// 교차 게시 판정용 해시 (`dedup_global`): 정규화 제목 + 정규화 링크. "학사일정 안내"처럼
+/// 흔한 제목이 학과마다 따로 올라와도 링크가 다르면 다른 공지로 본다.
+/// 링크를 해석할 수 없으면 제목만 쓴다.
+pub fn dedup_hash(title: &str, url: &str) -> String {
+    match canonical_url(url) {
+        Some(link) => fnv1a_hex(normalized_title(title).chain(['\n']).chain(link.chars())),
+        None => content_hash(title),
+    }
+}
+
+/// 링크 비교용 정규화: scheme/포트/fragment와 끝의 `/`를 무시하고 쿼리 파라미터는 정렬.
+fn canonical_url(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    let mut link = format!("{}{}", parsed.host_str()?, parsed.path().trim_end_matches('/'));
+    let mut query: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+    query.sort();
+    for (i, (key, value)) in query.iter().enumerate() {
+        link.push(if i == 0 { '?' } else { '&' });
+        link.push_str(&format!("{}={}", key, value));
+    }
+    Some(link)
+}
+
+/// 수정 감지용 제목 해시. 공백 차이만 무시하고 기호/숫자 변경은 수정으로 본다.
+pub fn title_hash(title: &str) -> String {
+    fnv1a_hex(title.chars().filter(|c| !c.is_whitespace()))
+}
+
+/// 재공지 판정용 제목 정규화: 앞머리 괄호 태그(`[재공지]`, `(마감연장)`, `【수정】` 등)를
+/// 모두 떼고, 공백/기호를 지운 뒤 소문자로.
+pub fn normalize_dup_title(title: &str) -> String {
+    let mut rest = title.trim();
+    loop {
+        let close = match rest.chars().next() {
+            Some('[') => ']',
+            Some('(') => ')',
+            Some('【') => '】',
+            Some('<') => '>',
+            _ => break,
+        };
+        match rest.find(close) {
+            Some(end) => rest = rest[end + close.len_utf8()..].trim_start(),
+            None => break,
+        }
+    }
+    rest.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// 정규화한 두 제목의 유사도 (글자 bigram Dice 계수, 0.0~1.0).
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    fn bigrams(s: &str) -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+    if a == b {
+        return 1.0;
+    }
+    let (x, mut y) = (bigrams(a), bigrams(b));
+    if x.is_empty() || y.is_empty() {
+        return 0.0;
+    }
+    let total = x.len() + y.len();
+    let mut common = 0;
+    for bg in &x {
+        if let Some(pos) = y.iter().position(|other| other == bg) {
+            y.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / total as f64
+}
+
+/// 같은 소스 재공지 억제 설정 (`bot.near_dup_threshold`).
+#[derive(Debug, Clone, Copy)]
+struct NearDup {
+    threshold: f64,
+    window_days: u32,
+}
+
+fn fnv1a_hex(chars: impl Iterator<Item = char>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for ch in chars {
+        let mut buf = [0u8; 4];
+        for b in ch.encode_utf8(&mut buf).bytes() {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
 }
 
 /// 사용자 구독 정보.
@@ -16,18 +128,152 @@ fn now_sqlite() -> String {
 pub struct UserSubs {
     pub keywords: Vec<String>,
     pub sources: Vec<String>,
+    /// 학과 구독별 카테고리 필터 (필터가 있는 구독만).
+    pub source_categories: std::collections::HashMap<String, Vec<String>>,
+}
+
+/// `/delete`용 채널 게시 기록 (`get_channel_post`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelPost {
+    pub title: String,
+    pub source_key: String,
+    /// 게시한 채널. 이 기록을 남기기 전에 올린 글은 `None`.
+    pub chat: Option<String>,
+    pub message_id: i32,
+    /// 올린 지 `EDIT_WINDOW_HOURS` 이내 (텔레그램이 봇의 삭제를 허용하는 기간).
+    pub deletable: bool,
+    pub deleted: bool,
+}
+
+/// 정렬·기간 기준 시각: 게시판 날짜(`published`)가 `YYYY-MM-DD…` 꼴이고 처음 발견한 날
+/// (`crawled_at`)보다 뒤가 아니면 그것, 아니면 발견 시각. 크롤링이 며칠 멈췄다 재개돼도
+/// 옛 공지가 최신처럼 보이지 않고, 날짜가 깨지거나 미래인 게시판은 발견 순서를 따른다.
+const SORT_TIME_SQL: &str = "CASE WHEN published GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]*'
+       AND substr(published, 1, 10) <= substr(crawled_at, 1, 10)
+     THEN published ELSE crawled_at END";
+
+/// `/notice`용 공지 시각 원본 (`get_notice_debug`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoticeDebug {
+    pub id: i64,
+    pub source_key: String,
+    pub notice_id: String,
+    pub title: String,
+    /// 게시판에 적힌 게시일 (정규화 후).
+    pub published: Option<String>,
+    /// 처음 발견(수집)한 시각.
+    pub crawled_at: String,
+    pub notified_at: Option<String>,
+    /// 정렬에 쓰는 시각 (`SORT_TIME_SQL`).
+    pub sort_time: String,
+}
+
+/// `merge_sources` 결과.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMerge {
+    /// 새 키로 옮긴 공지 수.
+    pub notices: usize,
+    /// 새 키에 이미 같은 공지 ID가 있어 지운 옛 공지 수.
+    pub duplicates: usize,
+    /// 새 키로 옮긴 학과 구독 수 (이미 새 키를 구독 중인 사용자는 제외).
+    pub subs: usize,
+}
+
+/// `/whoami`용 사용자 행 원본 (`get_user_debug`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserDebug {
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub registered: String,
+    pub is_active: bool,
+    pub lang: Option<String>,
+    /// (키워드, 등록 시각)
+    pub keywords: Vec<(String, String)>,
+    /// (소스 키, 카테고리 필터 원문, 등록 시각)
+    pub sources: Vec<(String, Option<String>, String)>,
+}
+
+/// 주간 리포트 집계 (`get_weekly_stats`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeeklyStats {
+    /// 소스별 수집 공지 수 (많은 순).
+    pub crawled: Vec<(String, u32)>,
+    pub new_users: u32,
+    pub dms_sent: u32,
+    /// 계속 실패 중인 소스: (소스, 연속 실패 횟수, 마지막 성공 시각).
+    pub failing: Vec<(String, u32, Option<String>)>,
 }
 
+/// 주간 리포트에서 "계속 실패"로 보는 연속 실패 횟수.
+const PERSISTENT_ERROR_COUNT: u32 = 3;
+
 /// 크롤 상태 통계.
 #[derive(Debug, Clone)]
 pub struct CrawlStat {
     pub source_key: String,
     pub last_crawled: Option<String>,
     pub error_count: u32,
+    /// 연속 실패로 자동 비활성화된 경우 재시도 시각 (KST).
+    pub disabled_until: Option<String>,
+    /// 마지막 실패의 에러 메시지.
+    pub last_error: Option<String>,
+    /// 목록 수집(fetch+parse) 소요 시간 EMA (ms).
+    pub avg_duration_ms: Option<f64>,
 }
 
-/// A stored notice from the database.
-#[derive(Debug, Clone)]
+impl CrawlStat {
+    /// 지금 자동 비활성(쿨다운) 상태인지.
+    pub fn is_cooling_down(&self) -> bool {
+        self.disabled_until
+            .as_deref()
+            .is_some_and(|until| until > now_sqlite().as_str())
+    }
+}
+
+/// 소스별 누적 공지 수와 마지막 성공 크롤 시각 (`/sources`용).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSummary {
+    pub source_key: String,
+    pub notice_count: u64,
+    pub last_success: Option<String>,
+}
+
+/// 일일 DM 한도를 넘어 요약으로 보낸 공지의 `dm_log.match_type`.
+pub const DM_SUMMARY_MATCH: &str = "summary";
+
+/// 텔레그램이 봇 메시지 수정을 허용하는 기간 (시간). 지나면 새로 올린다.
+const EDIT_WINDOW_HOURS: i64 = 48;
+
+/// 자동 비활성화 첫 쿨다운. 이후 실패마다 두 배.
+const COOLDOWN_BASE_MINUTES: i64 = 30;
+/// 쿨다운 상한.
+const COOLDOWN_MAX_HOURS: i64 = 24;
+
+/// 연속 `err_count`회 실패한 소스의 쿨다운 길이. `threshold` 미만이면 `None`.
+/// threshold에서 30분, 이후 1회마다 두 배 (최대 24시간).
+pub fn error_cooldown(err_count: u32, threshold: u32) -> Option<Duration> {
+    if threshold == 0 || err_count < threshold {
+        return None;
+    }
+    let doublings = (err_count - threshold).min(16);
+    let minutes = COOLDOWN_BASE_MINUTES.saturating_mul(1i64 << doublings);
+    Some(Duration::minutes(minutes).min(Duration::hours(COOLDOWN_MAX_HOURS)))
+}
+
+/// 크롤 소요 시간 EMA의 새 측정값 가중치. 클수록 최근 값에 빨리 따라간다.
+const DURATION_EMA_ALPHA: f64 = 0.2;
+
+/// 소요 시간 지수이동평균 갱신. 첫 측정이면 그 값 그대로.
+pub fn duration_ema(prev: Option<f64>, sample_ms: f64) -> f64 {
+    match prev {
+        Some(prev) => prev + DURATION_EMA_ALPHA * (sample_ms - prev),
+        None => sample_ms,
+    }
+}
+
+/// A stored notice from the database. `category`는 태그 문자열 그대로 직렬화된다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notice {
     pub id: i64,
     #[allow(dead_code)]
@@ -39,10 +285,53 @@ pub struct Notice {
     pub category: String,
     pub published: Option<String>,
     pub source_display_name: String,
+    /// 상세 페이지 본문 요약 (`fetch_detail` 소스만).
+    pub body: Option<String>,
+    /// 처음 본 뒤 제목이 수정된 공지 (`bot.notify_on_edit`).
+    pub edited: bool,
+    /// 긴급 공지 (`priority` > 0, `bot.urgent_keywords`). 먼저, 지연 없이 게시한다.
+    #[serde(default)]
+    pub urgent: bool,
+}
+
+/// `Notice`로 읽는 컬럼. 순서는 `notice_from_row`와 맞추고, 테이블 별칭은 `n`.
+const NOTICE_COLUMNS: &str =
+    "n.id, n.source_key, n.notice_id, n.title, n.url, n.author, n.category, n.published, n.body, n.edited, n.priority";
+
+/// `NOTICE_COLUMNS` 다음 컬럼의 위치.
+const NOTICE_COLUMN_COUNT: usize = 11;
+
+/// `offset`번째 컬럼부터 `NOTICE_COLUMNS`를 읽는다. 표시 이름은 소스 키 그대로
+/// (설정의 이름이 필요하면 `with_display_name`).
+fn notice_from_row(row: &rusqlite::Row<'_>, offset: usize) -> rusqlite::Result<Notice> {
+    let source_key: String = row.get(offset + 1)?;
+    Ok(Notice {
+        id: row.get(offset)?,
+        source_display_name: source_key.clone(),
+        source_key,
+        notice_id: row.get(offset + 2)?,
+        title: row.get(offset + 3)?,
+        url: row.get(offset + 4)?,
+        author: row.get(offset + 5)?,
+        category: row.get::<_, Option<String>>(offset + 6)?.unwrap_or_else(|| "general".into()),
+        published: row.get(offset + 7)?,
+        body: row.get(offset + 8)?,
+        edited: row.get::<_, Option<i64>>(offset + 9)?.unwrap_or(0) != 0,
+        urgent: row.get::<_, Option<i64>>(offset + 10)?.unwrap_or(0) > 0,
+    })
+}
+
+/// 소스 키 → 설정의 표시 이름 (없으면 키 그대로).
+fn with_display_name(mut notice: Notice, names: &std::collections::HashMap<String, String>) -> Notice {
+    if let Some(name) = names.get(&notice.source_key) {
+        notice.source_display_name = name.clone();
+    }
+    notice
 }
 
 pub struct Database {
     conn: Connection,
+    near_dup: Option<NearDup>,
 }
 
 impl Database {
@@ -62,7 +351,7 @@ impl Database {
                 category    TEXT DEFAULT 'general',
                 published   TEXT,
                 deadline    TEXT,
-                crawled_at  TEXT NOT NULL DEFAULT (datetime('now')),
+                crawled_at  TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
                 notified    INTEGER DEFAULT 0,
                 UNIQUE(source_key, notice_id)
             );
@@ -79,7 +368,7 @@ impl Database {
                 telegram_id  INTEGER PRIMARY KEY,
                 username     TEXT,
                 first_name   TEXT,
-                registered   TEXT NOT NULL DEFAULT (datetime('now')),
+                registered   TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
                 is_active    INTEGER DEFAULT 1
             );
 
@@ -87,7 +376,7 @@ impl Database {
                 id           INTEGER PRIMARY KEY AUTOINCREMENT,
                 telegram_id  INTEGER NOT NULL REFERENCES users(telegram_id),
                 keyword      TEXT NOT NULL,
-                created_at   TEXT NOT NULL DEFAULT (datetime('now')),
+                created_at   TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
                 UNIQUE(telegram_id, keyword)
             );
 
@@ -95,7 +384,7 @@ impl Database {
                 id           INTEGER PRIMARY KEY AUTOINCREMENT,
                 telegram_id  INTEGER NOT NULL REFERENCES users(telegram_id),
                 source_key   TEXT NOT NULL,
-                created_at   TEXT NOT NULL DEFAULT (datetime('now')),
+                created_at   TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
                 UNIQUE(telegram_id, source_key)
             );
 
@@ -105,29 +394,189 @@ impl Database {
                 telegram_id  INTEGER NOT NULL,
                 match_type   TEXT NOT NULL,
                 match_value  TEXT,
-                sent_at      TEXT NOT NULL DEFAULT (datetime('now')),
+                sent_at      TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
                 UNIQUE(notice_id, telegram_id)
             );
             CREATE INDEX IF NOT EXISTS idx_dm_log ON dm_log(notice_id);
+
+            CREATE TABLE IF NOT EXISTS dm_deferred (
+                notice_id    INTEGER NOT NULL,
+                telegram_id  INTEGER NOT NULL,
+                deferred_at  TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
+                UNIQUE(notice_id, telegram_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS dm_dismissed (
+                notice_id     INTEGER NOT NULL,
+                telegram_id   INTEGER NOT NULL,
+                dismissed_at  TEXT NOT NULL DEFAULT (datetime('now', '+9 hours')),
+                UNIQUE(notice_id, telegram_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS feedback (
+                id           INTEGER PRIMARY KEY AUTOINCREMENT,
+                telegram_id  INTEGER NOT NULL,
+                username     TEXT,
+                message      TEXT NOT NULL,
+                forwarded    INTEGER DEFAULT 0,
+                created_at   TEXT NOT NULL DEFAULT (datetime('now', '+9 hours'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_feedback_user ON feedback(telegram_id, created_at);
+
+            CREATE TABLE IF NOT EXISTS report_log (
+                kind     TEXT PRIMARY KEY,
+                sent_at  TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS category_overrides (
+                content_hash  TEXT PRIMARY KEY,
+                category      TEXT NOT NULL,
+                title         TEXT,
+                set_by        INTEGER,
+                created_at    TEXT NOT NULL DEFAULT (datetime('now', '+9 hours'))
+            );
+
+            CREATE TABLE IF NOT EXISTS notify_queue (
+                notice_id     INTEGER PRIMARY KEY,
+                attempts      INTEGER NOT NULL DEFAULT 0,
+                next_retry_at TEXT NOT NULL,
+                parts_sent    INTEGER NOT NULL DEFAULT 0,
+                parts_markup  TEXT
+            );
             ",
         )?;
 
-        Ok(Self { conn })
+        // 기존 DB에 나중에 추가된 컬럼 보강
+        add_column_if_missing(&conn, "notices", "body", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "content_hash", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "notified_at", "TEXT")?;
+        add_column_if_missing(&conn, "source_subs", "categories", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "title_hash", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "edited", "INTEGER DEFAULT 0")?;
+        add_column_if_missing(&conn, "crawl_state", "disabled_until", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "views", "INTEGER")?;
+        add_column_if_missing(&conn, "crawl_state", "last_success", "TEXT")?;
+        add_column_if_missing(&conn, "crawl_state", "last_error", "TEXT")?;
+        add_column_if_missing(&conn, "crawl_state", "avg_duration_ms", "REAL")?;
+        add_column_if_missing(&conn, "notices", "is_pinned", "INTEGER DEFAULT 0")?;
+        add_column_if_missing(&conn, "notices", "channel_message_id", "INTEGER")?;
+        add_column_if_missing(&conn, "notices", "channel_posted_at", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "channel_chat", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "deleted_at", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "priority", "INTEGER DEFAULT 0")?;
+        add_column_if_missing(&conn, "notices", "lang", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "dm_skipped", "INTEGER DEFAULT 0")?;
+        add_column_if_missing(&conn, "users", "lang", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "images", "TEXT")?;
+        add_column_if_missing(&conn, "notices", "dedup_hash", "TEXT")?;
+        add_column_if_missing(&conn, "crawl_state", "empty_streak", "INTEGER DEFAULT 0")?;
+        add_column_if_missing(&conn, "notify_queue", "parts_sent", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "notify_queue", "parts_markup", "TEXT")?;
+        migrate(&conn)?;
+        backfill_dedup_hash(&conn)?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_content_hash ON notices(content_hash);
+             CREATE INDEX IF NOT EXISTS idx_dedup_hash ON notices(dedup_hash);
+             CREATE INDEX IF NOT EXISTS idx_notice_url ON notices(source_key, url);",
+        )?;
+
+        Ok(Self { conn, near_dup: None })
+    }
+
+    /// 기존 DB를 읽기 전용으로 연다 (`doctor`). 파일을 만들거나 스키마를 바꾸지 않고,
+    /// 파일이 없으면 실패한다.
+    pub fn open_read_only(path: &str) -> anyhow::Result<Self> {
+        if !std::path::Path::new(path).is_file() {
+            anyhow::bail!("database file not found");
+        }
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(std::time::Duration::from_millis(5000))?;
+        Ok(Self { conn, near_dup: None })
+    }
+
+    /// 쓰기 한 문장 실행. 크롤 스레드와 봇 핸들러가 동시에 쓰다 `busy_timeout`을 넘겨
+    /// `SQLITE_BUSY`/`SQLITE_LOCKED`가 나면 잠깐 쉬고 다시 시도한다 (`retry_busy`).
+    fn execute<P: rusqlite::Params + Copy>(&self, sql: &str, params: P) -> rusqlite::Result<usize> {
+        retry_busy(|| self.conn.execute(sql, params))
+    }
+
+    /// `f` 안의 쓰기를 한 트랜잭션으로 묶는다. `f`가 에러를 내면 전부 롤백.
+    /// WAL에서 건별 커밋보다 빠르고, serve 모드 리더와의 락 경합도 줄어든다.
+    /// 처음부터 쓰기 잠금을 잡는다(`BEGIN IMMEDIATE`). 읽고 나서 쓰기로 올리다 다른 연결이
+    /// 먼저 커밋하면 `busy_timeout`으로도 풀리지 않는 `SQLITE_BUSY`가 나기 때문.
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let tx = retry_busy(|| Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate))?;
+        let value = f(self)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// 한 소스의 공지를 한 트랜잭션으로 저장. 반환: 새로 알릴 공지 수.
+    /// 중간에 실패하면 아무것도 저장하지 않는다.
+    pub fn insert_batch(
+        &self,
+        source_key: &str,
+        notices: &[RawNotice],
+        display_name: &str,
+        dedup_global: bool,
+    ) -> anyhow::Result<usize> {
+        self.transaction(|db| {
+            let mut new_count = 0;
+            for notice in notices {
+                if db.renumber_by_url(source_key, notice, notices)? {
+                    continue;
+                }
+                if db.insert_if_new(source_key, notice, display_name, dedup_global)? {
+                    new_count += 1;
+                }
+            }
+            Ok(new_count)
+        })
+    }
+
+    /// 같은 소스에서 `window_days`일 안에 정규화 제목 유사도가 `threshold` 이상인
+    /// 공지가 있으면 재공지로 보고 알림 없이 기록만 한다. 0 이하면 끔.
+    pub fn with_near_dup(mut self, threshold: f64, window_days: u32) -> Self {
+        self.near_dup = (threshold > 0.0).then_some(NearDup { threshold, window_days });
+        self
     }
 
     /// Insert a new notice. Returns true if it was actually new (not a duplicate).
+    ///
+    /// `dedup_global`이 켜진 소스는 다른 소스에 최근 올라온 같은 공지
+    /// (`dedup_hash` 일치: 제목과 링크가 같음)를 중복으로 본다. 이 경우 알림 없이 `notified=1`로
+    /// 기록만 하고 false를 반환한다.
+    ///
+    /// 고정 공지는 매 크롤마다 맨 위에 다시 나오므로 id만으로 판단하지 않는다.
+    /// 같은 소스에 내용 해시가 같은 고정 공지가 이미 있으면 게시판이 id를 바꿔 단
+    /// 것으로 보고 역시 알림 없이 기록만 한다. `with_near_dup`이 켜져 있으면
+    /// 제목만 조금 바뀐 재공지(`[재공지] ...`)도 같은 식으로 처리한다.
+    ///
+    /// 글 번호만 바뀐 공지는 먼저 `renumber_by_url`로 걸러야 한다.
     pub fn insert_if_new(
         &self,
         source_key: &str,
         notice: &RawNotice,
         display_name: &str,
+        dedup_global: bool,
     ) -> anyhow::Result<bool> {
-        let category = Category::classify(&notice.title);
+        let category = self.classify(notice.category.as_deref(), &notice.title)?;
         let now = now_sqlite();
+        let hash = content_hash(&notice.title);
 
-        let affected = self.conn.execute(
-            "INSERT OR IGNORE INTO notices (source_key, notice_id, title, url, author, category, published, crawled_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        let dedup = dedup_hash(&notice.title, &notice.url);
+
+        let cross_posted = dedup_global && self.is_content_seen(&dedup, source_key)?;
+        let pinned_repeat =
+            notice.is_pinned && self.is_pinned_seen(source_key, &hash, &notice.notice_id)?;
+        let mut suppressed = cross_posted || pinned_repeat;
+
+        let affected = self.execute(
+            "INSERT OR IGNORE INTO notices (source_key, notice_id, title, url, author, category, published, crawled_at, content_hash, notified, title_hash, views, is_pinned, lang, dedup_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 source_key,
                 notice.notice_id,
@@ -137,11 +586,64 @@ impl Database {
                 category.as_str(),
                 notice.date,
                 now,
+                hash,
+                suppressed as i64,
+                title_hash(&notice.title),
+                notice.views,
+                notice.is_pinned as i64,
+                crate::lang::detect(&notice.title),
+                dedup,
             ],
         )?;
 
+        // 재공지 검사는 새로 들어간 행만 (이미 있는 공지마다 조회하지 않도록)
+        let mut near_dup_of = None;
+        if affected > 0 && !suppressed {
+            if let Some(near_dup) = self.near_dup {
+                near_dup_of = self.find_near_dup(source_key, &notice.notice_id, &notice.title, near_dup)?;
+                if near_dup_of.is_some() {
+                    self.execute(
+                        "UPDATE notices SET notified = 1 WHERE source_key = ?1 AND notice_id = ?2",
+                        params![source_key, notice.notice_id],
+                    )?;
+                    suppressed = true;
+                }
+            }
+        }
+
+        if let Some(original) = near_dup_of {
+            tracing::info!(
+                source = %source_key,
+                notice_id = %notice.notice_id,
+                title = %notice.title,
+                original = %original,
+                "Skipping near-duplicate re-post"
+            );
+        } else if affected > 0 && cross_posted {
+            tracing::info!(
+                source = %source_key,
+                notice_id = %notice.notice_id,
+                title = %notice.title,
+                "Skipping cross-posted notice"
+            );
+        } else if affected > 0 && pinned_repeat {
+            tracing::info!(
+                source = %source_key,
+                notice_id = %notice.notice_id,
+                title = %notice.title,
+                "Skipping pinned notice re-posted under a new id"
+            );
+        } else if affected == 0 && notice.is_pinned {
+            // 나중에 고정된 공지도 이후 id 변경을 알아볼 수 있게 표시
+            self.execute(
+                "UPDATE notices SET is_pinned = 1
+                 WHERE source_key = ?1 AND notice_id = ?2 AND is_pinned = 0",
+                params![source_key, notice.notice_id],
+            )?;
+        }
+
         // Store display_name mapping in crawl_state for later use
-        let _ = self.conn.execute(
+        let _ = self.execute(
             "INSERT INTO crawl_state (source_key, last_crawled) VALUES (?1, ?2)
              ON CONFLICT(source_key) DO NOTHING",
             params![source_key, now],
@@ -150,174 +652,989 @@ impl Database {
         // We don't actually use display_name in the DB, but we pass it through via Notice
         let _ = display_name;
 
-        Ok(affected > 0)
+        Ok(affected > 0 && !suppressed)
     }
 
-    /// Get pending notifications (notified=0), most recent first.
-    pub fn get_pending(&self, limit: usize, source_display_names: &std::collections::HashMap<String, String>) -> anyhow::Result<Vec<Notice>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, source_key, notice_id, title, url, author, category, published
-             FROM notices WHERE notified = 0 ORDER BY crawled_at DESC LIMIT ?1",
+    /// 게시판이 글 번호만 바꾼 공지면 기존 행의 번호를 새 번호로 바꾼다 (`insert_if_new` 전에 호출).
+    /// 새 행을 만들면 DM 기록(행 ID 기준)이 끊겨 채널/DM으로 다시 나가기 때문이다.
+    /// 같은 소스에서 그 URL을 쓰는 행이 하나뿐이고, 그 행의 옛 번호가 이번 목록(`listing`)에
+    /// 없을 때만 번호가 바뀐 것으로 본다. 옛 번호가 아직 목록에 있으면 URL만 같은 다른
+    /// 글이다 (목록 주소로 링크하는 게시판 등). 반환: 번호를 바꿨으면 true.
+    pub fn renumber_by_url(&self, source_key: &str, notice: &RawNotice, listing: &[RawNotice]) -> anyhow::Result<bool> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, notice_id FROM notices WHERE source_key = ?1 AND url = ?2 LIMIT 2")?;
+        let rows = stmt
+            .query_map(params![source_key, notice.url], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let [(id, old_id)] = rows.as_slice() else {
+            return Ok(false);
+        };
+        if *old_id == notice.notice_id || listing.iter().any(|n| n.notice_id == *old_id) {
+            return Ok(false);
+        }
+        let changed = self.execute(
+            "UPDATE notices SET notice_id = ?2
+             WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM notices WHERE source_key = ?3 AND notice_id = ?2)",
+            params![id, notice.notice_id, source_key],
         )?;
+        if changed > 0 {
+            tracing::info!(
+                source = %source_key,
+                old_id = %old_id,
+                notice_id = %notice.notice_id,
+                "Notice renumbered; keeping the original row"
+            );
+        }
+        Ok(changed > 0)
+    }
 
-        let notices = stmt.query_map(params![limit as i64], |row| {
-            let source_key: String = row.get(1)?;
-            let display_name = source_display_names
-                .get(&source_key)
-                .cloned()
-                .unwrap_or_else(|| source_key.clone());
-            Ok(Notice {
-                id: row.get(0)?,
-                source_key,
-                notice_id: row.get(2)?,
-                title: row.get(3)?,
-                url: row.get(4)?,
-                author: row.get(5)?,
-                category: row.get::<_, Option<String>>(6)?.unwrap_or_else(|| "general".into()),
-                published: row.get(7)?,
-                source_display_name: display_name,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+    /// `lang`이 지정된 소스의 공지 언어를 설정값으로 맞춘다 (제목 추정보다 우선).
+    pub fn set_source_lang(&self, source_key: &str, lang: &str) -> anyhow::Result<usize> {
+        let n = self.execute(
+            "UPDATE notices SET lang = ?2 WHERE source_key = ?1 AND lang IS NOT ?2",
+            params![source_key, lang],
+        )?;
+        Ok(n)
+    }
 
-        Ok(notices)
+    /// 저장된 공지 언어. 예전 행처럼 기록이 없으면 `None`.
+    pub fn get_notice_lang(&self, notice_db_id: i64) -> anyhow::Result<Option<String>> {
+        let lang = self
+            .conn
+            .query_row(
+                "SELECT lang FROM notices WHERE id = ?1",
+                params![notice_db_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(lang)
     }
 
-    /// Mark a notice as notified.
-    pub fn mark_notified(&self, id: i64) -> anyhow::Result<()> {
-        self.conn.execute(
-            "UPDATE notices SET notified = 1 WHERE id = ?1",
-            params![id],
+    /// 같은 소스의 최근 공지 중 제목이 거의 같은 것의 제목.
+    fn find_near_dup(
+        &self,
+        source_key: &str,
+        notice_id: &str,
+        title: &str,
+        near_dup: NearDup,
+    ) -> anyhow::Result<Option<String>> {
+        let normalized = normalize_dup_title(title);
+        if normalized.is_empty() {
+            return Ok(None);
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT title FROM notices
+             WHERE source_key = ?1 AND notice_id != ?2 AND crawled_at >= ?3
+             ORDER BY id DESC LIMIT 500",
         )?;
-        Ok(())
+        let since = kst::sqlite_ago(Duration::days(near_dup.window_days as i64));
+        let titles = stmt.query_map(params![source_key, notice_id, since], |row| row.get::<_, String>(0))?;
+        for recent in titles {
+            let recent = recent?;
+            if title_similarity(&normalized, &normalize_dup_title(&recent)) >= near_dup.threshold {
+                return Ok(Some(recent));
+            }
+        }
+        Ok(None)
     }
 
-    /// Update crawl state after successful crawl.
-    pub fn update_crawl_state(&self, source_key: &str, last_id: Option<&str>) -> anyhow::Result<()> {
-        let now = now_sqlite();
-        self.conn.execute(
-            "INSERT INTO crawl_state (source_key, last_crawled, last_notice_id, error_count)
-             VALUES (?1, ?2, ?3, 0)
-             ON CONFLICT(source_key) DO UPDATE SET
-               last_crawled = ?2,
-               last_notice_id = COALESCE(?3, last_notice_id),
-               error_count = 0",
-            params![source_key, now, last_id],
+    /// 같은 소스에 내용이 같은 고정 공지가 다른 id로 이미 저장돼 있는지.
+    fn is_pinned_seen(&self, source_key: &str, hash: &str, notice_id: &str) -> anyhow::Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notices
+             WHERE source_key = ?1 AND content_hash = ?2 AND is_pinned = 1 AND notice_id != ?3",
+            params![source_key, hash, notice_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 이미 저장된 공지의 조회수 갱신 (매 크롤마다 호출해 인기 추이를 남긴다).
+    pub fn update_views(&self, source_key: &str, notice_id: &str, views: u32) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET views = ?3 WHERE source_key = ?1 AND notice_id = ?2",
+            params![source_key, notice_id, views],
         )?;
         Ok(())
     }
 
-    /// Increment error count and return the new count.
-    pub fn increment_error(&self, source_key: &str) -> anyhow::Result<u32> {
-        let now = now_sqlite();
-        self.conn.execute(
-            "INSERT INTO crawl_state (source_key, last_crawled, error_count)
-             VALUES (?1, ?2, 1)
-             ON CONFLICT(source_key) DO UPDATE SET
-               last_crawled = ?2,
-               error_count = error_count + 1",
-            params![source_key, now],
+    /// 최근 `days`일 게시 공지 중 조회수 상위 (`/trending`용). 반환: (공지, 조회수).
+    pub fn get_trending(&self, days: i64, limit: usize) -> anyhow::Result<Vec<(Notice, u32)>> {
+        let since = (kst::today() - Duration::days(days)).to_string();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}, views
+             FROM notices n
+             WHERE views IS NOT NULL
+               AND substr({sort}, 1, 10) >= ?1
+             ORDER BY views DESC, id DESC
+             LIMIT ?2",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![since, limit as i64], |row| {
+                Ok((notice_from_row(row, 0)?, row.get(NOTICE_COLUMN_COUNT)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
+    /// 최근 `days`일 공지 (게시일, 없으면 수집일 기준). 최신순.
+    pub fn get_recent_days(&self, days: i64, limit: usize) -> anyhow::Result<Vec<Notice>> {
+        let since = (kst::today() - Duration::days(days)).to_string();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE substr({sort}, 1, 10) >= ?1
+             ORDER BY {sort} DESC, crawled_at DESC, id DESC
+             LIMIT ?2",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![since, limit as i64], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
+    /// 이미 저장된 공지의 제목이 바뀌었으면 갱신하고 true를 반환.
+    /// 공백만 달라진 경우는 수정으로 보지 않는다. `notify`가 켜져 있으면
+    /// `notified=0, edited=1`로 되돌려 다음 발송 때 "수정됨"으로 다시 알린다.
+    pub fn update_if_edited(&self, source_key: &str, notice: &RawNotice, notify: bool) -> anyhow::Result<bool> {
+        let row: Option<(i64, String, Option<String>)> = self
+            .conn
+            .query_row(
+                "SELECT id, title, title_hash FROM notices WHERE source_key = ?1 AND notice_id = ?2",
+                params![source_key, notice.notice_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let (id, old_title, stored_hash) = match row {
+            Some(r) => r,
+            None => return Ok(false),
+        };
+
+        let new_hash = title_hash(&notice.title);
+        let old_hash = stored_hash.clone().unwrap_or_else(|| title_hash(&old_title));
+        if old_hash == new_hash {
+            // 컬럼 추가 전 저장된 행은 해시만 채운다
+            if stored_hash.is_none() {
+                self.execute(
+                    "UPDATE notices SET title_hash = ?1 WHERE id = ?2",
+                    params![new_hash, id],
+                )?;
+            }
+            return Ok(false);
+        }
+
+        let category = self.classify(notice.category.as_deref(), &notice.title)?;
+        self.execute(
+            "UPDATE notices SET title = ?1, title_hash = ?2, content_hash = ?3, category = ?4,
+                    notified = CASE WHEN ?5 THEN 0 ELSE notified END,
+                    edited = CASE WHEN ?5 THEN 1 ELSE edited END,
+                    dedup_hash = ?7
+             WHERE id = ?6",
+            params![
+                notice.title,
+                new_hash,
+                content_hash(&notice.title),
+                category.as_str(),
+                notify,
+                id,
+                dedup_hash(&notice.title, &notice.url)
+            ],
         )?;
+        tracing::info!(
+            source = %source_key,
+            notice_id = %notice.notice_id,
+            old = %old_title,
+            new = %notice.title,
+            "Notice title edited"
+        );
+        Ok(true)
+    }
 
-        let count: u32 = self.conn.query_row(
-            "SELECT error_count FROM crawl_state WHERE source_key = ?1",
-            params![source_key],
+    /// 다른 소스에 같은 공지(`dedup_hash`)가 최근(`DEDUP_WINDOW_DAYS`) 올라왔는지 확인.
+    /// 기간을 제한해 학기마다 반복되는 같은 제목의 공지는 중복으로 보지 않는다.
+    pub fn is_content_seen(&self, hash: &str, source_key: &str) -> anyhow::Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notices
+             WHERE dedup_hash = ?1 AND source_key != ?2
+               AND crawled_at >= ?3",
+            params![hash, source_key, kst::sqlite_ago(Duration::days(DEDUP_WINDOW_DAYS as i64))],
             |row| row.get(0),
         )?;
-
-        Ok(count)
+        Ok(count > 0)
     }
 
-    /// Reset error count for a source (used in tests and Phase 2).
-    #[allow(dead_code)]
-    pub fn reset_error(&self, source_key: &str) -> anyhow::Result<()> {
-        self.conn.execute(
-            "UPDATE crawl_state SET error_count = 0 WHERE source_key = ?1",
-            params![source_key],
-        )?;
-        Ok(())
+    /// Get pending notifications (notified=0), most recent first by default.
+    /// 재시도 대기열(`notify_queue`)에 있는 공지는 `get_due_retries`가 담당하므로 제외.
+    /// `order`는 `bot.post_order`. 기준 시각은 `SORT_TIME_SQL`(믿을 만한 게시일, 아니면
+    /// 발견 시각)이고, 같으면 발견 순서와 게시판 목록 순서(최신 글이 먼저 삽입됨)를 따른다.
+    pub fn get_pending(
+        &self,
+        limit: usize,
+        order: PostOrder,
+        source_display_names: &std::collections::HashMap<String, String>,
+    ) -> anyhow::Result<Vec<Notice>> {
+        // 긴급 공지는 어떤 순서에서든 먼저 (한도에 밀려 다음 사이클로 넘어가지 않게)
+        // 같은 게시 시각(날짜만 있는 게시판)이면 발견 순서로
+        let order_by = match order {
+            PostOrder::Latest => format!("COALESCE(priority, 0) DESC, {} DESC, crawled_at DESC", SORT_TIME_SQL),
+            PostOrder::Chronological => {
+                format!("COALESCE(priority, 0) DESC, {} ASC, crawled_at ASC, id DESC", SORT_TIME_SQL)
+            }
+            PostOrder::PinnedFirst => format!(
+                "COALESCE(priority, 0) DESC, COALESCE(is_pinned, 0) DESC, {} DESC, crawled_at DESC",
+                SORT_TIME_SQL
+            ),
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE notified = 0 AND id NOT IN (SELECT notice_id FROM notify_queue)
+             ORDER BY {order_by} LIMIT ?1",
+            cols = NOTICE_COLUMNS
+        ))?;
+
+        let notices = stmt.query_map(params![limit as i64], |row| {
+            notice_from_row(row, 0).map(|n| with_display_name(n, source_display_names))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notices)
     }
 
-    // ── Phase 2: 구독 / DM 관련 메서드 ─────────────────────────────
+    /// `since`(YYYY-MM-DD, KST) 이후 수집된 공지를 JSON 배열로 내보낸다 (오래된 순).
+    /// `None`이면 전체.
+    pub fn export_json(
+        &self,
+        since: Option<&str>,
+        source_display_names: &std::collections::HashMap<String, String>,
+    ) -> anyhow::Result<String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE crawled_at >= ?1
+             ORDER BY crawled_at, id",
+            cols = NOTICE_COLUMNS
+        ))?;
+
+        let notices = stmt.query_map(params![since.unwrap_or("")], |row| {
+            notice_from_row(row, 0).map(|n| with_display_name(n, source_display_names))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
-    /// 사용자 등록 (첫 /start 시 호출). 이미 있으면 활성화만 갱신.
-    pub fn register_user(
+        Ok(serde_json::to_string_pretty(&notices)?)
+    }
+
+    /// 상세 페이지에서 얻은 본문/게시 시각 저장. 게시 시각은 있을 때만 덮어쓴다.
+    pub fn set_detail(
         &self,
-        telegram_id: i64,
-        username: Option<&str>,
-        first_name: Option<&str>,
+        source_key: &str,
+        notice_id: &str,
+        body: Option<&str>,
+        published: Option<&str>,
     ) -> anyhow::Result<()> {
-        self.conn.execute(
-            "INSERT INTO users (telegram_id, username, first_name)
-             VALUES (?1, ?2, ?3)
-             ON CONFLICT(telegram_id) DO UPDATE SET
-               username = COALESCE(?2, username),
-               first_name = COALESCE(?3, first_name),
-               is_active = 1",
-            params![telegram_id, username, first_name],
+        self.execute(
+            "UPDATE notices SET body = COALESCE(?3, body), published = COALESCE(?4, published)
+             WHERE source_key = ?1 AND notice_id = ?2",
+            params![source_key, notice_id, body, published],
         )?;
         Ok(())
     }
 
-    /// 키워드 구독 추가. 이미 있으면 무시.
-    pub fn add_keyword_sub(&self, telegram_id: i64, keyword: &str) -> anyhow::Result<bool> {
-        let affected = self.conn.execute(
-            "INSERT OR IGNORE INTO keyword_subs (telegram_id, keyword) VALUES (?1, ?2)",
-            params![telegram_id, keyword],
+    /// 긴급 공지로 표시 (`priority = 1`). 대기 중이면 다음 게시에서 가장 먼저 나간다.
+    pub fn mark_urgent(&self, source_key: &str, notice_id: &str) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET priority = 1 WHERE source_key = ?1 AND notice_id = ?2",
+            params![source_key, notice_id],
         )?;
-        Ok(affected > 0)
+        Ok(())
     }
 
-    /// 키워드 구독 제거.
-    pub fn remove_keyword_sub(&self, telegram_id: i64, keyword: &str) -> anyhow::Result<bool> {
-        let affected = self.conn.execute(
-            "DELETE FROM keyword_subs WHERE telegram_id = ?1 AND keyword = ?2",
-            params![telegram_id, keyword],
+    /// 상세 페이지 이미지 주소 저장 (줄바꿈으로 구분).
+    pub fn set_images(&self, source_key: &str, notice_id: &str, images: &[String]) -> anyhow::Result<()> {
+        let joined = (!images.is_empty()).then(|| images.join("\n"));
+        self.execute(
+            "UPDATE notices SET images = ?3 WHERE source_key = ?1 AND notice_id = ?2",
+            params![source_key, notice_id, joined],
         )?;
-        Ok(affected > 0)
+        Ok(())
     }
 
-    /// 소스(학과) 구독 추가.
-    pub fn add_source_sub(&self, telegram_id: i64, source_key: &str) -> anyhow::Result<bool> {
-        let affected = self.conn.execute(
-            "INSERT OR IGNORE INTO source_subs (telegram_id, source_key) VALUES (?1, ?2)",
-            params![telegram_id, source_key],
-        )?;
-        Ok(affected > 0)
+    /// 공지 DB ID → 저장된 이미지 주소. 이미지가 없는 공지는 빠진다.
+    pub fn get_images(&self, ids: &[i64]) -> anyhow::Result<std::collections::HashMap<i64, Vec<String>>> {
+        let mut stmt = self.conn.prepare("SELECT images FROM notices WHERE id = ?1 AND images IS NOT NULL")?;
+        let mut images = std::collections::HashMap::new();
+        for &id in ids {
+            let joined: Option<String> = stmt.query_row(params![id], |row| row.get(0)).optional()?;
+            if let Some(joined) = joined {
+                images.insert(id, joined.lines().map(String::from).collect());
+            }
+        }
+        Ok(images)
     }
 
-    /// 소스(학과) 구독 제거.
-    pub fn remove_source_sub(&self, telegram_id: i64, source_key: &str) -> anyhow::Result<bool> {
-        let affected = self.conn.execute(
-            "DELETE FROM source_subs WHERE telegram_id = ?1 AND source_key = ?2",
-            params![telegram_id, source_key],
-        )?;
-        Ok(affected > 0)
+    /// 재시도 시각이 된 발송 실패 공지 (원래 순서대로).
+    pub fn get_due_retries(&self, limit: usize, source_display_names: &std::collections::HashMap<String, String>) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notify_queue q JOIN notices n ON n.id = q.notice_id
+             WHERE n.notified = 0 AND q.next_retry_at <= ?2
+             ORDER BY n.id ASC LIMIT ?1",
+            cols = NOTICE_COLUMNS
+        ))?;
+
+        let notices = stmt.query_map(params![limit as i64, now_sqlite()], |row| {
+            notice_from_row(row, 0).map(|n| with_display_name(n, source_display_names))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notices)
     }
 
-    /// 특정 사용자의 전체 구독 정보 조회.
-    pub fn get_user_subs(&self, telegram_id: i64) -> anyhow::Result<UserSubs> {
-        let mut kw_stmt = self.conn.prepare(
-            "SELECT keyword FROM keyword_subs WHERE telegram_id = ?1 ORDER BY keyword",
+    /// 채널 발송 실패 기록. 재시도 간격은 60초부터 두 배씩, 최대 1시간.
+    /// 반환: 누적 시도 횟수.
+    pub fn record_send_failure(&self, id: i64) -> anyhow::Result<u32> {
+        self.execute(
+            "INSERT INTO notify_queue (notice_id, attempts, next_retry_at)
+             VALUES (?1, 1, datetime(?2, '+60 seconds'))
+             ON CONFLICT(notice_id) DO UPDATE SET
+               attempts = attempts + 1,
+               next_retry_at = datetime(?2, '+' || min(3600, 60 << attempts) || ' seconds')",
+            params![id, now_sqlite()],
         )?;
-        let keywords: Vec<String> = kw_stmt
-            .query_map(params![telegram_id], |row| row.get(0))?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let mut src_stmt = self.conn.prepare(
-            "SELECT source_key FROM source_subs WHERE telegram_id = ?1 ORDER BY source_key",
+        let attempts: u32 = self.conn.query_row(
+            "SELECT attempts FROM notify_queue WHERE notice_id = ?1",
+            params![id],
+            |row| row.get(0),
         )?;
-        let sources: Vec<String> = src_stmt
-            .query_map(params![telegram_id], |row| row.get(0))?
-            .collect::<Result<Vec<_>, _>>()?;
+        Ok(attempts)
+    }
 
-        Ok(UserSubs { keywords, sources })
+    /// 나눠 보내다 실패한 공지의 진행 상황: 이미 보낸 앞 조각 수와 그 마크업 이름.
+    /// 재시도 대기열에 있는 공지만 기록된다 (`record_send_failure` 다음에 호출).
+    pub fn set_send_progress(&self, id: i64, markup: &str, parts_sent: usize) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notify_queue SET parts_sent = ?2, parts_markup = ?3 WHERE notice_id = ?1",
+            params![id, parts_sent as i64, markup],
+        )?;
+        Ok(())
     }
 
-    /// 특정 소스를 구독 중인 활성 사용자 목록.
-    pub fn get_source_subscribers(&self, source_key: &str) -> anyhow::Result<Vec<i64>> {
+    /// 앞 조각을 이미 보낸 공지들의 (마크업 이름, 보낸 조각 수). 재시도 때 나머지만 보낸다.
+    pub fn get_send_progress(&self, ids: &[i64]) -> anyhow::Result<std::collections::HashMap<i64, (String, usize)>> {
         let mut stmt = self.conn.prepare(
-            "SELECT s.telegram_id FROM source_subs s
+            "SELECT parts_markup, parts_sent FROM notify_queue
+             WHERE notice_id = ?1 AND parts_sent > 0 AND parts_markup IS NOT NULL",
+        )?;
+        let mut progress = std::collections::HashMap::new();
+        for &id in ids {
+            let row: Option<(String, i64)> = stmt.query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?))).optional()?;
+            if let Some((markup, parts)) = row {
+                progress.insert(id, (markup, parts as usize));
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Mark a notice as notified (발송 성공 확인 후에만 호출). 재시도 대기열에서도 제거.
+    pub fn mark_notified(&self, id: i64) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET notified = 1, notified_at = ?2 WHERE id = ?1",
+            params![id, now_sqlite()],
+        )?;
+        self.execute("DELETE FROM notify_queue WHERE notice_id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 채널에 올린 메시지 ID와 채널 기록 (수정/삭제 대상). 처음 올린 시각만 남긴다.
+    pub fn set_channel_message_id(&self, id: i64, chat: &str, message_id: i32) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET channel_message_id = ?2, channel_chat = ?4,
+                    channel_posted_at = COALESCE(channel_posted_at, ?3)
+             WHERE id = ?1",
+            params![id, message_id, now_sqlite(), chat],
+        )?;
+        Ok(())
+    }
+
+    /// 아직 수정 가능한 채널 메시지 ID. 기록이 없거나 올린 지
+    /// `EDIT_WINDOW_HOURS`가 지났으면 `None` (새 게시물로 대신 알린다).
+    pub fn get_editable_message_id(&self, id: i64) -> anyhow::Result<Option<i32>> {
+        let message_id = self
+            .conn
+            .query_row(
+                "SELECT channel_message_id FROM notices
+                 WHERE id = ?1 AND channel_message_id IS NOT NULL AND channel_posted_at >= ?2
+                   AND deleted_at IS NULL",
+                params![id, kst::sqlite_ago(Duration::hours(EDIT_WINDOW_HOURS))],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(message_id)
+    }
+
+    /// 공지의 채널 게시 기록. 메시지 ID가 없으면 (묶음/사진 발송, 게시 전) `None`.
+    pub fn get_channel_post(&self, id: i64) -> anyhow::Result<Option<ChannelPost>> {
+        let post = self
+            .conn
+            .query_row(
+                "SELECT title, source_key, channel_chat, channel_message_id,
+                        COALESCE(channel_posted_at >= ?2, 0), deleted_at IS NOT NULL
+                 FROM notices WHERE id = ?1 AND channel_message_id IS NOT NULL",
+                params![id, kst::sqlite_ago(Duration::hours(EDIT_WINDOW_HOURS))],
+                |row| {
+                    Ok(ChannelPost {
+                        title: row.get(0)?,
+                        source_key: row.get(1)?,
+                        chat: row.get(2)?,
+                        message_id: row.get(3)?,
+                        deletable: row.get(4)?,
+                        deleted: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(post)
+    }
+
+    /// 공지의 게시일/발견 시각/정렬 기준 (`/notice`).
+    pub fn get_notice_debug(&self, id: i64) -> anyhow::Result<Option<NoticeDebug>> {
+        let notice = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT source_key, notice_id, title, published, crawled_at, notified_at, {}
+                     FROM notices WHERE id = ?1",
+                    SORT_TIME_SQL
+                ),
+                params![id],
+                |row| {
+                    Ok(NoticeDebug {
+                        id,
+                        source_key: row.get(0)?,
+                        notice_id: row.get(1)?,
+                        title: row.get(2)?,
+                        published: row.get(3)?,
+                        crawled_at: row.get(4)?,
+                        notified_at: row.get(5)?,
+                        sort_time: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(notice)
+    }
+
+    /// 채널 게시물을 지운 것으로 기록 (`/delete`). 이후 수정 알림은 새 게시물로 나간다.
+    pub fn mark_deleted(&self, id: i64) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET deleted_at = ?2 WHERE id = ?1",
+            params![id, now_sqlite()],
+        )?;
+        Ok(())
+    }
+
+    /// 소스 키 변경/병합 (`rename` 명령): 공지, 크롤 상태, 학과 구독을 `from`에서 `to`로
+    /// 한 트랜잭션에 옮긴다. 새 키에 같은 공지 ID가 이미 있으면 새 키 쪽을 남기고,
+    /// 옛 행이 발송된 적 있으면 새 행도 발송 완료로 표시해 다시 알리지 않는다.
+    pub fn merge_sources(&self, from: &str, to: &str) -> anyhow::Result<SourceMerge> {
+        anyhow::ensure!(from != to, "source keys are the same: {}", from);
+        self.transaction(|db| {
+            let mut merge = SourceMerge {
+                notices: db.execute(
+                    "UPDATE OR IGNORE notices SET source_key = ?2 WHERE source_key = ?1",
+                    params![from, to],
+                )?,
+                ..SourceMerge::default()
+            };
+
+            // 남은 옛 행은 새 키와 겹치는 공지
+            db.execute(
+                "UPDATE notices SET notified = 1
+                 WHERE source_key = ?2 AND notified = 0
+                   AND notice_id IN (SELECT notice_id FROM notices WHERE source_key = ?1 AND notified = 1)",
+                params![from, to],
+            )?;
+            for table in ["notify_queue", "dm_log", "dm_deferred", "dm_dismissed"] {
+                db.execute(
+                    &format!(
+                        "DELETE FROM {} WHERE notice_id IN (SELECT id FROM notices WHERE source_key = ?1)",
+                        table
+                    ),
+                    params![from],
+                )?;
+            }
+            merge.duplicates = db.execute("DELETE FROM notices WHERE source_key = ?1", params![from])?;
+
+            // 크롤 상태는 새 키에 이미 있으면 그쪽을 유지
+            db.execute(
+                "UPDATE OR IGNORE crawl_state SET source_key = ?2 WHERE source_key = ?1",
+                params![from, to],
+            )?;
+            db.execute("DELETE FROM crawl_state WHERE source_key = ?1", params![from])?;
+
+            merge.subs = db.execute(
+                "UPDATE OR IGNORE source_subs SET source_key = ?2 WHERE source_key = ?1",
+                params![from, to],
+            )?;
+            db.execute("DELETE FROM source_subs WHERE source_key = ?1", params![from])?;
+            Ok(merge)
+        })
+    }
+
+    /// 아직 안 보낸 공지를 발송 없이 처리 완료로 표시 (`seed` 명령). `source_key`가
+    /// 없으면 전체. 실제로 보낸 게 아니므로 `notified_at`은 비워 두어 DM/발송 통계에서
+    /// 빠진다. 반환: 표시한 공지 수.
+    pub fn mark_all_notified(&self, source_key: Option<&str>) -> anyhow::Result<usize> {
+        self.transaction(|db| {
+            db.execute(
+                "DELETE FROM notify_queue WHERE notice_id IN
+                   (SELECT id FROM notices WHERE notified = 0 AND (?1 IS NULL OR source_key = ?1))",
+                params![source_key],
+            )?;
+            let n = db.execute(
+                "UPDATE notices SET notified = 1
+                 WHERE notified = 0 AND (?1 IS NULL OR source_key = ?1)",
+                params![source_key],
+            )?;
+            Ok(n)
+        })
+    }
+
+    /// `min_date`보다 오래된 공지: 저장은 두되 발송 없이 처리 완료로 표시한다.
+    /// `notified_at`을 남기지 않으므로 DM 대상이나 발송 통계에도 들어가지 않는다.
+    pub fn mark_backfill(&self, source_key: &str, notice_id: &str) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE notices SET notified = 1 WHERE source_key = ?1 AND notice_id = ?2 AND notified = 0",
+            params![source_key, notice_id],
+        )?;
+        Ok(())
+    }
+
+    /// 공지 분류: `/recat`으로 고친 적 있는 제목(같은 `content_hash`)이면 그 분류,
+    /// 아니면 게시판 분류/제목 키워드 규칙.
+    pub fn classify(&self, raw_category: Option<&str>, title: &str) -> anyhow::Result<Category> {
+        let corrected: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT category FROM category_overrides WHERE content_hash = ?1",
+                params![content_hash(title)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(match corrected {
+            Some(tag) => Category::from_str_tag(&tag),
+            None => Category::classify_notice(raw_category, title),
+        })
+    }
+
+    /// URL로 공지 조회 (같은 URL이 여럿이면 가장 최근 행). 표시 이름은 소스 키.
+    pub fn get_notice_by_url(&self, url: &str) -> anyhow::Result<Option<Notice>> {
+        let notice = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {cols} FROM notices n WHERE url = ?1 ORDER BY id DESC LIMIT 1",
+                    cols = NOTICE_COLUMNS
+                ),
+                params![url],
+                |row| notice_from_row(row, 0),
+            )
+            .optional()?;
+        Ok(notice)
+    }
+
+    /// URL로 찾은 공지의 분류를 고치고, 같은 제목이 다시 올라오면 쓰도록 교정 기록을 남긴다.
+    /// 반환: 고친 공지 제목. 그런 URL의 공지가 없으면 `None`.
+    pub fn set_category_override(&self, url: &str, tag: &str, set_by: i64) -> anyhow::Result<Option<String>> {
+        let Some(Notice { id, title, .. }) = self.get_notice_by_url(url)? else {
+            return Ok(None);
+        };
+        self.execute(
+            "INSERT INTO category_overrides (content_hash, category, title, set_by, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(content_hash) DO UPDATE SET
+               category = ?2, title = ?3, set_by = ?4, created_at = ?5",
+            params![content_hash(&title), tag, title, set_by, now_sqlite()],
+        )?;
+        self.execute("UPDATE notices SET category = ?1 WHERE id = ?2", params![tag, id])?;
+        Ok(Some(title))
+    }
+
+    /// DM을 끈 동안(`bot.enable_dm = false`) 처리된 공지를 DM 대상에서 뺀다.
+    /// 나중에 DM을 다시 켜도 그 사이 공지가 한꺼번에 나가지 않는다. `lookback`은 DM 엔진이
+    /// 돌아보는 범위(`bot.dm_lookback_hours`)와 같아야 한다. 반환: 표시한 수.
+    pub fn skip_pending_dms(&self, lookback: Duration) -> anyhow::Result<usize> {
+        let n = self.execute(
+            "UPDATE notices SET dm_skipped = 1
+             WHERE notified = 1 AND notified_at IS NOT NULL AND COALESCE(dm_skipped, 0) = 0
+               AND crawled_at >= ?1",
+            params![kst::sqlite_ago(lookback)],
+        )?;
+        Ok(n)
+    }
+
+    /// WAL 내용을 본 DB 파일에 반영하고 WAL을 비운다 (종료 직전 호출).
+    pub fn checkpoint(&self) -> anyhow::Result<()> {
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    /// Update crawl state after successful crawl. 실패 카운트와 자동 비활성화를 풀고,
+    /// 직전까지 쌓여 있던 연속 실패 횟수를 반환한다 (복구 알림 판단용).
+    pub fn update_crawl_state(&self, source_key: &str, last_id: Option<&str>) -> anyhow::Result<u32> {
+        let prev_errors = self.get_error_count(source_key)?;
+        let now = now_sqlite();
+        self.execute(
+            "INSERT INTO crawl_state (source_key, last_crawled, last_notice_id, error_count, last_success)
+             VALUES (?1, ?2, ?3, 0, ?2)
+             ON CONFLICT(source_key) DO UPDATE SET
+               last_crawled = ?2,
+               last_notice_id = COALESCE(?3, last_notice_id),
+               error_count = 0,
+               disabled_until = NULL,
+               last_success = ?2",
+            params![source_key, now, last_id],
+        )?;
+        Ok(prev_errors)
+    }
+
+    /// 목록이 0건이었는지 기록. 반환: 연속 0건 횟수 (0건이 아니면 0으로 초기화).
+    /// 1이면 이번에 처음 비었다는 뜻이라 그때만 알린다.
+    pub fn record_empty_parse(&self, source_key: &str, empty: bool) -> anyhow::Result<u32> {
+        self.execute(
+            "INSERT INTO crawl_state (source_key, empty_streak) VALUES (?1, ?2)
+             ON CONFLICT(source_key) DO UPDATE SET
+               empty_streak = CASE WHEN ?2 THEN COALESCE(empty_streak, 0) + 1 ELSE 0 END",
+            params![source_key, empty as i64],
+        )?;
+        let streak: Option<u32> = self.conn.query_row(
+            "SELECT empty_streak FROM crawl_state WHERE source_key = ?1",
+            params![source_key],
+            |row| row.get(0),
+        )?;
+        Ok(streak.unwrap_or(0))
+    }
+
+    /// 이번 목록 수집 소요 시간을 EMA에 반영. 반환: 반영 전 EMA (첫 측정이면 `None`).
+    pub fn record_crawl_duration(&self, source_key: &str, duration_ms: f64) -> anyhow::Result<Option<f64>> {
+        let prev: Option<f64> = self
+            .conn
+            .query_row(
+                "SELECT avg_duration_ms FROM crawl_state WHERE source_key = ?1",
+                params![source_key],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        self.execute(
+            "INSERT INTO crawl_state (source_key, avg_duration_ms) VALUES (?1, ?2)
+             ON CONFLICT(source_key) DO UPDATE SET avg_duration_ms = ?2",
+            params![source_key, duration_ema(prev, duration_ms)],
+        )?;
+        Ok(prev)
+    }
+
+    /// 지난 크롤에서 저장한 커서 (목록 맨 위 일반 공지 id).
+    pub fn get_last_notice_id(&self, source_key: &str) -> anyhow::Result<Option<String>> {
+        let id = self
+            .conn
+            .query_row(
+                "SELECT last_notice_id FROM crawl_state WHERE source_key = ?1",
+                params![source_key],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(id)
+    }
+
+    /// 현재 연속 실패 횟수 (기록 없으면 0).
+    pub fn get_error_count(&self, source_key: &str) -> anyhow::Result<u32> {
+        let count: Option<u32> = self
+            .conn
+            .query_row(
+                "SELECT error_count FROM crawl_state WHERE source_key = ?1",
+                params![source_key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(count.unwrap_or(0))
+    }
+
+    /// 연속 실패가 `threshold`에 도달했으면 쿨다운을 걸고 재시도 시각을 반환.
+    pub fn apply_error_cooldown(
+        &self,
+        source_key: &str,
+        err_count: u32,
+        threshold: u32,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(cooldown) = error_cooldown(err_count, threshold) else {
+            return Ok(None);
+        };
+        let until = kst::to_sqlite(chrono::Utc::now() + cooldown);
+        self.execute(
+            "UPDATE crawl_state SET disabled_until = ?2 WHERE source_key = ?1",
+            params![source_key, until],
+        )?;
+        Ok(Some(until))
+    }
+
+    /// 쿨다운 중이면 재시도 시각을 반환 (이번 사이클은 건너뛴다).
+    pub fn cooldown_until(&self, source_key: &str) -> anyhow::Result<Option<String>> {
+        let until: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT disabled_until FROM crawl_state
+                 WHERE source_key = ?1 AND disabled_until > ?2",
+                params![source_key, now_sqlite()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(until)
+    }
+
+    /// Increment error count, remember the error message and return the new count.
+    pub fn increment_error(&self, source_key: &str, error: &str) -> anyhow::Result<u32> {
+        let now = now_sqlite();
+        self.execute(
+            "INSERT INTO crawl_state (source_key, last_crawled, error_count, last_error)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(source_key) DO UPDATE SET
+               last_crawled = ?2,
+               error_count = error_count + 1,
+               last_error = ?3",
+            params![source_key, now, error],
+        )?;
+
+        let count: u32 = self.conn.query_row(
+            "SELECT error_count FROM crawl_state WHERE source_key = ?1",
+            params![source_key],
+            |row| row.get(0),
+        )?;
+
+        Ok(count)
+    }
+
+    /// Reset error count for a source (used in tests and Phase 2).
+    #[allow(dead_code)]
+    pub fn reset_error(&self, source_key: &str) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE crawl_state SET error_count = 0 WHERE source_key = ?1",
+            params![source_key],
+        )?;
+        Ok(())
+    }
+
+    // ── Phase 2: 구독 / DM 관련 메서드 ─────────────────────────────
+
+    /// 사용자 등록 (첫 /start 시 호출). 이미 있으면 활성화만 갱신.
+    pub fn register_user(
+        &self,
+        telegram_id: i64,
+        username: Option<&str>,
+        first_name: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.execute(
+            "INSERT INTO users (telegram_id, username, first_name, registered)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(telegram_id) DO UPDATE SET
+               username = COALESCE(?2, username),
+               first_name = COALESCE(?3, first_name),
+               is_active = 1",
+            params![telegram_id, username, first_name, now_sqlite()],
+        )?;
+        Ok(())
+    }
+
+    /// 사용자 선호 언어 설정 (`/lang`). `None`이면 해제.
+    pub fn set_user_lang(&self, telegram_id: i64, lang: Option<&str>) -> anyhow::Result<()> {
+        self.execute(
+            "UPDATE users SET lang = ?2 WHERE telegram_id = ?1",
+            params![telegram_id, lang],
+        )?;
+        Ok(())
+    }
+
+    /// 사용자 선호 언어.
+    pub fn get_user_lang(&self, telegram_id: i64) -> anyhow::Result<Option<String>> {
+        let lang = self
+            .conn
+            .query_row(
+                "SELECT lang FROM users WHERE telegram_id = ?1",
+                params![telegram_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(lang)
+    }
+
+    /// 선호 언어를 정한 사용자 전체 (DM 정렬용).
+    pub fn get_user_langs(&self) -> anyhow::Result<std::collections::HashMap<i64, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT telegram_id, lang FROM users WHERE lang IS NOT NULL")?;
+        let langs = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(langs)
+    }
+
+    /// 키워드 구독 추가. 이미 있으면 무시.
+    pub fn add_keyword_sub(&self, telegram_id: i64, keyword: &str) -> anyhow::Result<bool> {
+        let affected = self.execute(
+            "INSERT OR IGNORE INTO keyword_subs (telegram_id, keyword, created_at) VALUES (?1, ?2, ?3)",
+            params![telegram_id, keyword, now_sqlite()],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 키워드 구독 제거.
+    pub fn remove_keyword_sub(&self, telegram_id: i64, keyword: &str) -> anyhow::Result<bool> {
+        let affected = self.execute(
+            "DELETE FROM keyword_subs WHERE telegram_id = ?1 AND keyword = ?2",
+            params![telegram_id, keyword],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 소스(학과) 구독 추가.
+    pub fn add_source_sub(&self, telegram_id: i64, source_key: &str) -> anyhow::Result<bool> {
+        let affected = self.execute(
+            "INSERT OR IGNORE INTO source_subs (telegram_id, source_key, created_at) VALUES (?1, ?2, ?3)",
+            params![telegram_id, source_key, now_sqlite()],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 학과 구독의 카테고리 필터 설정 (태그 목록, 비어 있으면 전체).
+    pub fn set_source_sub_categories(
+        &self,
+        telegram_id: i64,
+        source_key: &str,
+        categories: &[String],
+    ) -> anyhow::Result<()> {
+        let value = if categories.is_empty() {
+            None
+        } else {
+            Some(categories.join(","))
+        };
+        self.execute(
+            "UPDATE source_subs SET categories = ?3 WHERE telegram_id = ?1 AND source_key = ?2",
+            params![telegram_id, source_key, value],
+        )?;
+        Ok(())
+    }
+
+    /// 소스(학과) 구독 제거.
+    pub fn remove_source_sub(&self, telegram_id: i64, source_key: &str) -> anyhow::Result<bool> {
+        let affected = self.execute(
+            "DELETE FROM source_subs WHERE telegram_id = ?1 AND source_key = ?2",
+            params![telegram_id, source_key],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 특정 사용자의 전체 구독 정보 조회.
+    pub fn get_user_subs(&self, telegram_id: i64) -> anyhow::Result<UserSubs> {
+        let mut kw_stmt = self.conn.prepare(
+            "SELECT keyword FROM keyword_subs WHERE telegram_id = ?1 ORDER BY keyword",
+        )?;
+        let keywords: Vec<String> = kw_stmt
+            .query_map(params![telegram_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut src_stmt = self.conn.prepare(
+            "SELECT source_key, categories FROM source_subs WHERE telegram_id = ?1 ORDER BY source_key",
+        )?;
+        let rows: Vec<(String, Option<String>)> = src_stmt
+            .query_map(params![telegram_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut sources = Vec::new();
+        let mut source_categories = std::collections::HashMap::new();
+        for (key, categories) in rows {
+            let filter = split_categories(categories.as_deref());
+            if !filter.is_empty() {
+                source_categories.insert(key.clone(), filter);
+            }
+            sources.push(key);
+        }
+
+        Ok(UserSubs { keywords, sources, source_categories })
+    }
+
+    /// 사용자 행과 구독 행을 가공 없이 (등록 시각 포함). 등록되지 않은 사용자는 `None`.
+    pub fn get_user_debug(&self, telegram_id: i64) -> anyhow::Result<Option<UserDebug>> {
+        let user = self
+            .conn
+            .query_row(
+                "SELECT username, first_name, registered, is_active, lang FROM users WHERE telegram_id = ?1",
+                params![telegram_id],
+                |row| {
+                    Ok(UserDebug {
+                        telegram_id,
+                        username: row.get(0)?,
+                        first_name: row.get(1)?,
+                        registered: row.get(2)?,
+                        is_active: row.get::<_, Option<i64>>(3)?.unwrap_or(0) != 0,
+                        lang: row.get(4)?,
+                        keywords: Vec::new(),
+                        sources: Vec::new(),
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut user) = user else {
+            return Ok(None);
+        };
+
+        let mut kw_stmt = self.conn.prepare(
+            "SELECT keyword, created_at FROM keyword_subs WHERE telegram_id = ?1 ORDER BY created_at, id",
+        )?;
+        user.keywords = kw_stmt
+            .query_map(params![telegram_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut src_stmt = self.conn.prepare(
+            "SELECT source_key, categories, created_at FROM source_subs WHERE telegram_id = ?1 ORDER BY created_at, id",
+        )?;
+        user.sources = src_stmt
+            .query_map(params![telegram_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(user))
+    }
+
+    /// 특정 소스 구독자 중 카테고리 필터가 있는 사용자 → 허용 카테고리 태그.
+    pub fn get_source_sub_filters(
+        &self,
+        source_key: &str,
+    ) -> anyhow::Result<std::collections::HashMap<i64, Vec<String>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT telegram_id, categories FROM source_subs
+             WHERE source_key = ?1 AND categories IS NOT NULL AND categories != ''",
+        )?;
+        let filters = stmt
+            .query_map(params![source_key], |row| {
+                let categories: Option<String> = row.get(1)?;
+                Ok((row.get(0)?, split_categories(categories.as_deref())))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(filters)
+    }
+
+    /// 특정 소스를 구독 중인 활성 사용자 목록.
+    pub fn get_source_subscribers(&self, source_key: &str) -> anyhow::Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.telegram_id FROM source_subs s
              JOIN users u ON u.telegram_id = s.telegram_id
              WHERE s.source_key = ?1 AND u.is_active = 1",
         )?;
@@ -359,170 +1676,1127 @@ impl Database {
         match_type: &str,
         match_value: Option<&str>,
     ) -> anyhow::Result<()> {
-        self.conn.execute(
-            "INSERT OR IGNORE INTO dm_log (notice_id, telegram_id, match_type, match_value)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![notice_db_id, telegram_id, match_type, match_value],
+        self.execute(
+            "INSERT OR IGNORE INTO dm_log (notice_id, telegram_id, match_type, match_value, sent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![notice_db_id, telegram_id, match_type, match_value, now_sqlite()],
         )?;
         Ok(())
     }
 
+    /// 오늘(KST) 개별 DM으로 보낸 수. 일일 요약(`DM_SUMMARY_MATCH`)으로 보낸 것은 빼고 센다.
+    pub fn count_dms_today(&self, telegram_id: i64) -> anyhow::Result<u32> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM dm_log
+             WHERE telegram_id = ?1 AND sent_at >= ?2 AND match_type != ?3",
+            params![telegram_id, format!("{} 00:00:00", kst::today()), DM_SUMMARY_MATCH],
+            |row| row.get(0),
+        )?;
+        Ok(n as u32)
+    }
+
+    /// 일일 한도를 넘은 매칭을 요약 대기열에 넣는다 (`bot.max_dms_per_user_per_day`).
+    pub fn defer_dm(&self, notice_db_id: i64, telegram_id: i64) -> anyhow::Result<()> {
+        self.execute(
+            "INSERT OR IGNORE INTO dm_deferred (notice_id, telegram_id, deferred_at) VALUES (?1, ?2, ?3)",
+            params![notice_db_id, telegram_id, now_sqlite()],
+        )?;
+        Ok(())
+    }
+
+    /// 이미 요약 대기 중인 매칭인지.
+    pub fn is_dm_deferred(&self, notice_db_id: i64, telegram_id: i64) -> anyhow::Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM dm_deferred WHERE notice_id = ?1 AND telegram_id = ?2",
+            params![notice_db_id, telegram_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// `before` 이전에 미뤄 둔 매칭을 사용자별로. 반환: (사용자, 공지 목록) 공지는 미룬 순.
+    pub fn get_deferred_dms(&self, before: &str) -> anyhow::Result<Vec<(i64, Vec<Notice>)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT d.telegram_id, {cols}
+             FROM dm_deferred d JOIN notices n ON n.id = d.notice_id
+             WHERE d.deferred_at < ?1
+             ORDER BY d.telegram_id, d.deferred_at, n.id",
+            cols = NOTICE_COLUMNS
+        ))?;
+        let rows = stmt
+            .query_map(params![before], |row| Ok((row.get::<_, i64>(0)?, notice_from_row(row, 1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut grouped: Vec<(i64, Vec<Notice>)> = Vec::new();
+        for (telegram_id, notice) in rows {
+            match grouped.last_mut() {
+                Some((id, list)) if *id == telegram_id => list.push(notice),
+                _ => grouped.push((telegram_id, vec![notice])),
+            }
+        }
+        Ok(grouped)
+    }
+
+    /// 요약으로 보낸 매칭: 대기열에서 빼고 발송 기록(`DM_SUMMARY_MATCH`)을 남긴다.
+    pub fn complete_deferred_dms(&self, telegram_id: i64, notice_db_ids: &[i64]) -> anyhow::Result<()> {
+        self.transaction(|db| {
+            for id in notice_db_ids {
+                db.execute(
+                    "DELETE FROM dm_deferred WHERE notice_id = ?1 AND telegram_id = ?2",
+                    params![id, telegram_id],
+                )?;
+                db.execute(
+                    "INSERT OR IGNORE INTO dm_log (notice_id, telegram_id, match_type, sent_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, telegram_id, DM_SUMMARY_MATCH, now_sqlite()],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// DM의 "확인" 버튼: 사용자가 공지를 읽음 처리. 반환: 새로 기록됐으면 true.
+    pub fn dismiss_notice(&self, notice_db_id: i64, telegram_id: i64) -> anyhow::Result<bool> {
+        let n = self.execute(
+            "INSERT OR IGNORE INTO dm_dismissed (notice_id, telegram_id, dismissed_at)
+             VALUES (?1, ?2, ?3)",
+            params![notice_db_id, telegram_id, now_sqlite()],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 사용자가 이미 확인(읽음) 처리한 공지인지.
+    pub fn is_dismissed(&self, notice_db_id: i64, telegram_id: i64) -> anyhow::Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM dm_dismissed WHERE notice_id = ?1 AND telegram_id = ?2",
+            params![notice_db_id, telegram_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 사용자의 키워드/학과 구독을 한 트랜잭션으로 모두 삭제. 반환: 삭제된 구독 수.
+    pub fn clear_user_subs(&self, telegram_id: i64) -> anyhow::Result<usize> {
+        self.transaction(|db| {
+            let keywords = db.execute(
+                "DELETE FROM keyword_subs WHERE telegram_id = ?1",
+                params![telegram_id],
+            )?;
+            let sources = db.execute(
+                "DELETE FROM source_subs WHERE telegram_id = ?1",
+                params![telegram_id],
+            )?;
+            Ok(keywords + sources)
+        })
+    }
+
+    /// 사용자 피드백 저장. 반환: 피드백 ID.
+    pub fn add_feedback(
+        &self,
+        telegram_id: i64,
+        username: Option<&str>,
+        message: &str,
+        forwarded: bool,
+    ) -> anyhow::Result<i64> {
+        self.execute(
+            "INSERT INTO feedback (telegram_id, username, message, forwarded, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![telegram_id, username, message, forwarded as i64, now_sqlite()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 오늘(KST) 사용자가 보낸 피드백 수 (`/feedback` 하루 제한용).
+    pub fn count_feedback_today(&self, telegram_id: i64) -> anyhow::Result<u32> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM feedback WHERE telegram_id = ?1 AND created_at >= ?2",
+            params![telegram_id, kst::today().to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(n as u32)
+    }
+
+    /// 활성 사용자 ID 목록 (`/broadcast` 대상).
+    pub fn get_active_user_ids(&self) -> anyhow::Result<Vec<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT telegram_id FROM users WHERE is_active = 1 ORDER BY telegram_id")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
     /// 사용자 비활성화 (봇 차단 등).
-    #[allow(dead_code)]
     pub fn deactivate_user(&self, telegram_id: i64) -> anyhow::Result<()> {
-        self.conn.execute(
+        self.execute(
             "UPDATE users SET is_active = 0 WHERE telegram_id = ?1",
             params![telegram_id],
         )?;
         Ok(())
     }
 
-    /// 마감일이 있는 최근 공지 조회 (Phase 3 알림용).
-    #[allow(dead_code)]
-    pub fn get_deadline_notices(&self, limit: usize) -> anyhow::Result<Vec<Notice>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, source_key, notice_id, title, url, author, category, published
-             FROM notices
-             WHERE deadline IS NOT NULL AND deadline >= date('now')
+    /// 오늘 이후 마감인 공지를 마감 임박순으로 조회. 반환: (공지, 마감 문자열).
+    /// `telegram_id`를 주면 그 사용자가 확인 처리한 공지는 뺀다 (`/deadlines`).
+    pub fn get_deadline_notices(
+        &self,
+        limit: usize,
+        telegram_id: Option<i64>,
+    ) -> anyhow::Result<Vec<(Notice, String)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}, deadline
+             FROM notices n
+             WHERE deadline IS NOT NULL AND deadline >= ?2
+               AND (?3 IS NULL OR id NOT IN
+                    (SELECT notice_id FROM dm_dismissed WHERE telegram_id = ?3))
              ORDER BY deadline ASC
              LIMIT ?1",
-        )?;
+            cols = NOTICE_COLUMNS
+        ))?;
         let notices = stmt
-            .query_map(params![limit as i64], |row| {
-                let source_key: String = row.get(1)?;
-                Ok(Notice {
-                    id: row.get(0)?,
-                    source_key: source_key.clone(),
-                    notice_id: row.get(2)?,
-                    title: row.get(3)?,
-                    url: row.get(4)?,
-                    author: row.get(5)?,
-                    category: row.get::<_, Option<String>>(6)?
-                        .unwrap_or_else(|| "general".into()),
-                    published: row.get(7)?,
-                    source_display_name: source_key,
-                })
+            .query_map(params![limit as i64, kst::today().to_string(), telegram_id], |row| {
+                Ok((notice_from_row(row, 0)?, row.get(NOTICE_COLUMN_COUNT)?))
             })?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(notices)
     }
 
+    /// 특정 소스의 최신 공지 조회 (`/recent`용). 게시일 최신순.
+    pub fn get_latest_by_source(&self, source_key: &str, limit: usize) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE source_key = ?1
+             ORDER BY {sort} DESC, crawled_at DESC, id DESC
+             LIMIT ?2",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![source_key, limit as i64], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
+    /// 카테고리별 최신 공지 (`/top`용). 전체 소스 대상, 게시일 최신순.
+    pub fn get_by_category(&self, tag: &str, limit: usize) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE COALESCE(category, 'general') = ?1
+             ORDER BY {sort} DESC, crawled_at DESC, id DESC
+             LIMIT ?2",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![tag, limit as i64], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
+    /// 아카이브에서 임의의 공지 하나 (`/random`). `category`가 있으면 그 분류 안에서만.
+    pub fn get_random(&self, category: Option<&str>) -> anyhow::Result<Option<Notice>> {
+        let notice = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {cols}
+                     FROM notices n
+                     WHERE ?1 IS NULL OR COALESCE(category, 'general') = ?1
+                     ORDER BY RANDOM() LIMIT 1",
+                    cols = NOTICE_COLUMNS
+                ),
+                params![category],
+                |row| notice_from_row(row, 0),
+            )
+            .optional()?;
+        Ok(notice)
+    }
+
+    /// 전체 최신 공지 페이지 조회 (`/latest`용).
+    pub fn get_latest(&self, limit: usize, offset: usize) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             ORDER BY {sort} DESC, crawled_at DESC, id DESC
+             LIMIT ?1 OFFSET ?2",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![limit as i64, offset as i64], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
+    /// 제목에 검색어가 포함된 공지 페이지 조회 (`/search`용). 최신순.
+    pub fn search_notices(&self, query: &str, limit: usize, offset: usize) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE title LIKE '%' || ?1 || '%'
+             ORDER BY {sort} DESC, crawled_at DESC, id DESC
+             LIMIT ?2 OFFSET ?3",
+            cols = NOTICE_COLUMNS,
+            sort = SORT_TIME_SQL
+        ))?;
+        let notices = stmt
+            .query_map(params![query, limit as i64, offset as i64], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+
     /// 공지에 마감일 설정.
     pub fn set_deadline(&self, notice_db_id: i64, deadline: &str) -> anyhow::Result<()> {
-        self.conn.execute(
+        self.execute(
             "UPDATE notices SET deadline = ?1 WHERE id = ?2",
             params![deadline, notice_db_id],
         )?;
         Ok(())
     }
 
-    /// 크롤 상태 통계 조회.
-    pub fn get_crawl_stats(&self) -> anyhow::Result<Vec<CrawlStat>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT source_key, last_crawled, error_count FROM crawl_state ORDER BY source_key",
-        )?;
-        let stats = stmt
-            .query_map([], |row| {
-                Ok(CrawlStat {
-                    source_key: row.get(0)?,
-                    last_crawled: row.get(1)?,
-                    error_count: row.get(2)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-        Ok(stats)
+    /// 활성 사용자 수.
+    pub fn get_user_count(&self) -> anyhow::Result<u64> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM users WHERE is_active = 1", [], |row| row.get(0))?;
+        Ok(n as u64)
+    }
+
+    /// 비활성 사용자 수 (봇 차단으로 자동 비활성화된 사용자 포함).
+    pub fn get_inactive_user_count(&self) -> anyhow::Result<u64> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM users WHERE is_active = 0", [], |row| row.get(0))?;
+        Ok(n as u64)
+    }
+
+    /// 전체 키워드 구독 수 (활성 사용자만).
+    pub fn get_keyword_sub_count(&self) -> anyhow::Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM keyword_subs k
+             JOIN users u ON u.telegram_id = k.telegram_id
+             WHERE u.is_active = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    /// 전체 학과 구독 수 (활성 사용자만).
+    pub fn get_source_sub_count(&self) -> anyhow::Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM source_subs s
+             JOIN users u ON u.telegram_id = s.telegram_id
+             WHERE u.is_active = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    /// 최근 `hours`시간 동안 채널로 발송된 공지 수.
+    pub fn get_sent_count_since(&self, hours: u32) -> anyhow::Result<u64> {
+        let n: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notices
+             WHERE notified_at IS NOT NULL AND notified_at >= ?1",
+            params![kst::sqlite_ago(Duration::hours(hours as i64))],
+            |row| row.get(0),
+        )?;
+        Ok(n as u64)
+    }
+
+    /// 소스별 저장된 공지 수 + 마지막 성공 크롤 시각. 크롤 기록이나 공지가 하나라도
+    /// 있는 소스만 나온다.
+    pub fn get_sources_summary(&self) -> anyhow::Result<Vec<SourceSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT k.source_key, COUNT(n.id), c.last_success
+             FROM (SELECT source_key FROM crawl_state
+                   UNION SELECT source_key FROM notices) k
+             LEFT JOIN crawl_state c ON c.source_key = k.source_key
+             LEFT JOIN notices n ON n.source_key = k.source_key
+             GROUP BY k.source_key
+             ORDER BY k.source_key",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(SourceSummary {
+                    source_key: row.get(0)?,
+                    notice_count: row.get::<_, i64>(1)? as u64,
+                    last_success: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// 소스의 최근 `weeks`주 주별 수집 공지 수 (`/stats`용). 오래된 주 → 이번 주 순.
+    /// 주 경계는 지금 시각 기준 7일 단위 (0 = 최근 7일).
+    pub fn weekly_counts(&self, source_key: &str, weeks: u32) -> anyhow::Result<Vec<u32>> {
+        let now = now_sqlite();
+        let mut stmt = self.conn.prepare(
+            "SELECT CAST((julianday(?2) - julianday(crawled_at)) / 7 AS INTEGER) AS wk, COUNT(*)
+             FROM notices
+             WHERE source_key = ?1 AND crawled_at >= ?3 AND crawled_at <= ?2
+             GROUP BY wk",
+        )?;
+        let since = kst::sqlite_ago(Duration::weeks(weeks as i64));
+        let mut counts = vec![0u32; weeks as usize];
+        let rows = stmt.query_map(params![source_key, now, since], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, u32>(1)?))
+        })?;
+        for row in rows {
+            let (wk, n) = row?;
+            if let Some(slot) = usize::try_from(wk).ok().filter(|w| *w < counts.len()) {
+                let idx = counts.len() - 1 - slot;
+                counts[idx] += n;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// `since` 이후 활동 집계 (주간 리포트용). 연속 실패가 쌓였거나 그 기간 동안
+    /// 한 번도 성공하지 못한 소스를 실패 목록에 넣는다.
+    pub fn get_weekly_stats(&self, since: &str) -> anyhow::Result<WeeklyStats> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source_key, COUNT(*) FROM notices
+             WHERE crawled_at >= ?1
+             GROUP BY source_key
+             ORDER BY COUNT(*) DESC, source_key",
+        )?;
+        let crawled = stmt
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let new_users = self.conn.query_row(
+            "SELECT COUNT(*) FROM users WHERE registered >= ?1",
+            params![since],
+            |row| row.get(0),
+        )?;
+        let dms_sent = self.conn.query_row(
+            "SELECT COUNT(*) FROM dm_log WHERE sent_at >= ?1",
+            params![since],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT source_key, error_count, last_success FROM crawl_state
+             WHERE error_count >= ?2 OR (error_count > 0 AND (last_success IS NULL OR last_success < ?1))
+             ORDER BY error_count DESC, source_key",
+        )?;
+        let failing = stmt
+            .query_map(params![since, PERSISTENT_ERROR_COUNT], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WeeklyStats { crawled, new_users, dms_sent, failing })
+    }
+
+    /// 정기 리포트(`kind`)를 마지막으로 보낸 시각.
+    pub fn get_report_sent(&self, kind: &str) -> anyhow::Result<Option<String>> {
+        let at = self
+            .conn
+            .query_row("SELECT sent_at FROM report_log WHERE kind = ?1", params![kind], |row| row.get(0))
+            .optional()?;
+        Ok(at)
+    }
+
+    /// 정기 리포트 발송 시각 기록.
+    pub fn set_report_sent(&self, kind: &str, at: &str) -> anyhow::Result<()> {
+        self.execute(
+            "INSERT INTO report_log (kind, sent_at) VALUES (?1, ?2)
+             ON CONFLICT(kind) DO UPDATE SET sent_at = ?2",
+            params![kind, at],
+        )?;
+        Ok(())
+    }
+
+    /// 크롤 상태 통계 조회.
+    pub fn get_crawl_stats(&self) -> anyhow::Result<Vec<CrawlStat>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source_key, last_crawled, error_count, disabled_until, last_error, avg_duration_ms
+             FROM crawl_state ORDER BY source_key",
+        )?;
+        let stats = stmt
+            .query_map([], |row| {
+                Ok(CrawlStat {
+                    source_key: row.get(0)?,
+                    last_crawled: row.get(1)?,
+                    error_count: row.get(2)?,
+                    disabled_until: row.get(3)?,
+                    last_error: row.get(4)?,
+                    avg_duration_ms: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(stats)
+    }
+
+    /// DM 대상 공지 조회 (실제로 발송된 최근 `lookback` 이내 공지). 시드/중복 처리로 발송 없이
+    /// `notified=1`이 된 공지는 `notified_at`이 없어 제외된다. 누구에게 이미 보냈는지는
+    /// 기간이 아니라 `dm_log`로 판단하므로, 기간을 넉넉히 잡으면 재시작 공백 뒤에도 빠짐없이 보낸다.
+    pub fn get_recent_for_dm(&self, lookback: Duration, limit: usize) -> anyhow::Result<Vec<Notice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {cols}
+             FROM notices n
+             WHERE notified = 1 AND notified_at IS NOT NULL AND crawled_at >= ?2
+               AND COALESCE(dm_skipped, 0) = 0
+             ORDER BY crawled_at DESC
+             LIMIT ?1",
+            cols = NOTICE_COLUMNS
+        ))?;
+        let notices = stmt
+            .query_map(params![limit as i64, kst::sqlite_ago(lookback)], |row| notice_from_row(row, 0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notices)
+    }
+}
+
+/// DB가 잠겨 실패한 쓰기를 다시 시도하는 횟수.
+const BUSY_RETRIES: u32 = 3;
+
+/// 재시도 전 대기 (시도마다 두 배). 모두 합쳐 140ms.
+const BUSY_BACKOFF_MS: u64 = 20;
+
+/// 잠금 충돌(`SQLITE_BUSY`, `SQLITE_LOCKED`)로 실패한 작업만 몇 번 더 시도한다.
+/// 다른 에러나 재시도를 다 쓴 경우는 그대로 반환.
+///
+/// 대기는 `std::thread::sleep`이라 봇 핸들러에서 부르면 그동안 tokio 워커가 멈춘다.
+/// `busy_timeout`(5초)도 이미 같은 식으로 막으므로 백오프는 짧게 둔다.
+fn retry_busy<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+                    && attempt < BUSY_RETRIES =>
+            {
+                let wait = BUSY_BACKOFF_MS << attempt;
+                attempt += 1;
+                tracing::warn!(attempt, wait_ms = wait, "Database is locked; retrying write");
+                std::thread::sleep(std::time::Duration::from_millis(wait));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 스키마 버전 (`PRAGMA user_version`). 1: 저장 시각을 UTC에서 KST로 옮김.
+const SCHEMA_VERSION: i64 = 1;
+
+/// 저장 시각 컬럼 (테이블, 컬럼).
+const TIMESTAMP_COLUMNS: &[(&str, &str)] = &[
+    ("notices", "crawled_at"),
+    ("notices", "notified_at"),
+    ("notices", "channel_posted_at"),
+    ("notices", "deleted_at"),
+    ("crawl_state", "last_crawled"),
+    ("crawl_state", "disabled_until"),
+    ("crawl_state", "last_success"),
+    ("users", "registered"),
+    ("keyword_subs", "created_at"),
+    ("source_subs", "created_at"),
+    ("dm_log", "sent_at"),
+    ("dm_deferred", "deferred_at"),
+    ("dm_dismissed", "dismissed_at"),
+    ("feedback", "created_at"),
+    ("report_log", "sent_at"),
+    ("category_overrides", "created_at"),
+    ("notify_queue", "next_retry_at"),
+];
+
+/// 버전별 한 번만 도는 마이그레이션. 새로 만든 DB는 옮길 행이 없으니 버전만 올라간다.
+fn migrate(conn: &Connection) -> anyhow::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+    let tx = conn.unchecked_transaction()?;
+    if version < 1 {
+        // KST 전환 전에는 UTC로 저장했다. 그대로 두면 최근 N일/재시도/쿨다운 비교가
+        // 9시간 어긋나므로 +9시간 옮긴다. 시각으로 해석되지 않는 값은 그대로.
+        for (table, column) in TIMESTAMP_COLUMNS {
+            tx.execute(
+                &format!(
+                    "UPDATE {table} SET {column} = COALESCE(datetime({column}, '+9 hours'), {column})
+                     WHERE {column} IS NOT NULL"
+                ),
+                [],
+            )?;
+        }
+    }
+    tx.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// `dedup_hash` 컬럼 추가 전에 저장된 공지의 해시를 채운다 (해시는 SQL로 계산할 수 없다).
+fn backfill_dedup_hash(conn: &Connection) -> anyhow::Result<()> {
+    let rows = conn
+        .prepare("SELECT id, title, url FROM notices WHERE dedup_hash IS NULL")?
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let tx = conn.unchecked_transaction()?;
+    for (id, title, url) in rows {
+        tx.execute("UPDATE notices SET dedup_hash = ?2 WHERE id = ?1", params![id, dedup_hash(&title, &url)])?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// `CREATE TABLE IF NOT EXISTS`는 기존 테이블에 컬럼을 추가하지 않으므로
+/// 새 컬럼은 여기서 `ALTER TABLE`로 보강한다.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::parser::RawNotice;
+
+    /// 테스트용 목록 항목 (다른 모듈 테스트도 같이 쓴다).
+    pub(crate) fn make_notice(id: &str, title: &str) -> RawNotice {
+        RawNotice {
+            notice_id: id.to_string(),
+            title: title.to_string(),
+            url: format!("https://example.com/{}", id),
+            author: Some("테스트".into()),
+            date: Some("2026-02-01".into()),
+            original_date: Some("2026.02.01".into()),
+            category: None,
+            is_pinned: false,
+            views: None,
+        }
+    }
+
+    #[test]
+    fn test_category_override() {
+        let db = Database::init(":memory:").unwrap();
+        let title = "2026 캠퍼스 투어 가이드 안내";
+        assert_eq!(db.classify(None, title).unwrap(), Category::General);
+        db.insert_if_new("biz", &make_notice("1", title), "경영", false).unwrap();
+
+        assert_eq!(db.set_category_override("https://example.com/404", "event", 9).unwrap(), None);
+        let fixed = db.set_category_override("https://example.com/1", "event", 9).unwrap();
+        assert_eq!(fixed.as_deref(), Some(title));
+        assert_eq!(db.get_by_category("event", 10).unwrap().len(), 1);
+
+        // 같은 제목(공백/기호 차이 무시)은 다음부터 고친 분류로
+        assert_eq!(db.classify(None, title).unwrap(), Category::Event);
+        assert_eq!(db.classify(Some("일반"), "2026 캠퍼스 투어 가이드 안내!").unwrap(), Category::Event);
+        db.insert_if_new("edu", &make_notice("2", title), "사범", false).unwrap();
+        assert_eq!(db.get_by_category("event", 10).unwrap().len(), 2);
+        // 다른 제목은 그대로
+        assert_eq!(db.classify(None, "도서관 휴관 안내").unwrap(), Category::General);
+    }
+
+    #[test]
+    fn test_retry_busy() {
+        let busy = |code| rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(code), None);
+
+        // 한 번 잠겨 있다가 풀리면 성공
+        let mut calls = 0;
+        let result = retry_busy(|| {
+            calls += 1;
+            if calls == 1 {
+                Err(busy(rusqlite::ffi::SQLITE_BUSY))
+            } else {
+                Ok(7)
+            }
+        });
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(calls, 2);
+
+        // 계속 잠겨 있으면 재시도 횟수만큼 시도 후 에러
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = retry_busy(|| {
+            calls += 1;
+            Err(busy(rusqlite::ffi::SQLITE_LOCKED))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, BUSY_RETRIES + 1);
+
+        // 잠금이 아닌 에러는 바로 반환
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = retry_busy(|| {
+            calls += 1;
+            Err(busy(rusqlite::ffi::SQLITE_CONSTRAINT))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_insert_and_dedup() {
+        let db = Database::init(":memory:").unwrap();
+        let n = make_notice("123", "테스트 공지");
+
+        let first = db.insert_if_new("test", &n, "테스트 소스", false).unwrap();
+        assert!(first, "First insert should be new");
+
+        let second = db.insert_if_new("test", &n, "테스트 소스", false).unwrap();
+        assert!(!second, "Duplicate insert should be ignored");
+    }
+
+    #[test]
+    fn test_source_category_wins_over_title() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::from([
+            ("test".to_string(), "테스트 소스".to_string()),
+        ]);
+
+        // 제목은 학사 키워드지만 게시판 분류는 장학
+        let mut n = make_notice("1", "2026-1학기 수강신청 관련 장학 안내");
+        n.category = Some("장학".into());
+        db.insert_if_new("test", &n, "테스트 소스", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "수강신청 안내"), "테스트 소스", false).unwrap();
+
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        let cat = |id: &str| pending.iter().find(|p| p.notice_id == id).unwrap().category.clone();
+        assert_eq!(cat("1"), "scholarship");
+        assert_eq!(cat("2"), "academic");
+    }
+
+    #[test]
+    fn test_clear_user_subs() {
+        let db = Database::init(":memory:").unwrap();
+        db.register_user(1, None, None).unwrap();
+        db.register_user(2, None, None).unwrap();
+        db.add_keyword_sub(1, "장학금").unwrap();
+        db.add_keyword_sub(1, "수강").unwrap();
+        db.add_source_sub(1, "biz").unwrap();
+        db.add_keyword_sub(2, "장학금").unwrap();
+        db.add_source_sub(2, "biz").unwrap();
+
+        assert_eq!(db.clear_user_subs(1).unwrap(), 3);
+        let mine = db.get_user_subs(1).unwrap();
+        assert!(mine.keywords.is_empty() && mine.sources.is_empty());
+
+        let other = db.get_user_subs(2).unwrap();
+        assert_eq!(other.keywords, vec!["장학금".to_string()]);
+        assert_eq!(other.sources, vec!["biz".to_string()]);
+
+        assert_eq!(db.clear_user_subs(1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_usage_counts() {
+        let db = Database::init(":memory:").unwrap();
+        for id in 1..=3 {
+            db.register_user(id, None, None).unwrap();
+        }
+        db.deactivate_user(3).unwrap();
+        db.add_keyword_sub(1, "장학금").unwrap();
+        db.add_keyword_sub(2, "수강").unwrap();
+        db.add_keyword_sub(3, "채용").unwrap(); // 비활성 사용자
+        db.add_source_sub(1, "biz").unwrap();
+
+        assert_eq!(db.get_user_count().unwrap(), 2);
+        assert_eq!(db.get_keyword_sub_count().unwrap(), 2);
+        assert_eq!(db.get_source_sub_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_sent_count_since() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        for id in ["1", "2", "3"] {
+            db.insert_if_new("test", &make_notice(id, id), "테스트", false).unwrap();
+        }
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        db.mark_notified(pending[0].id).unwrap();
+        db.mark_notified(pending[1].id).unwrap();
+        // 오래전에 발송된 공지는 제외
+        db.conn
+            .execute(
+                "UPDATE notices SET notified_at = datetime('now', '+9 hours', '-2 days') WHERE id = ?1",
+                params![pending[1].id],
+            )
+            .unwrap();
+
+        assert_eq!(db.get_sent_count_since(24).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_pending_and_mark_notified() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::from([
+            ("test".to_string(), "테스트 소스".to_string()),
+        ]);
+
+        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트 소스", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "공지2"), "테스트 소스", false).unwrap();
+
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 2);
+
+        db.mark_notified(pending[0].id).unwrap();
+
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_seed_leaves_nothing_pending() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.insert_if_new("biz", &make_notice("1", "경영 공지1"), "경영", false).unwrap();
+        db.insert_if_new("biz", &make_notice("2", "경영 공지2"), "경영", false).unwrap();
+        db.insert_if_new("edu", &make_notice("1", "교육 공지"), "교육", false).unwrap();
+        db.record_send_failure(1).unwrap();
+
+        assert_eq!(db.mark_all_notified(Some("biz")).unwrap(), 2);
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].source_key, "edu");
+        assert!(db.get_due_retries(10, &display).unwrap().is_empty());
+
+        // 다시 돌려도 바뀌는 게 없고, 전체 시드는 나머지를 처리
+        assert_eq!(db.mark_all_notified(Some("biz")).unwrap(), 0);
+        assert_eq!(db.mark_all_notified(None).unwrap(), 1);
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+
+        // 발송한 적 없으니 DM 대상도, 발송 통계도 아니다
+        assert!(db.get_recent_for_dm(Duration::days(1), 10).unwrap().is_empty());
+        assert_eq!(db.get_sent_count_since(24).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_dm_lookback_survives_outage() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("biz", &make_notice("1", "장학금 신청 안내"), "경영", false).unwrap();
+        db.mark_notified(1).unwrap();
+        // 봇이 30시간 멈춰 있던 사이 올라온 공지
+        db.conn
+            .execute("UPDATE notices SET crawled_at = datetime('now', '+9 hours', '-30 hours')", [])
+            .unwrap();
+        db.log_dm(1, 200, "keyword", Some("장학금")).unwrap();
+
+        assert!(db.get_recent_for_dm(Duration::hours(24), 10).unwrap().is_empty());
+        let recent = db.get_recent_for_dm(Duration::hours(48), 10).unwrap();
+        assert_eq!(recent.iter().map(|n| n.id).collect::<Vec<_>>(), vec![1]);
+        // 발송 여부는 dm_log로만 판단: 받은 사용자는 건너뛰고 못 받은 사용자는 보낸다
+        assert!(db.is_dm_sent(1, 200).unwrap());
+        assert!(!db.is_dm_sent(1, 100).unwrap());
     }
 
-    /// DM 대상 공지 조회 (notified=1이면서 아직 DM 처리 안 된 최근 공지).
-    pub fn get_recent_for_dm(&self, limit: usize) -> anyhow::Result<Vec<Notice>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, source_key, notice_id, title, url, author, category, published
-             FROM notices
-             WHERE notified = 1 AND crawled_at >= datetime('now', '-1 day')
-             ORDER BY crawled_at DESC
-             LIMIT ?1",
-        )?;
-        let notices = stmt
-            .query_map(params![limit as i64], |row| {
-                let source_key: String = row.get(1)?;
-                Ok(Notice {
-                    id: row.get(0)?,
-                    source_key: source_key.clone(),
-                    notice_id: row.get(2)?,
-                    title: row.get(3)?,
-                    url: row.get(4)?,
-                    author: row.get(5)?,
-                    category: row.get::<_, Option<String>>(6)?
-                        .unwrap_or_else(|| "general".into()),
-                    published: row.get(7)?,
-                    source_display_name: source_key,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-        Ok(notices)
+    #[test]
+    fn test_backfill_stored_but_not_pending() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        let old = RawNotice { date: Some("2019-03-04".into()), ..make_notice("7", "2019학년도 공지") };
+        assert!(db.insert_if_new("biz", &old, "경영", false).unwrap());
+        db.insert_if_new("biz", &make_notice("8", "새 공지"), "경영", false).unwrap();
+        db.mark_backfill("biz", "7").unwrap();
+
+        let pending: Vec<String> =
+            db.get_pending(10, PostOrder::Latest, &display).unwrap().into_iter().map(|n| n.notice_id).collect();
+        assert_eq!(pending, vec!["8".to_string()]);
+        assert_eq!(db.get_latest_by_source("biz", 10).unwrap().len(), 2);
+        // 발송한 적 없으니 DM 대상도 아니다
+        db.mark_notified(db.get_pending(10, PostOrder::Latest, &display).unwrap()[0].id).unwrap();
+        let dm: Vec<String> = db
+            .get_recent_for_dm(Duration::days(1), 10)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.notice_id)
+            .collect();
+        assert_eq!(dm, vec!["8".to_string()]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::parser::RawNotice;
+    #[test]
+    fn test_disabled_modes_advance_notified() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.insert_if_new("biz", &make_notice("1", "공지1"), "경영", false).unwrap();
+        db.insert_if_new("biz", &make_notice("2", "공지2"), "경영", false).unwrap();
 
-    fn make_notice(id: &str, title: &str) -> RawNotice {
-        RawNotice {
-            notice_id: id.to_string(),
-            title: title.to_string(),
-            url: format!("https://example.com/{}", id),
-            author: Some("테스트".into()),
-            date: Some("2026-02-01".into()),
-            category: None,
-            is_pinned: false,
+        // 채널을 끄면 게시 없이 처리 완료 → 대기열은 비고 DM 대상은 된다
+        for notice in db.get_pending(10, PostOrder::Latest, &display).unwrap() {
+            db.mark_notified(notice.id).unwrap();
         }
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+        let lookback = Duration::hours(48);
+        assert_eq!(db.get_recent_for_dm(lookback, 10).unwrap().len(), 2);
+        // 하루보다 오래됐어도 DM 범위 안이면 건너뛴다
+        db.conn
+            .execute("UPDATE notices SET crawled_at = datetime('now', '+9 hours', '-30 hours') WHERE notice_id = '1'", [])
+            .unwrap();
+
+        // DM을 끄면 그동안 처리된 공지는 나중에 DM을 다시 켜도 보내지 않는다
+        assert_eq!(db.skip_pending_dms(lookback).unwrap(), 2);
+        assert!(db.get_recent_for_dm(lookback, 10).unwrap().is_empty());
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+
+        // 다시 켠 뒤 새 공지는 정상적으로 DM 대상
+        db.insert_if_new("biz", &make_notice("3", "공지3"), "경영", false).unwrap();
+        let id = db.get_pending(10, PostOrder::Latest, &display).unwrap()[0].id;
+        db.mark_notified(id).unwrap();
+        let dm: Vec<i64> = db.get_recent_for_dm(lookback, 10).unwrap().iter().map(|n| n.id).collect();
+        assert_eq!(dm, vec![id]);
+        assert_eq!(db.skip_pending_dms(lookback).unwrap(), 1);
+        assert_eq!(db.skip_pending_dms(lookback).unwrap(), 0);
     }
 
     #[test]
-    fn test_insert_and_dedup() {
+    fn test_channel_message_id_lookup() {
         let db = Database::init(":memory:").unwrap();
-        let n = make_notice("123", "테스트 공지");
+        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "공지2"), "테스트", false).unwrap();
+
+        // 기록 전에는 수정할 메시지가 없다
+        assert_eq!(db.get_editable_message_id(1).unwrap(), None);
+        db.set_channel_message_id(1, "@cbnu_notice", 501).unwrap();
+        assert_eq!(db.get_editable_message_id(1).unwrap(), Some(501));
+        assert_eq!(db.get_editable_message_id(2).unwrap(), None);
+
+        // 삭제용 조회: 게시 기록이 없거나 없는 공지는 `None`
+        let post = db.get_channel_post(1).unwrap().unwrap();
+        assert_eq!(post.chat.as_deref(), Some("@cbnu_notice"));
+        assert_eq!(post.message_id, 501);
+        assert_eq!(post.title, "공지1");
+        assert!(post.deletable && !post.deleted);
+        assert_eq!(db.get_channel_post(2).unwrap(), None);
+        assert_eq!(db.get_channel_post(99).unwrap(), None);
+
+        // 48시간이 지난 메시지는 수정하지 않고 새로 올린다
+        db.conn
+            .execute(
+                "UPDATE notices SET channel_posted_at = datetime('now', '+9 hours', '-3 days') WHERE id = 1",
+                [],
+            )
+            .unwrap();
+        assert_eq!(db.get_editable_message_id(1).unwrap(), None);
+        assert!(!db.get_channel_post(1).unwrap().unwrap().deletable);
+
+        // 지운 게시물은 다시 수정하지 않는다
+        db.set_channel_message_id(2, "@cbnu_notice", 502).unwrap();
+        db.mark_deleted(2).unwrap();
+        assert!(db.get_channel_post(2).unwrap().unwrap().deleted);
+        assert_eq!(db.get_editable_message_id(2).unwrap(), None);
+    }
 
-        let first = db.insert_if_new("test", &n, "테스트 소스").unwrap();
-        assert!(first, "First insert should be new");
+    #[test]
+    fn test_transaction_takes_write_lock_first() {
+        let path = std::env::temp_dir().join(format!("cbnu_tx_immediate_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        let db = Database::init(&path).unwrap();
+        let other = Connection::open(&path).unwrap();
+        other.busy_timeout(std::time::Duration::ZERO).unwrap();
+
+        // 읽기만 한 시점에도 다른 연결은 쓸 수 없다 (쓰기로 올리다 실패하지 않게)
+        db.transaction(|db| {
+            db.get_last_notice_id("biz")?;
+            assert!(other.execute("INSERT INTO users (telegram_id) VALUES (1)", []).is_err());
+            Ok(())
+        })
+        .unwrap();
+        other.execute("INSERT INTO users (telegram_id) VALUES (1)", []).unwrap();
+        drop((db, other));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
 
-        let second = db.insert_if_new("test", &n, "테스트 소스").unwrap();
-        assert!(!second, "Duplicate insert should be ignored");
+    #[test]
+    fn test_open_read_only() {
+        let path = std::env::temp_dir().join(format!("cbnu_read_only_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        // 없는 파일은 만들지 않고 실패
+        assert!(Database::open_read_only(&path).is_err());
+        assert!(!std::path::Path::new(&path).exists());
+
+        Database::init(&path).unwrap().register_user(1, None, None).unwrap();
+        let db = Database::open_read_only(&path).unwrap();
+        assert_eq!(db.get_user_count().unwrap(), 1);
+        assert!(db.register_user(2, None, None).is_err());
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
     }
 
     #[test]
-    fn test_pending_and_mark_notified() {
+    fn test_insert_batch_rolls_back_on_failure() {
         let db = Database::init(":memory:").unwrap();
-        let display = std::collections::HashMap::from([
-            ("test".to_string(), "테스트 소스".to_string()),
-        ]);
+        let batch = vec![make_notice("1", "공지1"), make_notice("2", "공지2")];
+        assert_eq!(db.insert_batch("test", &batch, "테스트", false).unwrap(), 2);
+        // 다시 넣으면 새 공지 없음
+        assert_eq!(db.insert_batch("test", &batch, "테스트", false).unwrap(), 0);
+
+        // 중간 공지에서 실패하면 앞서 넣은 것까지 되돌린다
+        db.conn
+            .execute_batch(
+                "CREATE TRIGGER fail_insert BEFORE INSERT ON notices WHEN NEW.title = 'boom'
+                 BEGIN SELECT RAISE(ABORT, 'boom'); END;",
+            )
+            .unwrap();
+        let batch = vec![make_notice("3", "공지3"), make_notice("4", "boom"), make_notice("5", "공지5")];
+        assert!(db.insert_batch("test", &batch, "테스트", false).is_err());
+        let count: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM notices", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // 롤백 뒤에도 연결은 정상
+        assert_eq!(db.insert_batch("test", &batch[..1], "테스트", false).unwrap(), 1);
+    }
 
-        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트 소스").unwrap();
-        db.insert_if_new("test", &make_notice("2", "공지2"), "테스트 소스").unwrap();
+    #[test]
+    fn test_notice_and_user_lang() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("intl", &make_notice("1", "Exchange Program Guide"), "국제", false).unwrap();
+        db.insert_if_new("intl", &make_notice("2", "교환학생 안내"), "국제", false).unwrap();
+        db.insert_if_new("biz", &make_notice("1", "Career Fair"), "경영", false).unwrap();
+
+        // 제목으로 추정
+        assert_eq!(db.get_notice_lang(1).unwrap().as_deref(), Some("en"));
+        assert_eq!(db.get_notice_lang(2).unwrap().as_deref(), Some("ko"));
+        // 소스 설정이 우선, 다른 소스는 그대로
+        assert_eq!(db.set_source_lang("intl", "en").unwrap(), 1);
+        assert_eq!(db.get_notice_lang(2).unwrap().as_deref(), Some("en"));
+        assert_eq!(db.get_notice_lang(3).unwrap().as_deref(), Some("en"));
+        assert_eq!(db.get_notice_lang(99).unwrap(), None);
 
-        let pending = db.get_pending(10, &display).unwrap();
-        assert_eq!(pending.len(), 2);
+        db.register_user(100, None, None).unwrap();
+        db.register_user(200, None, None).unwrap();
+        assert_eq!(db.get_user_lang(100).unwrap(), None);
+        db.set_user_lang(100, Some("en")).unwrap();
+        assert_eq!(db.get_user_lang(100).unwrap().as_deref(), Some("en"));
+        assert_eq!(db.get_user_langs().unwrap().len(), 1);
+        db.set_user_lang(100, None).unwrap();
+        assert!(db.get_user_langs().unwrap().is_empty());
+    }
 
-        db.mark_notified(pending[0].id).unwrap();
+    #[test]
+    fn test_normalize_dup_title() {
+        assert_eq!(normalize_dup_title("[재공지] 장학금 신청 안내"), "장학금신청안내");
+        assert_eq!(normalize_dup_title(" [마감연장] (수정) 장학금  신청 안내 "), "장학금신청안내");
+        assert_eq!(normalize_dup_title("【재공지】 Career Fair"), "careerfair");
+        // 가운데 괄호는 남긴다
+        assert_eq!(normalize_dup_title("장학금 [2차] 신청"), "장학금2차신청");
+        // 닫히지 않은 괄호는 그대로
+        assert_eq!(normalize_dup_title("[재공지 장학금"), "재공지장학금");
+        assert_eq!(title_similarity("장학금신청안내", "장학금신청안내"), 1.0);
+        assert!(title_similarity("장학금신청안내", "장학금신청안내2차") > 0.8);
+        assert!(title_similarity("장학금신청안내", "수강신청일정") < 0.5);
+    }
 
-        let pending = db.get_pending(10, &display).unwrap();
-        assert_eq!(pending.len(), 1);
+    #[test]
+    fn test_near_dup_repost_suppressed() {
+        let db = Database::init(":memory:").unwrap().with_near_dup(0.85, 7);
+        assert!(db.insert_if_new("biz", &make_notice("1", "2026학년도 장학금 신청 안내"), "경영", false).unwrap());
+        // 접두사만 붙인 재공지: 저장은 하되 알림 없음
+        assert!(!db.insert_if_new("biz", &make_notice("2", "[재공지] 2026학년도 장학금 신청 안내"), "경영", false).unwrap());
+        let stored: i64 = db.conn.query_row("SELECT COUNT(*) FROM notices", [], |r| r.get(0)).unwrap();
+        assert_eq!(stored, 2);
+        // 다른 소스, 다른 제목은 그대로 알림
+        assert!(db.insert_if_new("math", &make_notice("1", "[재공지] 2026학년도 장학금 신청 안내"), "수학", false).unwrap());
+        assert!(db.insert_if_new("biz", &make_notice("3", "2026학년도 수강신청 일정"), "경영", false).unwrap());
+        let pending = db.get_pending(10, PostOrder::Latest, &std::collections::HashMap::new()).unwrap();
+        assert_eq!(pending.len(), 3);
+
+        // 설정이 없으면 억제하지 않는다
+        let off = Database::init(":memory:").unwrap();
+        off.insert_if_new("biz", &make_notice("1", "장학금 신청 안내"), "경영", false).unwrap();
+        assert!(off.insert_if_new("biz", &make_notice("2", "[재공지] 장학금 신청 안내"), "경영", false).unwrap());
+    }
+
+    #[test]
+    fn test_weekly_stats() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("biz", &make_notice("1", "공지 1"), "경영", false).unwrap();
+        db.insert_if_new("biz", &make_notice("2", "공지 2"), "경영", false).unwrap();
+        db.insert_if_new("math", &make_notice("1", "공지 3"), "수학", false).unwrap();
+        db.insert_if_new("math", &make_notice("2", "지난달 공지"), "수학", false).unwrap();
+        db.conn
+            .execute("UPDATE notices SET crawled_at = '2026-01-01 00:00:00' WHERE title = '지난달 공지'", [])
+            .unwrap();
+        db.register_user(100, None, None).unwrap();
+        db.register_user(200, None, None).unwrap();
+        db.conn
+            .execute("UPDATE users SET registered = '2026-01-01 00:00:00' WHERE telegram_id = 200", [])
+            .unwrap();
+        db.log_dm(1, 100, "keyword", Some("공지")).unwrap();
+        db.log_dm(3, 100, "source", Some("math")).unwrap();
+
+        // physics: 연속 실패 누적, lib: 1회 실패지만 기간 내 성공 없음, math: 1회 실패지만 최근 성공,
+        // music: 한 번도 성공한 적 없는 새 소스
+        db.update_crawl_state("math", None).unwrap();
+        db.increment_error("math", "HTTP 500").unwrap();
+        for _ in 0..3 {
+            db.increment_error("physics", "HTTP 500").unwrap();
+        }
+        db.update_crawl_state("lib", None).unwrap();
+        db.conn
+            .execute("UPDATE crawl_state SET last_success = '2026-01-01 00:00:00' WHERE source_key = 'lib'", [])
+            .unwrap();
+        db.increment_error("lib", "HTTP 500").unwrap();
+        db.increment_error("music", "HTTP 404").unwrap();
+
+        let since = kst::sqlite_ago(Duration::days(7));
+        let stats = db.get_weekly_stats(&since).unwrap();
+        assert_eq!(stats.crawled, vec![("biz".to_string(), 2), ("math".to_string(), 1)]);
+        assert_eq!(stats.new_users, 1);
+        assert_eq!(stats.dms_sent, 2);
+        let failing: Vec<(&str, u32)> = stats.failing.iter().map(|(k, n, _)| (k.as_str(), *n)).collect();
+        assert_eq!(failing, vec![("physics", 3), ("lib", 1), ("music", 1)]);
+
+        assert_eq!(db.get_report_sent("weekly").unwrap(), None);
+        db.set_report_sent("weekly", "2026-03-02 09:00:00").unwrap();
+        db.set_report_sent("weekly", "2026-03-09 09:00:00").unwrap();
+        assert_eq!(db.get_report_sent("weekly").unwrap().as_deref(), Some("2026-03-09 09:00:00"));
     }
 
     #[test]
     fn test_error_count() {
         let db = Database::init(":memory:").unwrap();
-        let c1 = db.increment_error("test").unwrap();
+        let c1 = db.increment_error("test", "HTTP 500").unwrap();
         assert_eq!(c1, 1);
-        let c2 = db.increment_error("test").unwrap();
+        let c2 = db.increment_error("test", "HTTP 500").unwrap();
         assert_eq!(c2, 2);
         db.reset_error("test").unwrap();
-        let c3 = db.increment_error("test").unwrap();
+        let c3 = db.increment_error("test", "HTTP 500").unwrap();
         assert_eq!(c3, 1);
     }
 
+    #[test]
+    fn test_increment_error_keeps_message() {
+        let db = Database::init(":memory:").unwrap();
+        let last_error = |db: &Database| {
+            db.get_crawl_stats().unwrap().into_iter().find(|s| s.source_key == "biz").unwrap().last_error
+        };
+        db.increment_error("biz", "HTTP 503 Service Unavailable").unwrap();
+        assert_eq!(last_error(&db).as_deref(), Some("HTTP 503 Service Unavailable"));
+        // 다음 실패는 메시지를 덮어쓴다
+        db.increment_error("biz", "operation timed out").unwrap();
+        assert_eq!(last_error(&db).as_deref(), Some("operation timed out"));
+        assert_eq!(db.get_crawl_stats().unwrap()[0].error_count, 2);
+    }
+
     #[test]
     fn test_user_registration_and_subs() {
         let db = Database::init(":memory:").unwrap();
@@ -550,6 +2824,496 @@ mod tests {
         assert_eq!(subs.keywords, vec!["장학금"]);
     }
 
+    #[test]
+    fn test_user_debug() {
+        let db = Database::init(":memory:").unwrap();
+        assert_eq!(db.get_user_debug(7).unwrap(), None);
+
+        db.register_user(7, Some("cbnu"), Some("충북")).unwrap();
+        db.add_keyword_sub(7, "채용").unwrap();
+        db.add_keyword_sub(7, "장학금").unwrap();
+        db.add_source_sub(7, "biz").unwrap();
+        db.set_source_sub_categories(7, "biz", &["scholarship".into(), "recruit".into()]).unwrap();
+        db.set_user_lang(7, Some("en")).unwrap();
+        db.conn
+            .execute("UPDATE keyword_subs SET created_at = '2026-03-01 09:00:00' WHERE keyword = '채용'", [])
+            .unwrap();
+
+        let user = db.get_user_debug(7).unwrap().unwrap();
+        assert_eq!(user.username.as_deref(), Some("cbnu"));
+        assert!(user.is_active);
+        assert_eq!(user.lang.as_deref(), Some("en"));
+        // 가공 없이: 등록 순서, 시각, 필터 원문 그대로
+        assert_eq!(user.keywords[0], ("채용".to_string(), "2026-03-01 09:00:00".to_string()));
+        assert_eq!(user.keywords[1].0, "장학금");
+        assert_eq!(user.sources.len(), 1);
+        assert_eq!(user.sources[0].0, "biz");
+        assert_eq!(user.sources[0].1.as_deref(), Some("scholarship,recruit"));
+        assert_eq!(user.sources[0].2.len(), "YYYY-MM-DD HH:MM:SS".len());
+
+        db.deactivate_user(7).unwrap();
+        assert!(!db.get_user_debug(7).unwrap().unwrap().is_active);
+    }
+
+    #[test]
+    fn test_get_random_scoped_by_category() {
+        let db = Database::init(":memory:").unwrap();
+        assert!(db.get_random(None).unwrap().is_none());
+
+        db.insert_if_new("test", &make_notice("1", "2026 국가장학금 신청 안내"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "교내 장학금 지급 결과"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("3", "학위수여식 일정 안내"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("4", "도서관 휴관 안내"), "테스트", false).unwrap();
+
+        for _ in 0..20 {
+            let notice = db.get_random(Some("scholarship")).unwrap().unwrap();
+            assert_eq!(notice.category, "scholarship");
+            assert!(["1", "2"].contains(&notice.notice_id.as_str()));
+        }
+        assert!(db.get_random(Some("recruit")).unwrap().is_none());
+        assert!(db.get_random(None).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_pending_post_order() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        // 지난 사이클에 1, 이번 사이클에 게시판 목록 순서(최신 먼저)대로 4, 3(고정), 2
+        let mut rows = vec![
+            make_notice("1", "지난 사이클 공지"),
+            make_notice("4", "넷째 공지"),
+            make_notice("3", "[필독] 고정 공지"),
+            make_notice("2", "둘째 공지"),
+        ];
+        rows[2].is_pinned = true;
+        rows[1].date = Some("2026-02-04".into());
+        rows[2].date = Some("2026-01-15".into());
+        rows[3].date = Some("2026-02-02".into());
+        for row in &rows {
+            db.insert_if_new("test", row, "테스트", false).unwrap();
+        }
+        db.conn
+            .execute("UPDATE notices SET crawled_at = '2026-02-01 09:00:00' WHERE notice_id = '1'", [])
+            .unwrap();
+        db.conn
+            .execute("UPDATE notices SET crawled_at = '2026-02-05 09:00:00' WHERE notice_id != '1'", [])
+            .unwrap();
+
+        let ids = |order| -> Vec<String> {
+            db.get_pending(10, order, &display).unwrap().into_iter().map(|n| n.notice_id).collect()
+        };
+        // 게시일 기준: 이번 사이클에 발견했어도 1월 게시글(3)은 지난 사이클 공지(1)보다 오래됐다
+        assert_eq!(ids(PostOrder::Latest), vec!["4", "2", "1", "3"]);
+        assert_eq!(ids(PostOrder::Chronological), vec!["3", "1", "2", "4"]);
+        assert_eq!(ids(PostOrder::PinnedFirst), vec!["3", "4", "2", "1"]);
+    }
+
+    #[test]
+    fn test_sort_by_published_or_first_seen() {
+        let db = Database::init(":memory:").unwrap();
+        // (공지 ID, 게시일, 발견 시각)
+        let rows = [
+            ("a", Some("2026-02-01"), "2026-02-10 09:00:00"), // 크롤링이 밀려 늦게 발견
+            ("b", None, "2026-02-05 09:00:00"),               // 게시일 없음
+            ("c", Some("2026-09-01"), "2026-02-03 09:00:00"), // 미래 날짜: 믿지 않음
+            ("d", Some("2026.02.04"), "2026-02-02 09:00:00"), // 정규화 안 된 날짜
+        ];
+        for (id, date, crawled_at) in rows {
+            let mut notice = make_notice(id, &format!("공지 {}", id));
+            notice.date = date.map(String::from);
+            db.insert_if_new("biz", &notice, "경영", false).unwrap();
+            db.conn
+                .execute("UPDATE notices SET crawled_at = ?2 WHERE notice_id = ?1", params![id, crawled_at])
+                .unwrap();
+        }
+        let ids = |notices: Vec<Notice>| notices.into_iter().map(|n| n.notice_id).collect::<Vec<_>>();
+
+        // 발견 순서만 보면 a가 가장 최신이지만, 게시일 기준으로는 가장 오래됐다
+        let by_crawled: Vec<String> = {
+            let mut stmt = db.conn.prepare("SELECT notice_id FROM notices ORDER BY crawled_at DESC").unwrap();
+            stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap()
+        };
+        assert_eq!(by_crawled, ["a", "b", "c", "d"]);
+        assert_eq!(ids(db.get_latest(10, 0).unwrap()), ["b", "c", "d", "a"]);
+        assert_eq!(ids(db.get_latest_by_source("biz", 10).unwrap()), ["b", "c", "d", "a"]);
+        let display = std::collections::HashMap::new();
+        assert_eq!(ids(db.get_pending(10, PostOrder::Latest, &display).unwrap()), ["b", "c", "d", "a"]);
+        assert_eq!(ids(db.get_pending(10, PostOrder::Chronological, &display).unwrap()), ["a", "d", "c", "b"]);
+
+        let a = db.get_notice_by_url("https://example.com/a").unwrap().unwrap();
+        let debug = db.get_notice_debug(a.id).unwrap().unwrap();
+        assert_eq!(debug.published.as_deref(), Some("2026-02-01"));
+        assert_eq!(debug.crawled_at, "2026-02-10 09:00:00");
+        assert_eq!(debug.sort_time, "2026-02-01");
+        let c = db.get_notice_by_url("https://example.com/c").unwrap().unwrap();
+        assert_eq!(db.get_notice_debug(c.id).unwrap().unwrap().sort_time, "2026-02-03 09:00:00");
+        assert!(db.get_notice_debug(999).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_edit_detection() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        let original = make_notice("1", "2026학년도 장학금 신청 안내 (3.10.까지)");
+        db.insert_if_new("test", &original, "테스트", false).unwrap();
+        let id = db.get_pending(10, PostOrder::Latest, &display).unwrap()[0].id;
+        db.mark_notified(id).unwrap();
+
+        // 공백만 바뀐 건 수정 아님
+        let spaced = make_notice("1", "2026학년도  장학금 신청 안내 (3.10.까지) ");
+        assert!(!db.update_if_edited("test", &spaced, true).unwrap());
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+
+        // 날짜가 바뀌면 수정으로 보고 다시 발송 대기
+        let edited = make_notice("1", "2026학년도 장학금 신청 안내 (3.17.까지)");
+        assert!(db.update_if_edited("test", &edited, true).unwrap());
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].edited);
+        assert_eq!(pending[0].title, "2026학년도 장학금 신청 안내 (3.17.까지)");
+
+        // 같은 제목으로 다시 보면 변화 없음
+        assert!(!db.update_if_edited("test", &edited, true).unwrap());
+
+        // notify 꺼짐: 제목만 갱신
+        db.mark_notified(id).unwrap();
+        let again = make_notice("1", "장학금 신청 안내 (연장)");
+        assert!(db.update_if_edited("test", &again, false).unwrap());
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+
+        // 없는 공지는 false
+        assert!(!db.update_if_edited("test", &make_notice("999", "x"), true).unwrap());
+    }
+
+    #[test]
+    fn test_edit_keeps_row_and_dm_log() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.register_user(100, None, None).unwrap();
+        let original = make_notice("1", "2026학년도 장학금 신청 안내 (3.10.까지)");
+        db.insert_if_new("test", &original, "테스트", false).unwrap();
+        let id = db.get_notice_by_url(&original.url).unwrap().unwrap().id;
+        db.mark_notified(id).unwrap();
+        db.log_dm(id, 100, "keyword", Some("장학금")).unwrap();
+
+        // 제목이 수정돼 다시 발송 대기로: 새 행이 아니라 같은 행
+        let edited = make_notice("1", "2026학년도 장학금 신청 안내 (3.17.까지)");
+        assert!(db.update_if_edited("test", &edited, true).unwrap());
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, id);
+        let count: i64 = db.conn.query_row("SELECT COUNT(*) FROM notices", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+
+        // 이미 DM 받은 사용자는 수정본을 다시 받지 않는다
+        assert!(db.is_dm_sent(pending[0].id, 100).unwrap());
+
+        assert!(db.get_notice_by_url("https://example.com/none").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_renumbered_notice_keeps_row() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.register_user(100, None, None).unwrap();
+        let original = make_notice("1", "2026학년도 장학금 신청 안내");
+        assert!(db.insert_if_new("test", &original, "테스트", false).unwrap());
+        let id = db.get_notice_by_url(&original.url).unwrap().unwrap().id;
+        db.mark_notified(id).unwrap();
+        db.log_dm(id, 100, "keyword", Some("장학금")).unwrap();
+
+        // 크롤 순서 그대로: 옛 번호가 목록에서 사라지고 같은 URL이 새 번호로 보이면 새 공지가 아니다
+        let mut renumbered = make_notice("2", "2026학년도 장학금 신청 안내");
+        renumbered.url = original.url.clone();
+        let listing = [renumbered.clone()];
+        assert!(db.renumber_by_url("test", &renumbered, &listing).unwrap());
+        assert!(!db.update_if_edited("test", &renumbered, true).unwrap());
+        assert!(db.get_pending(10, PostOrder::Latest, &display).unwrap().is_empty());
+        let count: i64 = db.conn.query_row("SELECT COUNT(*) FROM notices", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+        let dm_targets = db.get_recent_for_dm(Duration::days(1), 10).unwrap();
+        assert_eq!(dm_targets.len(), 1);
+        assert_eq!(dm_targets[0].id, id);
+        assert_eq!(dm_targets[0].notice_id, "2");
+        assert!(db.is_dm_sent(id, 100).unwrap());
+
+        // 번호가 바뀐 뒤 제목 수정도 같은 행에서
+        let mut edited = renumbered.clone();
+        edited.title = "2026학년도 장학금 신청 안내 (연장)".into();
+        assert!(!db.renumber_by_url("test", &edited, &[edited.clone()]).unwrap());
+        assert!(!db.insert_if_new("test", &edited, "테스트", false).unwrap());
+        assert!(db.update_if_edited("test", &edited, true).unwrap());
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, id);
+
+    }
+
+    #[test]
+    fn test_shared_url_is_not_renumbering() {
+        // 모든 글이 목록 주소로 링크하는 게시판: URL이 같아도 번호가 다르면 다른 글
+        let db = Database::init(":memory:").unwrap();
+        let mut first = make_notice("10", "수강신청 안내");
+        first.url = "https://example.com/list".into();
+        assert_eq!(db.insert_batch("test", &[first.clone()], "테스트", false).unwrap(), 1);
+
+        let second = RawNotice { notice_id: "11".into(), title: "졸업 안내".into(), ..first.clone() };
+        let listing = [second.clone(), first.clone()];
+        assert_eq!(db.insert_batch("test", &listing, "테스트", false).unwrap(), 1);
+        // 다음 크롤에도 두 행 그대로 (번호가 서로 뒤바뀌지 않는다)
+        assert_eq!(db.insert_batch("test", &listing, "테스트", false).unwrap(), 0);
+        let mut stmt = db.conn.prepare("SELECT notice_id, title FROM notices ORDER BY notice_id").unwrap();
+        let rows: Vec<(String, String)> =
+            stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?))).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            rows,
+            vec![("10".to_string(), "수강신청 안내".to_string()), ("11".to_string(), "졸업 안내".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_pinned_notice_across_crawls() {
+        let db = Database::init(":memory:").unwrap();
+        let mut pinned = make_notice("100", "[필독] 2026학년도 학사일정 안내");
+        pinned.is_pinned = true;
+
+        // 첫 크롤: 정확히 한 번 알림
+        assert!(db.insert_if_new("biz", &pinned, "경영학부", false).unwrap());
+        // 다음 크롤들: 같은 id로 계속 맨 위에 보임
+        assert!(!db.insert_if_new("biz", &pinned, "경영학부", false).unwrap());
+        assert!(!db.insert_if_new("biz", &pinned, "경영학부", false).unwrap());
+
+        // 게시판이 고정 공지를 다시 올리며 id가 바뀜: 내용이 같으니 재알림 없음
+        let mut reposted = pinned.clone();
+        reposted.notice_id = "205".into();
+        reposted.title = "[필독] 2026학년도  학사일정 안내".into();
+        assert!(!db.insert_if_new("biz", &reposted, "경영학부", false).unwrap());
+        let display = std::collections::HashMap::new();
+        assert_eq!(db.get_pending(10, PostOrder::Latest, &display).unwrap().len(), 1, "only the first one pending");
+
+        // 다른 내용의 고정 공지, 다른 소스의 같은 제목은 새 공지
+        let mut other = make_notice("101", "[필독] 등록금 납부 안내");
+        other.is_pinned = true;
+        assert!(db.insert_if_new("biz", &other, "경영학부", false).unwrap());
+        assert!(db.insert_if_new("math", &reposted, "수학과", false).unwrap());
+    }
+
+    #[test]
+    fn test_weekly_counts_buckets() {
+        let db = Database::init(":memory:").unwrap();
+        let seeds = [
+            ("1", "-1 days"),
+            ("2", "-6 days"),
+            ("3", "-8 days"),
+            ("4", "-13 days"),
+            ("5", "-20 days"),
+            ("6", "-60 days"), // 8주 밖
+        ];
+        for (id, ago) in seeds {
+            db.insert_if_new("biz", &make_notice(id, &format!("공지 {}", id)), "경영학부", false).unwrap();
+            db.conn
+                .execute(
+                    "UPDATE notices SET crawled_at = datetime('now', '+9 hours', ?1) WHERE notice_id = ?2",
+                    params![ago, id],
+                )
+                .unwrap();
+        }
+        db.insert_if_new("math", &make_notice("9", "다른 학과"), "수학과", false).unwrap();
+
+        let counts = db.weekly_counts("biz", 8).unwrap();
+        assert_eq!(counts, vec![0, 0, 0, 0, 0, 1, 2, 2]);
+        assert_eq!(db.weekly_counts("none", 4).unwrap(), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_sources_summary() {
+        let db = Database::init(":memory:").unwrap();
+        for i in 0..3 {
+            db.insert_if_new("biz", &make_notice(&i.to_string(), &format!("공지 {}", i)), "경영학부", false)
+                .unwrap();
+        }
+        db.insert_if_new("math", &make_notice("1", "수학과 공지"), "수학과", false).unwrap();
+        db.update_crawl_state("biz", Some("2")).unwrap();
+        // 크롤은 시도했지만 실패만 한 소스
+        db.increment_error("dead", "HTTP 500").unwrap();
+
+        let summary = db.get_sources_summary().unwrap();
+        let by_key = |k: &str| summary.iter().find(|s| s.source_key == k).unwrap().clone();
+        assert_eq!(summary.len(), 3);
+
+        let biz = by_key("biz");
+        assert_eq!(biz.notice_count, 3);
+        assert!(biz.last_success.is_some());
+
+        // insert_if_new가 crawl_state 행만 만들어 둔 경우: 성공 기록 없음
+        let math = by_key("math");
+        assert_eq!(math.notice_count, 1);
+        assert!(math.last_success.is_none());
+
+        let dead = by_key("dead");
+        assert_eq!(dead.notice_count, 0);
+        assert!(dead.last_success.is_none());
+    }
+
+    #[test]
+    fn test_export_json_round_trip() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("biz", &make_notice("1", "2026학년도 국가장학금 신청"), "경영학부", false).unwrap();
+        db.insert_if_new("biz", &make_notice("2", "학생회관 공사 안내"), "경영학부", false).unwrap();
+        db.conn
+            .execute(
+                "UPDATE notices SET crawled_at = '2025-01-01 09:00:00' WHERE notice_id = '2'",
+                [],
+            )
+            .unwrap();
+        let names = std::collections::HashMap::from([("biz".to_string(), "경영학부".to_string())]);
+
+        let json = db.export_json(None, &names).unwrap();
+        let all: Vec<Notice> = serde_json::from_str(&json).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].notice_id, "2", "oldest first");
+        assert_eq!(all[1].category, "scholarship");
+        assert_eq!(all[1].source_display_name, "경영학부");
+        assert!(json.contains("\"category\": \"scholarship\""));
+
+        let recent: Vec<Notice> =
+            serde_json::from_str(&db.export_json(Some("2026-01-01"), &names).unwrap()).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].title, "2026학년도 국가장학금 신청");
+    }
+
+    #[test]
+    fn test_views_update_and_trending() {
+        let db = Database::init(":memory:").unwrap();
+        let today = kst::today().to_string();
+        let mut hot = make_notice("1", "수강신청 안내");
+        hot.date = Some(today.clone());
+        hot.views = Some(100);
+        let mut cold = make_notice("2", "도로 보수공사");
+        cold.date = Some(today);
+        cold.views = Some(5);
+        let mut old = make_notice("3", "작년 공지");
+        old.date = Some("2020-01-01".into());
+        old.views = Some(9999);
+        for n in [&hot, &cold, &old] {
+            db.insert_if_new("biz", n, "경영학부", false).unwrap();
+        }
+        db.insert_if_new("biz", &make_notice("4", "조회수 없음"), "경영학부", false).unwrap();
+
+        // 다음 크롤에서 조회수가 역전
+        db.update_views("biz", "2", 300).unwrap();
+
+        let trending = db.get_trending(7, 10).unwrap();
+        let ids: Vec<_> = trending.iter().map(|(n, v)| (n.notice_id.as_str(), *v)).collect();
+        assert_eq!(ids, vec![("2", 300), ("1", 100)]);
+    }
+
+    #[test]
+    fn test_error_cooldown_backoff() {
+        assert_eq!(error_cooldown(4, 5), None);
+        assert_eq!(error_cooldown(10, 0), None);
+        assert_eq!(error_cooldown(5, 5), Some(Duration::minutes(30)));
+        assert_eq!(error_cooldown(6, 5), Some(Duration::minutes(60)));
+        assert_eq!(error_cooldown(7, 5), Some(Duration::minutes(120)));
+        assert_eq!(error_cooldown(50, 5), Some(Duration::hours(24)));
+    }
+
+    #[test]
+    fn test_duration_ema() {
+        assert_eq!(duration_ema(None, 1200.0), 1200.0);
+        assert!((duration_ema(Some(1000.0), 2000.0) - 1200.0).abs() < 1e-9);
+        assert!((duration_ema(Some(1000.0), 500.0) - 900.0).abs() < 1e-9);
+
+        // 같은 값이 이어지면 그 값으로 수렴
+        let mut ema = None;
+        for _ in 0..50 {
+            ema = Some(duration_ema(ema.or(Some(5000.0)), 800.0));
+        }
+        assert!((ema.unwrap() - 800.0).abs() < 1.0);
+
+        let db = Database::init(":memory:").unwrap();
+        assert_eq!(db.record_crawl_duration("biz", 1000.0).unwrap(), None);
+        assert_eq!(db.record_crawl_duration("biz", 2000.0).unwrap(), Some(1000.0));
+        let avg = db.get_crawl_stats().unwrap()[0].avg_duration_ms.unwrap();
+        assert!((avg - 1200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_source_cooldown_transitions() {
+        let db = Database::init(":memory:").unwrap();
+        db.update_crawl_state("biz", Some("1")).unwrap();
+
+        // 임계치 전: 쿨다운 없음
+        for _ in 0..2 {
+            let n = db.increment_error("biz", "HTTP 500").unwrap();
+            assert_eq!(db.apply_error_cooldown("biz", n, 3).unwrap(), None);
+        }
+        assert!(db.cooldown_until("biz").unwrap().is_none());
+
+        // 임계치 도달: 비활성
+        let n = db.increment_error("biz", "HTTP 500").unwrap();
+        assert!(db.apply_error_cooldown("biz", n, 3).unwrap().is_some());
+        assert!(db.cooldown_until("biz").unwrap().is_some());
+        assert!(db.get_crawl_stats().unwrap()[0].is_cooling_down());
+
+        // 쿨다운이 지나면 다시 시도 대상
+        db.conn
+            .execute(
+                "UPDATE crawl_state SET disabled_until = datetime('now', '+9 hours', '-1 minute')",
+                [],
+            )
+            .unwrap();
+        assert!(db.cooldown_until("biz").unwrap().is_none());
+
+        // 성공하면 이전 실패 횟수를 돌려주고 상태 초기화
+        assert_eq!(db.update_crawl_state("biz", None).unwrap(), 3);
+        let stat = &db.get_crawl_stats().unwrap()[0];
+        assert_eq!(stat.error_count, 0);
+        assert!(stat.disabled_until.is_none());
+    }
+
+    #[test]
+    fn test_feedback_daily_count() {
+        let db = Database::init(":memory:").unwrap();
+        assert_eq!(db.count_feedback_today(1).unwrap(), 0);
+        db.add_feedback(1, Some("kim"), "제목이 깨져요", true).unwrap();
+        db.add_feedback(1, Some("kim"), "날짜가 틀려요", false).unwrap();
+        db.add_feedback(2, None, "감사합니다", true).unwrap();
+        // 어제 보낸 건 세지 않는다
+        db.conn
+            .execute(
+                "INSERT INTO feedback (telegram_id, message, created_at)
+                 VALUES (1, 'old', datetime('now', '+9 hours', '-2 days'))",
+                [],
+            )
+            .unwrap();
+
+        assert_eq!(db.count_feedback_today(1).unwrap(), 2);
+        assert_eq!(db.count_feedback_today(2).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_source_sub_categories() {
+        let db = Database::init(":memory:").unwrap();
+        db.register_user(100, None, None).unwrap();
+        db.register_user(200, None, None).unwrap();
+        db.add_source_sub(100, "biz").unwrap();
+        db.add_source_sub(200, "biz").unwrap();
+        db.set_source_sub_categories(100, "biz", &["scholarship".into(), "recruit".into()])
+            .unwrap();
+
+        let filters = db.get_source_sub_filters("biz").unwrap();
+        assert_eq!(filters.len(), 1);
+        assert_eq!(filters[&100], vec!["scholarship", "recruit"]);
+
+        let subs = db.get_user_subs(100).unwrap();
+        assert_eq!(subs.source_categories["biz"], vec!["scholarship", "recruit"]);
+
+        // 빈 필터 = 전체
+        db.set_source_sub_categories(100, "biz", &[]).unwrap();
+        assert!(db.get_source_sub_filters("biz").unwrap().is_empty());
+    }
+
     #[test]
     fn test_source_subscribers() {
         let db = Database::init(":memory:").unwrap();
@@ -574,7 +3338,7 @@ mod tests {
     fn test_dm_log() {
         let db = Database::init(":memory:").unwrap();
         db.register_user(100, None, None).unwrap();
-        db.insert_if_new("test", &make_notice("1", "장학금 공지"), "테스트").unwrap();
+        db.insert_if_new("test", &make_notice("1", "장학금 공지"), "테스트", false).unwrap();
 
         // 아직 DM 안 보냄
         assert!(!db.is_dm_sent(1, 100).unwrap());
@@ -586,4 +3350,401 @@ mod tests {
         // 중복 기록은 무시
         db.log_dm(1, 100, "keyword", Some("장학금")).unwrap();
     }
+
+    #[test]
+    fn test_dismiss_notice() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("test", &make_notice("1", "장학금 신청 마감"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "수강신청 마감"), "테스트", false).unwrap();
+        let deadline = (kst::today() + Duration::days(3)).to_string();
+        db.set_deadline(1, &deadline).unwrap();
+        db.set_deadline(2, &deadline).unwrap();
+
+        assert!(!db.is_dismissed(1, 100).unwrap());
+        assert!(db.dismiss_notice(1, 100).unwrap());
+        assert!(db.is_dismissed(1, 100).unwrap());
+        // 두 번 눌러도 한 번만 기록
+        assert!(!db.dismiss_notice(1, 100).unwrap());
+        // 다른 사용자에게는 영향 없음
+        assert!(!db.is_dismissed(1, 200).unwrap());
+
+        let ids = |user| -> Vec<i64> {
+            db.get_deadline_notices(10, user).unwrap().iter().map(|(n, _)| n.id).collect()
+        };
+        assert_eq!(ids(Some(100)), vec![2]);
+        assert_eq!(ids(Some(200)).len(), 2);
+        assert_eq!(ids(None).len(), 2);
+    }
+
+    #[test]
+    fn test_set_detail() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트", false).unwrap();
+
+        db.set_detail("test", "1", Some("본문 내용"), Some("2026-02-01 09:30")).unwrap();
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending[0].body.as_deref(), Some("본문 내용"));
+        assert_eq!(pending[0].published.as_deref(), Some("2026-02-01 09:30"));
+
+        // 게시 시각을 못 얻었으면 목록 날짜를 유지
+        db.set_detail("test", "1", None, None).unwrap();
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending[0].body.as_deref(), Some("본문 내용"));
+        assert_eq!(pending[0].published.as_deref(), Some("2026-02-01 09:30"));
+    }
+
+    #[test]
+    fn test_migrates_utc_timestamps() {
+        let path = std::env::temp_dir().join(format!("cbnu_kst_migration_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        {
+            // KST 전환 전 DB: 기본값/저장 시각이 UTC
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE notices (
+                    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                    source_key  TEXT NOT NULL,
+                    notice_id   TEXT NOT NULL,
+                    title       TEXT NOT NULL,
+                    url         TEXT NOT NULL,
+                    author      TEXT,
+                    category    TEXT DEFAULT 'general',
+                    published   TEXT,
+                    deadline    TEXT,
+                    crawled_at  TEXT NOT NULL DEFAULT (datetime('now')),
+                    notified    INTEGER DEFAULT 0,
+                    notified_at TEXT,
+                    UNIQUE(source_key, notice_id)
+                );
+                CREATE TABLE notify_queue (
+                    notice_id     INTEGER PRIMARY KEY,
+                    attempts      INTEGER NOT NULL DEFAULT 0,
+                    next_retry_at TEXT NOT NULL
+                );
+                INSERT INTO notices (source_key, notice_id, title, url, crawled_at, notified, notified_at)
+                VALUES ('biz', '1', '장학금 안내', 'https://example.com/1', '2026-03-10 14:30:00', 1, '2026-03-10 14:35:00');
+                INSERT INTO notices (source_key, notice_id, title, url, crawled_at)
+                VALUES ('biz', '2', '수강신청 안내', 'https://example.com/2', '2026-03-10 15:00:00');
+                INSERT INTO notify_queue (notice_id, attempts, next_retry_at) VALUES (2, 1, '2026-03-10 15:01:00');",
+            )
+            .unwrap();
+        }
+
+        let times = |db: &Database| -> (String, Option<String>, String) {
+            let (crawled, notified) = db
+                .conn
+                .query_row("SELECT crawled_at, notified_at FROM notices WHERE notice_id = '1'", [], |r| {
+                    Ok((r.get(0)?, r.get(1)?))
+                })
+                .unwrap();
+            let retry = db.conn.query_row("SELECT next_retry_at FROM notify_queue", [], |r| r.get(0)).unwrap();
+            (crawled, notified, retry)
+        };
+        let expected = ("2026-03-10 23:30:00".to_string(), Some("2026-03-10 23:35:00".to_string()), "2026-03-11 00:01:00".to_string());
+
+        let db = Database::init(&path).unwrap();
+        assert_eq!(times(&db), expected);
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        drop(db);
+
+        // 다시 열어도 두 번 옮기지 않는다
+        let db = Database::init(&path).unwrap();
+        assert_eq!(times(&db), expected);
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[test]
+    fn test_upgraded_db_writes_kst() {
+        let path = std::env::temp_dir().join(format!("cbnu_kst_defaults_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        {
+            // 기존 테이블은 UTC 기본값을 그대로 가진다
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE users (
+                    telegram_id  INTEGER PRIMARY KEY,
+                    username     TEXT,
+                    first_name   TEXT,
+                    registered   TEXT NOT NULL DEFAULT (datetime('now')),
+                    is_active    INTEGER DEFAULT 1
+                );
+                CREATE TABLE dm_log (
+                    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+                    notice_id    INTEGER NOT NULL,
+                    telegram_id  INTEGER NOT NULL,
+                    match_type   TEXT NOT NULL,
+                    match_value  TEXT,
+                    sent_at      TEXT NOT NULL DEFAULT (datetime('now')),
+                    UNIQUE(notice_id, telegram_id)
+                );",
+            )
+            .unwrap();
+        }
+
+        let db = Database::init(&path).unwrap();
+        db.register_user(1, None, None).unwrap();
+        db.log_dm(1, 1, "keyword", Some("장학")).unwrap();
+        assert_eq!(db.count_dms_today(1).unwrap(), 1);
+        let recent = |sql: &str| -> i64 { db.conn.query_row(sql, [], |r| r.get(0)).unwrap() };
+        assert_eq!(recent("SELECT COUNT(*) FROM dm_log WHERE sent_at >= datetime('now', '+9 hours', '-1 minute')"), 1);
+        assert_eq!(recent("SELECT COUNT(*) FROM users WHERE registered >= datetime('now', '+9 hours', '-1 minute')"), 1);
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[test]
+    fn test_empty_parse_streak() {
+        let db = Database::init(":memory:").unwrap();
+        // 처음 비었을 때만 1 (알림), 계속 비면 늘어나기만 한다
+        assert_eq!(db.record_empty_parse("biz", true).unwrap(), 1);
+        assert_eq!(db.record_empty_parse("biz", true).unwrap(), 2);
+        assert_eq!(db.record_empty_parse("biz", true).unwrap(), 3);
+        assert_eq!(db.record_empty_parse("physics", true).unwrap(), 1);
+
+        // 공지가 다시 나오면 초기화, 다음에 비면 다시 알림
+        assert_eq!(db.record_empty_parse("biz", false).unwrap(), 0);
+        assert_eq!(db.record_empty_parse("biz", true).unwrap(), 1);
+        assert_eq!(db.record_empty_parse("new_source", false).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_content_hash_normalizes() {
+        assert_eq!(
+            content_hash("2026학년도 국가장학금 신청 안내"),
+            content_hash(" 2026학년도  국가장학금 신청 안내! ")
+        );
+        assert_ne!(
+            content_hash("2026학년도 국가장학금 신청 안내"),
+            content_hash("2025학년도 국가장학금 신청 안내")
+        );
+    }
+
+    #[test]
+    fn test_dedup_hash_canonical_link() {
+        let title = "2026학년도 국가장학금 신청 안내";
+        let link = "https://www.chungbuk.ac.kr/view.do?nttNo=5&bbsNo=8";
+        assert_eq!(dedup_hash(title, link), dedup_hash(" 2026학년도  국가장학금 신청 안내! ", link));
+        assert_eq!(
+            dedup_hash(title, link),
+            dedup_hash(title, "http://WWW.chungbuk.ac.kr/view.do/?bbsNo=8&nttNo=5#top")
+        );
+        assert_ne!(dedup_hash(title, link), dedup_hash(title, "https://www.chungbuk.ac.kr/view.do?nttNo=6&bbsNo=8"));
+        // 링크를 해석할 수 없으면 제목만
+        assert_eq!(dedup_hash(title, ""), content_hash(title));
+    }
+
+    #[test]
+    fn test_cross_post_dedup_hit() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        let n = make_notice("1", "2026학년도 국가장학금 신청 안내");
+        assert!(db.insert_if_new("cbnu_main", &n, "본부", true).unwrap());
+
+        // 학과 게시판에 같은 공지가 다른 ID로, 본부 글 링크를 달고 올라옴
+        let mut cross = make_notice("900", "2026학년도 국가장학금 신청 안내");
+        cross.url = format!("{}#view", n.url.replace("https://", "http://"));
+        assert!(!db.insert_if_new("biz", &cross, "경영", true).unwrap());
+
+        // 기록은 되지만 알림 대상은 아님
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].source_key, "cbnu_main");
+    }
+
+    #[test]
+    fn test_cross_post_dedup_avoids_false_collision() {
+        let db = Database::init(":memory:").unwrap();
+        let n = make_notice("1", "2026학년도 국가장학금 신청 안내");
+        db.insert_if_new("cbnu_main", &n, "본부", true).unwrap();
+
+        // 옵트인하지 않은 소스는 그대로 알림
+        let cross = RawNotice { notice_id: "900".into(), ..n.clone() };
+        assert!(db.insert_if_new("biz", &cross, "경영", false).unwrap());
+
+        // 흔한 제목이라도 링크가 다른 학과 공지는 각각 알림
+        let biz = make_notice("31", "학사일정 안내");
+        let physics = make_notice("77", "학사일정 안내");
+        assert!(db.insert_if_new("biz", &biz, "경영", true).unwrap());
+        assert!(db.insert_if_new("physics", &physics, "물리", true).unwrap());
+
+        // 지난 학기의 같은 제목 공지는 기간 밖이므로 중복이 아님
+        db.conn
+            .execute(
+                "UPDATE notices SET crawled_at = datetime('now', '+9 hours', '-180 days')",
+                [],
+            )
+            .unwrap();
+        let next = RawNotice { notice_id: "901".into(), ..n.clone() };
+        assert!(db.insert_if_new("physics", &next, "물리", true).unwrap());
+    }
+
+    #[test]
+    fn test_get_latest_by_source() {
+        let db = Database::init(":memory:").unwrap();
+        let mut old = make_notice("1", "오래된 공지");
+        old.date = Some("2026-01-10".into());
+        let mut new = make_notice("2", "새 공지");
+        new.date = Some("2026-02-05".into());
+        db.insert_if_new("biz", &old, "경영", false).unwrap();
+        db.insert_if_new("biz", &new, "경영", false).unwrap();
+        db.insert_if_new("physics", &make_notice("3", "다른 학과"), "물리", false).unwrap();
+
+        let latest = db.get_latest_by_source("biz", 10).unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].title, "새 공지");
+        assert_eq!(latest[1].title, "오래된 공지");
+
+        assert_eq!(db.get_latest_by_source("biz", 1).unwrap().len(), 1);
+        assert!(db.get_latest_by_source("unknown", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_sources() {
+        let db = Database::init(":memory:").unwrap();
+        db.insert_if_new("biz_old", &make_notice("1", "옛 주소 공지"), "경영", false).unwrap();
+        db.insert_if_new("biz_old", &make_notice("2", "겹치는 공지"), "경영", false).unwrap();
+        let old_dup = db.get_notice_by_url("https://example.com/2").unwrap().unwrap();
+        db.mark_notified(old_dup.id).unwrap();
+        db.insert_if_new("biz", &make_notice("2", "겹치는 공지"), "경영", false).unwrap();
+        db.update_crawl_state("biz_old", Some("2")).unwrap();
+
+        db.register_user(1, None, None).unwrap();
+        db.register_user(2, None, None).unwrap();
+        db.add_source_sub(1, "biz_old").unwrap();
+        db.add_source_sub(2, "biz_old").unwrap();
+        db.add_source_sub(2, "biz").unwrap();
+
+        let merge = db.merge_sources("biz_old", "biz").unwrap();
+        assert_eq!(merge, SourceMerge { notices: 1, duplicates: 1, subs: 1 });
+
+        // 공지는 새 키로, 겹친 공지는 한 건만 남고 다시 알리지 않는다
+        assert_eq!(db.get_latest_by_source("biz", 10).unwrap().len(), 2);
+        assert!(db.get_latest_by_source("biz_old", 10).unwrap().is_empty());
+        let pending = db.get_pending(10, PostOrder::Latest, &std::collections::HashMap::new()).unwrap();
+        assert_eq!(pending.iter().map(|n| n.notice_id.as_str()).collect::<Vec<_>>(), ["1"]);
+
+        // 구독은 중복 없이 이전
+        let mut subscribers = db.get_source_subscribers("biz").unwrap();
+        subscribers.sort();
+        assert_eq!(subscribers, [1, 2]);
+        assert!(db.get_source_subscribers("biz_old").unwrap().is_empty());
+
+        let stats = db.get_crawl_stats().unwrap();
+        assert_eq!(stats.iter().map(|s| s.source_key.as_str()).collect::<Vec<_>>(), ["biz"]);
+
+        assert!(db.merge_sources("biz", "biz").is_err());
+    }
+
+    #[test]
+    fn test_get_by_category() {
+        let db = Database::init(":memory:").unwrap();
+        let mut old = make_notice("1", "국가장학금 신청 안내");
+        old.date = Some("2026-01-10".into());
+        let mut new = make_notice("2", "교내 장학생 선발");
+        new.date = Some("2026-02-05".into());
+        db.insert_if_new("biz", &old, "경영", false).unwrap();
+        db.insert_if_new("physics", &new, "물리", false).unwrap();
+        db.insert_if_new("biz", &make_notice("3", "수강신청 일정"), "경영", false).unwrap();
+
+        // 소스와 상관없이 카테고리만 보고 최신순
+        let top = db.get_by_category("scholarship", 10).unwrap();
+        let titles: Vec<&str> = top.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["교내 장학생 선발", "국가장학금 신청 안내"]);
+        assert_eq!(db.get_by_category("scholarship", 1).unwrap().len(), 1);
+        assert_eq!(db.get_by_category("academic", 10).unwrap().len(), 1);
+        assert!(db.get_by_category("recruit", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_latest_and_search_paging() {
+        let db = Database::init(":memory:").unwrap();
+        for i in 1..=15 {
+            let mut n = make_notice(&i.to_string(), &format!("장학금 공지 {}", i));
+            n.date = Some(format!("2026-01-{:02}", i));
+            db.insert_if_new("test", &n, "테스트", false).unwrap();
+        }
+        db.insert_if_new("test", &make_notice("99", "채용 공고"), "테스트", false).unwrap();
+
+        let page1 = db.get_latest(10, 0).unwrap();
+        assert_eq!(page1.len(), 10);
+        assert_eq!(db.get_latest(10, 10).unwrap().len(), 6);
+
+        let hits = db.search_notices("장학금", 10, 0).unwrap();
+        assert_eq!(hits.len(), 10);
+        assert_eq!(hits[0].title, "장학금 공지 15");
+        let rest = db.search_notices("장학금", 10, 10).unwrap();
+        assert_eq!(rest.len(), 5);
+        assert_eq!(rest[4].title, "장학금 공지 1");
+        assert!(db.search_notices("없는검색어", 10, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_send_failure_queues_with_backoff() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트", false).unwrap();
+        db.insert_if_new("test", &make_notice("2", "공지2"), "테스트", false).unwrap();
+        let id = db.get_pending(10, PostOrder::Latest, &display).unwrap().iter().find(|n| n.notice_id == "1").unwrap().id;
+
+        // 발송 실패 → 대기열, 신규 목록에서는 빠짐
+        assert_eq!(db.record_send_failure(id).unwrap(), 1);
+        let pending = db.get_pending(10, PostOrder::Latest, &display).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].notice_id, "2");
+        // backoff 시간 전이라 재시도 대상 아님
+        assert!(db.get_due_retries(10, &display).unwrap().is_empty());
+
+        // 또 실패하면 시도 횟수 증가
+        assert_eq!(db.record_send_failure(id).unwrap(), 2);
+
+        // 재시도 시각 도래
+        db.conn
+            .execute("UPDATE notify_queue SET next_retry_at = datetime('now', '+9 hours', '-1 second')", [])
+            .unwrap();
+        let due = db.get_due_retries(10, &display).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, id);
+
+        // 성공 시 대기열에서 제거
+        db.mark_notified(id).unwrap();
+        assert!(db.get_due_retries(10, &display).unwrap().is_empty());
+        let queued: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM notify_queue", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(queued, 0);
+    }
+
+    #[test]
+    fn test_send_progress_roundtrip() {
+        let db = Database::init(":memory:").unwrap();
+        let display = std::collections::HashMap::new();
+        db.insert_if_new("test", &make_notice("1", "공지1"), "테스트", false).unwrap();
+        let id = db.get_pending(10, PostOrder::Latest, &display).unwrap()[0].id;
+
+        // 대기열에 없는 공지는 기록되지 않음
+        db.set_send_progress(id, "html", 2).unwrap();
+        assert!(db.get_send_progress(&[id]).unwrap().is_empty());
+
+        db.record_send_failure(id).unwrap();
+        db.set_send_progress(id, "html", 2).unwrap();
+        let progress = db.get_send_progress(&[id, id + 100]).unwrap();
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[&id], ("html".to_string(), 2));
+
+        // 다시 실패해도 진행 상황은 유지, 성공하면 대기열과 함께 사라짐
+        db.record_send_failure(id).unwrap();
+        assert_eq!(db.get_send_progress(&[id]).unwrap()[&id].1, 2);
+        db.mark_notified(id).unwrap();
+        assert!(db.get_send_progress(&[id]).unwrap().is_empty());
+    }
 }
@@ -1,56 +1,185 @@
-use chrono::{Local, NaiveDate};
+use std::sync::LazyLock;
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
 use regex::Regex;
 
-/// 공지 제목에서 마감일을 추출한다.
-/// "~까지", "마감" 키워드 근처의 날짜를 우선, 없으면 제목 내 마지막 날짜를 반환.
+/// 추출한 마감 시점. 제목에 시각이 있으면 `time`도 채운다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+}
+
+impl Deadline {
+    /// 실제 마감 시각. 시각이 없으면 그날 23:59:59.
+    #[cfg(test)]
+    pub fn cutoff(self) -> chrono::NaiveDateTime {
+        self.date
+            .and_time(self.time.unwrap_or_else(|| NaiveTime::from_hms_opt(23, 59, 59).unwrap()))
+    }
+
+    /// DB 저장 형식: `YYYY-MM-DD` 또는 `YYYY-MM-DD HH:MM`. 날짜만 비교하는 기존
+    /// 쿼리(`deadline >= 오늘`)와 문자열 비교가 그대로 맞는다.
+    pub fn to_db_string(self) -> String {
+        match self.time {
+            Some(t) => format!("{} {}", self.date.format("%Y-%m-%d"), t.format("%H:%M")),
+            None => self.date.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// 공지 제목에서 마감일을 추출한다 (날짜만).
+#[cfg(test)]
 pub fn extract_deadline(title: &str) -> Option<NaiveDate> {
-    let year = Local::now().format("%Y").to_string().parse::<i32>().unwrap_or(2026);
+    extract_deadline_detail(title).map(|d| d.date)
+}
+
+/// 공지 제목에서 마감 날짜와 (있으면) 시각을 추출한다.
+/// "~까지", "마감" 키워드 근처의 날짜를 우선, 없으면 제목 내 마지막 날짜를 사용.
+/// 시각은 날짜 바로 뒤의 `18:00`, `오후 6시`, `오전 10시 30분` 형태만 인정한다.
+pub fn extract_deadline_detail(title: &str) -> Option<Deadline> {
+    let date = find_deadline_date(title)?;
+    let time = time_after(title, date.1);
+    Some(Deadline { date: date.0, time })
+}
+
+/// YYYY.MM.DD / YYYY-MM-DD / YYYY/MM/DD 또는 M.D / M/D / M월 D일
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{4})[.\-/]\s?(\d{1,2})[.\-/]\s?(\d{1,2})|(\d{1,2})[./\uc6d4]\s?(\d{1,2})[.\uc77c]?").unwrap()
+});
+
+/// 날짜 바로 뒤의 시각 (`18:00`, `오후 6시`, `10시 30분`, `2시 반`). 요일 괄호 허용.
+static TIME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\.?\s*(?:\([^)]{1,3}\)\s*)?(?:(오전|오후)\s*)?(\d{1,2})(?::(\d{2})|\s*시(?:\s*(\d{1,2})\s*분|\s*반)?)")
+        .unwrap()
+});
+
+/// 제목에서 찾은 날짜 하나와 그 표기의 바이트 범위.
+struct DateMatch {
+    date: NaiveDate,
+    start: usize,
+    end: usize,
+}
 
-    // 패턴 1: YYYY.MM.DD / YYYY-MM-DD / YYYY/MM/DD
-    let re_full = Regex::new(r"(\d{4})[.\-/](\d{1,2})[.\-/](\d{1,2})").unwrap();
-    // 패턴 2: M.D / M월D일 / M월 D일
-    let re_md = Regex::new(r"(\d{1,2})[.\uc6d4]\s?(\d{1,2})[.\uc77c]?").unwrap();
+/// 제목의 날짜 표기를 앞에서부터 모두 찾는다. 연도가 없는 날짜는 앞 날짜의 연도를
+/// 물려받고 (없으면 올해), 범위 끝(`~` 뒤)이 시작보다 앞서면 다음 해로 본다.
+fn scan_dates(title: &str) -> Vec<DateMatch> {
+    let mut dates: Vec<DateMatch> = Vec::new();
+    let mut year = crate::kst::today().year();
+    for caps in DATE_RE.captures_iter(title) {
+        let whole = caps.get(0).unwrap();
+        let date = if let Some(y) = caps.get(1) {
+            parse_ymd(y.as_str(), &caps[2], &caps[3])
+        } else {
+            parse_md(year, &caps[4], &caps[5]).map(|d| match dates.last() {
+                // 12.20~1.10 처럼 해를 넘기는 범위
+                Some(prev) if is_range_gap(&title[prev.end..whole.start()]) && d < prev.date => {
+                    d.with_year(d.year() + 1).unwrap_or(d)
+                }
+                _ => d,
+            })
+        };
+        if let Some(date) = date {
+            year = date.year();
+            dates.push(DateMatch { date, start: whole.start(), end: whole.end() });
+        }
+    }
+    dates
+}
 
-    // "까지", "마감" 근처 날짜 우선 탐색
+/// 두 날짜 사이가 범위 구분자(`~`, `-`)뿐인지. 시작 날짜 뒤 요일 괄호는 허용.
+fn is_range_gap(between: &str) -> bool {
+    let rest = between.trim_start();
+    let rest = match rest.strip_prefix('(') {
+        Some(r) => match r.find(')') {
+            Some(i) => r[i + 1..].trim_start(),
+            None => return false,
+        },
+        None => rest,
+    };
+    let mut chars = rest.chars();
+    matches!(chars.next(), Some('~' | '\u{223c}' | '\u{301c}' | '-' | '\u{2013}'))
+        && chars.as_str().trim().is_empty()
+}
+
+/// 마감 날짜와 제목 안에서 그 날짜 표기가 끝나는 바이트 위치.
+/// 범위(`2.6~2.8`)는 항상 끝 날짜를 고른다.
+fn find_deadline_date(title: &str) -> Option<(NaiveDate, usize)> {
+    let dates = scan_dates(title);
+
+    // "까지", "마감" 키워드 앞 40바이트 안의 마지막 날짜 우선
     let deadline_keywords = ["까지", "마감", "이내"];
     for kw in &deadline_keywords {
         if let Some(pos) = title.find(kw) {
-            // 키워드 앞 40바이트 범위에서 날짜 검색 (char boundary 보정)
-            let mut start = pos.saturating_sub(40);
-            while start > 0 && !title.is_char_boundary(start) {
-                start -= 1;
-            }
-            let region = &title[start..pos];
-
-            if let Some(caps) = re_full.captures(region) {
-                if let Some(d) = parse_ymd(&caps[1], &caps[2], &caps[3]) {
-                    return Some(d);
-                }
-            }
-            if let Some(caps) = re_md.captures(region) {
-                if let Some(d) = parse_md(year, &caps[1], &caps[2]) {
-                    return Some(d);
-                }
+            if let Some(m) = dates
+                .iter()
+                .rev()
+                .find(|m| m.end <= pos && m.start + 40 >= pos)
+            {
+                return Some((m.date, m.end));
             }
         }
     }
 
+    // "~ 2/28": 앞에 시작 날짜 없이 물결로 시작하면 "까지"와 같은 뜻
+    if let Some(m) = dates.iter().find(|m| {
+        let before = title[..m.start].trim_end();
+        before.ends_with(['~', '\u{223c}', '\u{301c}'])
+    }) {
+        return Some(range_end(title, &dates, m));
+    }
+
     // fallback: 제목 전체에서 마지막으로 등장하는 날짜
-    let mut last: Option<NaiveDate> = None;
-    for caps in re_full.captures_iter(title) {
-        if let Some(d) = parse_ymd(&caps[1], &caps[2], &caps[3]) {
-            last = Some(d);
+    dates.last().map(|m| (m.date, m.end))
+}
+
+/// `m`이 범위의 시작이면 이어지는 끝 날짜를, 아니면 `m` 자신을 반환.
+fn range_end(title: &str, dates: &[DateMatch], m: &DateMatch) -> (NaiveDate, usize) {
+    let mut cur = m;
+    for next in dates.iter().skip_while(|d| d.start <= m.start) {
+        if !is_range_gap(&title[cur.end..next.start]) {
+            break;
         }
+        cur = next;
     }
-    if last.is_some() {
-        return last;
+    (cur.date, cur.end)
+}
+
+/// 날짜 표기 바로 뒤(요일 괄호 허용)의 시각. `오후 6시` → 18:00, `18:00` → 18:00.
+fn time_after(title: &str, date_end: usize) -> Option<NaiveTime> {
+    let caps = TIME_RE.captures(title.get(date_end..)?)?;
+
+    let mut hour: u32 = caps[2].parse().ok()?;
+    let minute: u32 = match (caps.get(3), caps.get(4)) {
+        (Some(m), _) | (None, Some(m)) => m.as_str().parse().ok()?,
+        _ if caps[0].ends_with('반') => 30,
+        _ => 0,
+    };
+    match caps.get(1).map(|m| m.as_str()) {
+        Some("오후") if hour < 12 => hour += 12,
+        Some("오전") if hour == 12 => hour = 0,
+        _ => {}
     }
-    for caps in re_md.captures_iter(title) {
-        if let Some(d) = parse_md(year, &caps[1], &caps[2]) {
-            last = Some(d);
-        }
+    // 24:00은 그날 자정 직전으로 본다
+    if hour == 24 && minute == 0 {
+        return NaiveTime::from_hms_opt(23, 59, 59);
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// DB에 저장된 마감 문자열(`YYYY-MM-DD[ HH:MM]`)의 날짜 부분.
+pub fn parse_db_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// 남은 일수 표시. 당일이면 `D-Day`, 지났으면 `D+N`.
+pub fn d_day(deadline: NaiveDate, today: NaiveDate) -> String {
+    let days = (deadline - today).num_days();
+    match days {
+        0 => "D-Day".to_string(),
+        d if d > 0 => format!("D-{}", d),
+        d => format!("D+{}", -d),
     }
-    last
 }
 
 fn parse_ymd(y: &str, m: &str, d: &str) -> Option<NaiveDate> {
@@ -97,6 +226,77 @@ mod tests {
         assert!(extract_deadline("장학금 신청 안내").is_none());
     }
 
+    #[test]
+    fn test_range_inherits_year_from_start() {
+        let d = extract_deadline("등록금 납부 2026.2.6(금) ~ 2.8");
+        assert_eq!(d, NaiveDate::from_ymd_opt(2026, 2, 8));
+
+        let d = extract_deadline("2025.12.20~1.10 동계 계절학기 신청");
+        assert_eq!(d, NaiveDate::from_ymd_opt(2026, 1, 10));
+    }
+
+    #[test]
+    fn test_open_range_means_until() {
+        let d = extract_deadline("휴학 신청 (~ 2/28)").unwrap();
+        assert_eq!((d.month(), d.day()), (2, 28));
+
+        // 뒤에 다른 날짜(발표일)가 있어도 물결 뒤 날짜가 마감
+        let d = extract_deadline("교환학생 모집 ~2026.3.14 / 결과발표 2026.4.1");
+        assert_eq!(d, NaiveDate::from_ymd_opt(2026, 3, 14));
+    }
+
+    #[test]
+    fn test_range_before_keyword_picks_end() {
+        let d = extract_deadline("2026.2.6~2.8까지 수강정정");
+        assert_eq!(d, NaiveDate::from_ymd_opt(2026, 2, 8));
+    }
+
+    #[test]
+    fn test_time_24h() {
+        let d = extract_deadline_detail("서류 제출 2026.02.14 18:00까지").unwrap();
+        assert_eq!(d.date, NaiveDate::from_ymd_opt(2026, 2, 14).unwrap());
+        assert_eq!(d.time, NaiveTime::from_hms_opt(18, 0, 0));
+        assert_eq!(d.to_db_string(), "2026-02-14 18:00");
+    }
+
+    #[test]
+    fn test_time_korean_pm() {
+        let d = extract_deadline_detail("근로장학생 모집 (2.14 오후 6시 마감)").unwrap();
+        assert_eq!((d.date.month(), d.date.day()), (2, 14));
+        assert_eq!(d.time, NaiveTime::from_hms_opt(18, 0, 0));
+
+        let d = extract_deadline_detail("2.14.(금) 오전 10시 30분까지 접수").unwrap();
+        assert_eq!(d.time, NaiveTime::from_hms_opt(10, 30, 0));
+    }
+
+    #[test]
+    fn test_date_only_has_no_time() {
+        let d = extract_deadline_detail("장학금 신청 (~2026.02.14까지)").unwrap();
+        assert_eq!(d.time, None);
+        assert_eq!(d.to_db_string(), "2026-02-14");
+        assert_eq!(d.cutoff().time(), NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+        // 날짜와 떨어진 숫자는 시각으로 보지 않는다
+        let d = extract_deadline_detail("2026.03.01 마감, 정원 20명").unwrap();
+        assert_eq!(d.time, None);
+    }
+
+    #[test]
+    fn test_d_day_across_month_boundaries() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(d_day(day(2026, 1, 30), day(2026, 1, 30)), "D-Day");
+        assert_eq!(d_day(day(2026, 2, 2), day(2026, 1, 30)), "D-3");
+        // 2월 말 → 3월 (윤년 아님)
+        assert_eq!(d_day(day(2026, 3, 1), day(2026, 2, 27)), "D-2");
+        assert_eq!(d_day(day(2028, 3, 1), day(2028, 2, 27)), "D-3");
+        // 해 넘김
+        assert_eq!(d_day(day(2027, 1, 2), day(2026, 12, 31)), "D-2");
+        assert_eq!(d_day(day(2026, 4, 30), day(2026, 5, 1)), "D+1");
+
+        assert_eq!(parse_db_date("2026-02-14 18:00"), Some(day(2026, 2, 14)));
+        assert_eq!(parse_db_date("2026-02-14"), Some(day(2026, 2, 14)));
+        assert_eq!(parse_db_date("2/14"), None);
+    }
+
     #[test]
     fn test_full_iso_date() {
         let d = extract_deadline("2026-03-01 마감 공지");
@@ -0,0 +1,78 @@
+use serde_json::{json, Value};
+
+use crate::category::Category;
+use crate::db::Notice;
+
+/// 임베드 제목 최대 길이 (디스코드 제한).
+const EMBED_TITLE_MAX: usize = 256;
+
+/// 임베드 왼쪽 색 막대 (충북대 파랑).
+const EMBED_COLOR: u32 = 0x0067AC;
+
+/// 디스코드 webhook 요청 본문 (`[discord]`, `webhook::WebhookNotifier`로 게시):
+/// 제목(링크), 소스, 분류, 게시일을 담은 임베드 하나.
+/// 제목에 `@everyone` 같은 문자열이 있어도 멘션이 되지 않게 막는다.
+pub fn embed_payload(notice: &Notice) -> Value {
+    let category = Category::from_str_tag(&notice.category);
+    let mut title: String = notice.title.chars().take(EMBED_TITLE_MAX).collect();
+    if notice.edited {
+        title = format!("[수정됨] {}", title).chars().take(EMBED_TITLE_MAX).collect();
+    }
+    json!({
+        "allowed_mentions": { "parse": [] },
+        "embeds": [{
+            "title": title,
+            "url": notice.url,
+            "color": EMBED_COLOR,
+            "author": { "name": notice.source_display_name },
+            "fields": [
+                { "name": "분류", "value": format!("{} {}", category.emoji(), category.label()), "inline": true },
+                { "name": "게시일", "value": notice.published.as_deref().unwrap_or("날짜 미상"), "inline": true },
+                { "name": "작성자", "value": notice.author.as_deref().unwrap_or("작성자 미상"), "inline": true },
+            ],
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_payload() {
+        let notice = Notice {
+            id: 1,
+            source_key: "biz".into(),
+            notice_id: "42".into(),
+            title: "2026학년도 @everyone 장학금 신청 안내".into(),
+            url: "https://biz.chungbuk.ac.kr/view?no=42".into(),
+            author: Some("학생지원팀".into()),
+            category: "scholarship".into(),
+            published: Some("2026-03-02".into()),
+            source_display_name: "경영학부".into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        };
+        let payload = embed_payload(&notice);
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "2026학년도 @everyone 장학금 신청 안내");
+        assert_eq!(embed["url"], "https://biz.chungbuk.ac.kr/view?no=42");
+        assert_eq!(embed["author"]["name"], "경영학부");
+        assert_eq!(embed["fields"][1]["value"], "2026-03-02");
+        assert_eq!(embed["fields"][2]["value"], "학생지원팀");
+        assert!(payload["allowed_mentions"]["parse"].as_array().unwrap().is_empty());
+
+        let long = Notice {
+            title: "가".repeat(300),
+            published: None,
+            edited: true,
+            ..notice
+        };
+        let embed = &embed_payload(&long)["embeds"][0];
+        let title = embed["title"].as_str().unwrap();
+        assert_eq!(title.chars().count(), EMBED_TITLE_MAX);
+        assert!(title.starts_with("[수정됨] "));
+        assert_eq!(embed["fields"][1]["value"], "날짜 미상");
+    }
+}
@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+
 use teloxide::prelude::*;
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
 use tokio::time::{sleep, Duration};
 
 use crate::category::Category;
 use crate::db::{Database, Notice};
+use crate::format::{html_escape, render_template, split_message, Markup, MESSAGE_MAX_CHARS};
+use crate::kst;
+use crate::lang;
+use crate::throttle::RateLimiter;
 
 /// DM 매칭 + 발송 엔진.
 /// 크롤링 후 새 공지를 구독자에게 개인 DM으로 전달한다.
@@ -11,119 +17,416 @@ pub struct DmEngine<'a> {
     bot: &'a Bot,
     db: &'a Database,
     delay_ms: u64,
+    /// `[templates] dm_template`. 없으면 기본 레이아웃.
+    template: Option<String>,
+    /// 채널 발송과 공유하는 전역 속도 제한.
+    limiter: Option<&'a RateLimiter>,
+    /// 소스 키 → 설정된 언어 (`[[source]] lang`).
+    source_langs: HashMap<String, String>,
+    /// DM 매칭 대상 기간 (시간)과 최대 공지 수.
+    lookback_hours: u32,
+    scan_limit: usize,
+    /// 사용자별 하루 최대 개별 DM 수 (`bot.max_dms_per_user_per_day`). 0이면 제한 없음.
+    daily_cap: u32,
+    /// 키워드를 본문에서도 찾는다 (`bot.match_body`).
+    match_body: bool,
+    /// DM에 "확인" 버튼을 붙인다. 콜백을 받는 봇으로 보낼 때만 켠다.
+    dismiss_button: bool,
+}
+
+/// 일일 한도를 넘겨 미뤄 둔 공지를 요약으로 보내는 시각 (KST, 시).
+const DM_SUMMARY_HOUR: u32 = 21;
+
+/// `now` 이전(같은 시각 포함) 가장 최근의 요약 시각. 그 전에 미룬 매칭이 요약 대상이다.
+fn summary_cutoff(now: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+    let time = chrono::NaiveTime::from_hms_opt(DM_SUMMARY_HOUR, 0, 0).unwrap_or(chrono::NaiveTime::MIN);
+    let slot = now.date().and_time(time);
+    if slot > now {
+        slot - chrono::Duration::days(1)
+    } else {
+        slot
+    }
+}
+
+/// DM 발송 실패 사유 (`classify_dm_error`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmFailure {
+    /// 사용자가 봇을 차단했거나 계정이 삭제됨 (403).
+    Blocked,
+    /// 대화방 없음 (봇을 시작한 적 없거나 잘못된 ID).
+    ChatNotFound,
+    Other,
+}
+
+impl DmFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DmFailure::Blocked => "blocked",
+            DmFailure::ChatNotFound => "chat_not_found",
+            DmFailure::Other => "other",
+        }
+    }
+}
+
+/// 텔레그램 에러 문자열로 실패 사유 분류.
+/// 봇과 대화를 시작한 적 없는 사용자("can't initiate conversation")는 차단이 아니다
+/// (DM 전용 봇을 아직 `/start`하지 않은 경우) → 비활성화하지 않도록 `ChatNotFound`.
+pub(crate) fn classify_dm_error(message: &str) -> DmFailure {
+    let lower = message.to_lowercase();
+    if lower.contains("can't initiate conversation") {
+        DmFailure::ChatNotFound
+    } else if lower.contains("forbidden")
+        || lower.contains("bot was blocked")
+        || lower.contains("user is deactivated")
+    {
+        DmFailure::Blocked
+    } else if lower.contains("chat not found") {
+        DmFailure::ChatNotFound
+    } else {
+        DmFailure::Other
+    }
+}
+
+/// 한 사이클의 DM 발송 결과.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DmOutcome {
+    pub sent: u32,
+    pub blocked: u32,
+    pub chat_not_found: u32,
+    pub other: u32,
+    /// 차단으로 비활성화한 사용자 수.
+    pub deactivated: u32,
+    /// 일일 한도를 넘어 요약으로 미룬 매칭 수.
+    pub deferred: u32,
+    /// 보낸 일일 요약 메시지 수.
+    pub summaries: u32,
+}
+
+impl DmOutcome {
+    fn record_failure(&mut self, reason: DmFailure) {
+        match reason {
+            DmFailure::Blocked => self.blocked += 1,
+            DmFailure::ChatNotFound => self.chat_not_found += 1,
+            DmFailure::Other => self.other += 1,
+        }
+    }
+
+    pub fn failed(&self) -> u32 {
+        self.blocked + self.chat_not_found + self.other
+    }
 }
 
 /// DM 매칭 결과.
 struct DmMatch {
     telegram_id: i64,
     match_type: String,  // "keyword" or "source"
-    match_value: String,
+    /// 걸린 키워드 전부 (구독 순서), 학과 매칭이면 소스 키 하나.
+    match_values: Vec<String>,
 }
 
 impl<'a> DmEngine<'a> {
     pub fn new(bot: &'a Bot, db: &'a Database, delay_ms: u64) -> Self {
-        Self { bot, db, delay_ms }
+        Self {
+            bot,
+            db,
+            delay_ms,
+            template: None,
+            limiter: None,
+            source_langs: HashMap::new(),
+            lookback_hours: 24,
+            scan_limit: 100,
+            daily_cap: 0,
+            match_body: false,
+            dismiss_button: true,
+        }
+    }
+
+    /// "확인" 버튼 여부. DM 전용 봇은 콜백을 받는 디스패처가 없어서 끈다.
+    pub fn with_dismiss_button(mut self, enabled: bool) -> Self {
+        self.dismiss_button = enabled;
+        self
+    }
+
+    /// 사용자별 하루 최대 DM 수. 넘는 매칭은 그날 저녁 요약 한 통으로 보낸다.
+    pub fn with_daily_cap(mut self, cap: u32) -> Self {
+        self.daily_cap = cap;
+        self
+    }
+
+    /// 키워드 매칭에 본문 포함 여부 (`bot.match_body`).
+    pub fn with_match_body(mut self, match_body: bool) -> Self {
+        self.match_body = match_body;
+        self
+    }
+
+    /// DM 매칭 대상 기간과 최대 공지 수 (`bot.dm_lookback_hours`, `bot.dm_scan_limit`).
+    pub fn with_lookback(mut self, hours: u32, limit: usize) -> Self {
+        self.lookback_hours = hours;
+        self.scan_limit = limit;
+        self
+    }
+
+    /// 소스별 언어 지정. 없는 소스는 DB에 저장된 값(제목 추정)을 쓴다.
+    pub fn with_source_langs(mut self, source_langs: HashMap<String, String>) -> Self {
+        self.source_langs = source_langs;
+        self
+    }
+
+    /// 전역 발송 속도 제한 지정 (`Notifier::rate_limiter`).
+    pub fn with_rate_limiter(mut self, limiter: &'a RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// DM 메시지 템플릿 지정.
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
     }
 
     /// 최근 공지에 대해 구독 매칭 → DM 발송.
-    /// 반환: 발송된 DM 수.
-    pub async fn process(&self) -> anyhow::Result<u32> {
-        // 최근 24시간 이내 공지 (이미 채널에 전송된 것들)
-        let notices = self.db.get_recent_for_dm(100)?;
-        if notices.is_empty() {
-            return Ok(0);
+    /// 반환: 발송 수와 사유별 실패 수.
+    pub async fn process(&self) -> anyhow::Result<DmOutcome> {
+        // 최근 `lookback_hours` 이내 공지 (이미 채널에 전송된 것들). 보낸 여부는 dm_log로 판단
+        let notices = self
+            .db
+            .get_recent_for_dm(chrono::Duration::hours(self.lookback_hours as i64), self.scan_limit)?;
+        let mut outcome = DmOutcome::default();
+        if !notices.is_empty() {
+            self.deliver(&notices, &mut outcome).await?;
         }
+        outcome.summaries = self.send_due_summaries().await?;
 
-        // 전체 구독 데이터 로드
-        let keyword_subs = self.db.get_all_keyword_subs()?;
+        if outcome.sent > 0 || outcome.failed() > 0 || outcome.deferred > 0 || outcome.summaries > 0 {
+            tracing::info!(
+                count = outcome.sent,
+                failed = outcome.failed(),
+                deactivated = outcome.deactivated,
+                deferred = outcome.deferred,
+                summaries = outcome.summaries,
+                "DM delivery complete"
+            );
+        }
 
-        let mut total_sent = 0u32;
+        Ok(outcome)
+    }
 
-        for notice in &notices {
-            let matches = self.find_matches(notice, &keyword_subs)?;
+    /// 매칭된 구독자에게 개별 DM 발송 (일일 한도를 넘는 매칭은 요약으로 미룬다).
+    async fn deliver(&self, notices: &[Notice], outcome: &mut DmOutcome) -> anyhow::Result<()> {
+        // 전체 구독 데이터 로드
+        let keyword_subs = self.db.get_all_keyword_subs()?;
+        let user_langs = self.db.get_user_langs()?;
 
-            for dm_match in &matches {
-                // 이미 보냈으면 스킵
-                if self.db.is_dm_sent(notice.id, dm_match.telegram_id)? {
+        let mut pending: Vec<PendingDm> = Vec::new();
+        for notice in notices {
+            let notice_lang = self.notice_lang(notice)?;
+            for dm_match in self.find_matches(notice, &keyword_subs)? {
+                // 이미 보냈거나 사용자가 확인 처리했으면 스킵
+                if self.db.is_dm_sent(notice.id, dm_match.telegram_id)?
+                    || self.db.is_dismissed(notice.id, dm_match.telegram_id)?
+                    || self.db.is_dm_deferred(notice.id, dm_match.telegram_id)?
+                {
                     continue;
                 }
+                let lang_note = lang_note(
+                    user_langs.get(&dm_match.telegram_id).map(String::as_str),
+                    &notice_lang,
+                );
+                pending.push(PendingDm { notice, dm_match, lang_note });
+            }
+        }
+        // 선호 언어 공지를 먼저 보낸다 (나머지는 원래 순서 유지)
+        pending.sort_by_key(|p| p.lang_note.is_some());
+        let pending = self.apply_daily_cap(pending, outcome)?;
 
-                match self
-                    .send_dm(dm_match.telegram_id, notice, &dm_match.match_type, &dm_match.match_value)
-                    .await
-                {
-                    Ok(()) => {
-                        self.db.log_dm(
-                            notice.id,
-                            dm_match.telegram_id,
-                            &dm_match.match_type,
-                            Some(&dm_match.match_value),
-                        )?;
-                        total_sent += 1;
-                        tracing::debug!(
-                            telegram_id = dm_match.telegram_id,
-                            notice_id = %notice.notice_id,
-                            match_type = %dm_match.match_type,
-                            "DM sent"
-                        );
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            telegram_id = dm_match.telegram_id,
-                            error = %e,
-                            "DM send failed (user may have blocked bot)"
-                        );
-                        // 403 Forbidden → 사용자가 봇을 차단한 경우
-                        if e.to_string().contains("Forbidden") {
-                            let _ = self.db.deactivate_user(dm_match.telegram_id);
-                        }
+        let mut deactivated = std::collections::HashSet::new();
+
+        for PendingDm { notice, dm_match, lang_note } in &pending {
+            let notice = *notice;
+            match self
+                .send_dm(
+                    dm_match.telegram_id,
+                    notice,
+                    &dm_match.match_type,
+                    &dm_match.match_values,
+                    lang_note.as_deref(),
+                )
+                .await
+            {
+                Ok(()) => {
+                    self.db.log_dm(
+                        notice.id,
+                        dm_match.telegram_id,
+                        &dm_match.match_type,
+                        Some(&dm_match.match_values.join(", ")),
+                    )?;
+                    outcome.sent += 1;
+                    tracing::debug!(
+                        telegram_id = dm_match.telegram_id,
+                        notice_id = %notice.notice_id,
+                        match_type = %dm_match.match_type,
+                        "DM sent"
+                    );
+                }
+                Err(e) => {
+                    let reason = classify_dm_error(&e.to_string());
+                    tracing::warn!(
+                        telegram_id = dm_match.telegram_id,
+                        reason = reason.as_str(),
+                        error = %e,
+                        "DM send failed"
+                    );
+                    outcome.record_failure(reason);
+                    // 차단/탈퇴 → 이후 매칭에서 제외
+                    if reason == DmFailure::Blocked
+                        && deactivated.insert(dm_match.telegram_id)
+                        && self.db.deactivate_user(dm_match.telegram_id).is_ok()
+                    {
+                        outcome.deactivated += 1;
                     }
                 }
+            }
+
+            // Rate limit 준수
+            sleep(Duration::from_millis(self.delay_ms)).await;
+        }
 
-                // Rate limit 준수
-                sleep(Duration::from_millis(self.delay_ms)).await;
+        Ok(())
+    }
+
+    /// 오늘 이미 받은 DM과 이번 발송분을 합쳐 한도를 넘는 매칭은 요약 대기열로.
+    /// 반환: 지금 개별로 보낼 매칭.
+    fn apply_daily_cap<'n>(
+        &self,
+        pending: Vec<PendingDm<'n>>,
+        outcome: &mut DmOutcome,
+    ) -> anyhow::Result<Vec<PendingDm<'n>>> {
+        if self.daily_cap == 0 {
+            return Ok(pending);
+        }
+        let mut counts: HashMap<i64, u32> = HashMap::new();
+        let mut kept = Vec::with_capacity(pending.len());
+        for p in pending {
+            let telegram_id = p.dm_match.telegram_id;
+            let count = match counts.get_mut(&telegram_id) {
+                Some(count) => count,
+                None => counts.entry(telegram_id).or_insert(self.db.count_dms_today(telegram_id)?),
+            };
+            if *count >= self.daily_cap {
+                self.db.defer_dm(p.notice.id, telegram_id)?;
+                outcome.deferred += 1;
+            } else {
+                *count += 1;
+                kept.push(p);
             }
         }
+        Ok(kept)
+    }
 
-        if total_sent > 0 {
-            tracing::info!(count = total_sent, "DM delivery complete");
+    /// 요약 시각(`DM_SUMMARY_HOUR`)이 지났으면 그 전에 미뤄 둔 매칭을 사용자별 한 통으로.
+    /// 반환: 보낸 요약 수.
+    async fn send_due_summaries(&self) -> anyhow::Result<u32> {
+        let cutoff = summary_cutoff(kst::now().naive_local()).format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut sent = 0;
+        for (telegram_id, notices) in self.db.get_deferred_dms(&cutoff)? {
+            let text = summary_text(&notices);
+            let mut result = Ok(());
+            for part in split_message(&text, MESSAGE_MAX_CHARS) {
+                if let Some(limiter) = self.limiter {
+                    limiter.acquire().await;
+                }
+                let req = self
+                    .bot
+                    .send_message(ChatId(telegram_id), part)
+                    .parse_mode(Markup::Html.parse_mode())
+                    .link_preview_options(teloxide::types::LinkPreviewOptions {
+                        is_disabled: true,
+                        url: None,
+                        prefer_small_media: false,
+                        prefer_large_media: false,
+                        show_above_text: false,
+                    });
+                if let Err(e) = req.await {
+                    result = Err(e);
+                    break;
+                }
+            }
+            match result {
+                Ok(()) => {
+                    let ids: Vec<i64> = notices.iter().map(|n| n.id).collect();
+                    self.db.complete_deferred_dms(telegram_id, &ids)?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    let reason = classify_dm_error(&e.to_string());
+                    tracing::warn!(telegram_id, reason = reason.as_str(), error = %e, "DM summary failed");
+                    if reason == DmFailure::Blocked {
+                        // 받을 수 없는 사용자: 대기열만 비운다
+                        let _ = self.db.deactivate_user(telegram_id);
+                        let ids: Vec<i64> = notices.iter().map(|n| n.id).collect();
+                        self.db.complete_deferred_dms(telegram_id, &ids)?;
+                    }
+                }
+            }
+            sleep(Duration::from_millis(self.delay_ms)).await;
         }
+        Ok(sent)
+    }
 
-        Ok(total_sent)
+    /// 공지 언어: 소스 설정 → DB 저장값 → 제목 추정 순.
+    fn notice_lang(&self, notice: &Notice) -> anyhow::Result<String> {
+        if let Some(lang) = self.source_langs.get(&notice.source_key) {
+            return Ok(lang.clone());
+        }
+        Ok(self
+            .db
+            .get_notice_lang(notice.id)?
+            .unwrap_or_else(|| lang::detect(&notice.title).to_string()))
     }
 
-    /// 공지에 매칭되는 구독자 목록 수집.
+    /// 공지에 매칭되는 구독자 목록 수집. 사용자당 하나로, 키워드가 여럿 걸리면 모두 모은다.
     fn find_matches(
         &self,
         notice: &Notice,
         keyword_subs: &[(i64, String)],
     ) -> anyhow::Result<Vec<DmMatch>> {
         let mut matches: Vec<DmMatch> = Vec::new();
-        let mut seen_users = std::collections::HashSet::new();
-
-        let title_lower = notice.title.to_lowercase();
+        let mut seen_users: HashMap<i64, usize> = HashMap::new();
 
-        // 1. 키워드 매칭
+        // 1. 키워드 매칭 (기본은 제목[+본문], `author:` 접두사는 작성자)
         for (telegram_id, keyword) in keyword_subs {
-            if title_lower.contains(&keyword.to_lowercase()) {
-                if seen_users.insert(*telegram_id) {
+            if !keyword_matches(notice, keyword, self.match_body) {
+                continue;
+            }
+            match seen_users.get(telegram_id) {
+                Some(&i) => matches[i].match_values.push(keyword.clone()),
+                None => {
+                    seen_users.insert(*telegram_id, matches.len());
                     matches.push(DmMatch {
                         telegram_id: *telegram_id,
                         match_type: "keyword".to_string(),
-                        match_value: keyword.clone(),
+                        match_values: vec![keyword.clone()],
                     });
                 }
             }
         }
 
-        // 2. 소스(학과) 매칭
+        // 2. 소스(학과) 매칭 (카테고리 필터가 있으면 허용 카테고리만)
         let source_subscribers = self.db.get_source_subscribers(&notice.source_key)?;
+        let filters = self.db.get_source_sub_filters(&notice.source_key)?;
         for telegram_id in source_subscribers {
-            if seen_users.insert(telegram_id) {
+            if let Some(allowed) = filters.get(&telegram_id) {
+                if !allowed.contains(&notice.category) {
+                    continue;
+                }
+            }
+            // 키워드로 이미 걸린 사용자는 학과 매칭을 따로 보내지 않는다
+            if let std::collections::hash_map::Entry::Vacant(slot) = seen_users.entry(telegram_id) {
+                slot.insert(matches.len());
                 matches.push(DmMatch {
                     telegram_id,
                     match_type: "source".to_string(),
-                    match_value: notice.source_key.clone(),
+                    match_values: vec![notice.source_key.clone()],
                 });
             }
         }
@@ -137,58 +440,548 @@ impl<'a> DmEngine<'a> {
         telegram_id: i64,
         notice: &Notice,
         match_type: &str,
-        match_value: &str,
+        match_values: &[String],
+        lang_note: Option<&str>,
     ) -> anyhow::Result<()> {
         let category = Category::from_str_tag(&notice.category);
-        let match_label = match match_type {
-            "keyword" => format!("\u{1f50d} 키워드: {}", match_value),
-            "source" => format!("\u{1f3eb} 학과: {}", notice.source_display_name),
-            _ => String::new(),
-        };
+        let mut match_label = match_label(match_type, match_values, &notice.source_display_name);
+        if let Some(note) = lang_note {
+            match_label = format!("{} · {}", match_label, note);
+        }
 
-        let text = format!(
-            "{emoji} <b>{source}</b>\n\n\
-             {title}\n\n\
-             {match_label}\n\
-             \u{1f4c5} {date}",
-            emoji = category.emoji(),
-            source = html_escape(&notice.source_display_name),
-            title = html_escape(&notice.title),
-            match_label = html_escape(&match_label),
-            date = html_escape(notice.published.as_deref().unwrap_or("날짜 미상")),
-        );
+        let snippet = notice
+            .body
+            .as_deref()
+            .map(|b| format!("{}\n\n", html_escape(&body_snippet(b))))
+            .unwrap_or_default();
 
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url(
-            "\u{1f517} 원문 보기",
-            reqwest::Url::parse(&notice.url)?,
-        )]]);
+        let text = match &self.template {
+            // 사용자 템플릿: 치환 후 통째로 이스케이프, 매칭 사유는 항상 덧붙인다
+            Some(template) => format!(
+                "{}\n\n{}",
+                html_escape(&render_template(template, notice)),
+                html_escape(&match_label)
+            ),
+            None => format!(
+                "{emoji} {source}\n\n\
+                 {title}\n\n\
+                 {snippet}\
+                 {match_label}\n\
+                 \u{1f4c5} {date}",
+                emoji = category.emoji(),
+                source = Markup::Html.bold(&notice.source_display_name),
+                title = html_escape(&notice.title),
+                snippet = snippet,
+                match_label = html_escape(&match_label),
+                date = html_escape(notice.published.as_deref().unwrap_or("날짜 미상")),
+            ),
+        };
+
+        let mut buttons = vec![InlineKeyboardButton::url("\u{1f517} 원문 보기", reqwest::Url::parse(&notice.url)?)];
+        if self.dismiss_button {
+            buttons.push(InlineKeyboardButton::callback("\u{2714} 확인", format!("{}{}", DISMISS_PREFIX, notice.id)));
+        }
+        let keyboard = InlineKeyboardMarkup::new(vec![buttons]);
 
-        self.bot
-            .send_message(ChatId(telegram_id), &text)
-            .parse_mode(ParseMode::Html)
-            .reply_markup(keyboard)
-            .await
-            .map_err(|e| anyhow::anyhow!("DM failed: {}", e))?;
+        // 너무 길면 나눠 보내고 버튼은 마지막 조각에만
+        let parts = split_message(&text, MESSAGE_MAX_CHARS);
+        let last = parts.len() - 1;
+        for (i, part) in parts.into_iter().enumerate() {
+            if let Some(limiter) = self.limiter {
+                limiter.acquire().await;
+            }
+            let mut req = self
+                .bot
+                .send_message(ChatId(telegram_id), part)
+                .parse_mode(Markup::Html.parse_mode());
+            if i == last {
+                req = req.reply_markup(keyboard.clone());
+            }
+            req.await.map_err(|e| anyhow::anyhow!("DM failed: {}", e))?;
+        }
 
         Ok(())
     }
 }
 
-/// HTML 특수문자 이스케이프.
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
+/// 발송 대기 중인 DM 한 건.
+struct PendingDm<'n> {
+    notice: &'n Notice,
+    dm_match: DmMatch,
+    /// 사용자 선호 언어가 아닐 때 붙이는 표시.
+    lang_note: Option<String>,
+}
+
+/// 선호 언어(`/lang`)와 다른 공지에 붙일 표시. 선호가 없거나 같으면 `None`.
+pub(crate) fn lang_note(pref: Option<&str>, notice_lang: &str) -> Option<String> {
+    match pref {
+        Some(pref) if pref != notice_lang => Some(format!("\u{1f310} {}", lang::label(notice_lang))),
+        _ => None,
+    }
+}
+
+/// DM "확인" 버튼 callback_data 접두사 (뒤에 공지 DB id).
+pub(crate) const DISMISS_PREFIX: &str = "dismiss|";
+
+/// 작성자 대상 키워드 접두사 (`/sub author:학사과`).
+const AUTHOR_PREFIX: &str = "author:";
+
+/// 키워드 구독이 검사하는 필드.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeywordTarget {
+    Title,
+    Author,
+}
+
+/// 저장된 키워드 → (대상 필드, 검색어). 접두사가 없으면 제목.
+pub(crate) fn parse_keyword(raw: &str) -> (KeywordTarget, &str) {
+    match raw.strip_prefix(AUTHOR_PREFIX) {
+        Some(term) => (KeywordTarget::Author, term.trim()),
+        None => (KeywordTarget::Title, raw),
+    }
+}
+
+/// 저장용 키워드 형태: `author: 학사과` → `author:학사과`.
+pub(crate) fn normalize_keyword(raw: &str) -> String {
+    match parse_keyword(raw) {
+        (KeywordTarget::Author, term) => format!("{}{}", AUTHOR_PREFIX, term),
+        (KeywordTarget::Title, term) => term.to_string(),
+    }
+}
+
+/// 띄어쓰기를 무시하고 비교할 최소 키워드 길이 (공백 제외 문자 수).
+/// 짧은 키워드까지 붙여 비교하면 "장 학생"이 "장학"에 걸리는 식의 오탐이 생긴다.
+const COMPACT_MATCH_MIN_CHARS: usize = 3;
+
+/// 비교용 형태: 소문자 + 풀어쓴 한글 자모(NFD)를 완성형 음절로 합침.
+fn match_form(text: &str) -> String {
+    compose_hangul(&text.to_lowercase())
+}
+
+/// 조합형 한글 자모(초성+중성[+종성])를 완성형 음절로 합친다 (한글 부분의 NFC).
+/// macOS 등에서 넘어온 NFD 제목도 같은 글자로 비교되게 한다.
+fn compose_hangul(text: &str) -> String {
+    const S_BASE: u32 = 0xAC00;
+    const L_BASE: u32 = 0x1100;
+    const V_BASE: u32 = 0x1161;
+    const T_BASE: u32 = 0x11A7;
+    const V_COUNT: u32 = 21;
+    const T_COUNT: u32 = 28;
+
+    let mut out: Vec<char> = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let code = c as u32;
+        if let Some(&last) = out.last() {
+            let prev = last as u32;
+            // 초성 + 중성 → LV 음절
+            if (L_BASE..L_BASE + 19).contains(&prev) && (V_BASE..V_BASE + V_COUNT).contains(&code) {
+                let syllable = S_BASE + ((prev - L_BASE) * V_COUNT + (code - V_BASE)) * T_COUNT;
+                *out.last_mut().unwrap() = char::from_u32(syllable).unwrap_or(last);
+                continue;
+            }
+            // 종성 없는 음절 + 종성 → LVT 음절
+            if (S_BASE..S_BASE + 11172).contains(&prev)
+                && (prev - S_BASE).is_multiple_of(T_COUNT)
+                && (T_BASE + 1..T_BASE + T_COUNT).contains(&code)
+            {
+                *out.last_mut().unwrap() = char::from_u32(prev + (code - T_BASE)).unwrap_or(last);
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out.into_iter().collect()
+}
+
+/// 본문 매칭 시 검사할 최대 길이 (문자 수). 긴 본문 전체를 매번 정규화하지 않도록.
+const BODY_MATCH_MAX_CHARS: usize = 2000;
+
+/// 키워드가 공지에 맞는지 (대소문자 무시 부분 일치).
+/// 그대로 맞지 않으면 양쪽 공백을 모두 지우고 다시 비교한다 ("장 학 금" ↔ "장학금").
+/// 단, 키워드가 `COMPACT_MATCH_MIN_CHARS`자 이상일 때만.
+/// `match_body`면 제목 키워드를 본문 앞부분(`BODY_MATCH_MAX_CHARS`)에서도 찾는다.
+/// DM 발송과 `/preview`가 같은 판정을 쓰도록 이 함수 하나로 모은다.
+pub(crate) fn keyword_matches(notice: &Notice, keyword: &str, match_body: bool) -> bool {
+    let (target, term) = parse_keyword(keyword);
+    if term.is_empty() {
+        return false;
+    }
+    let haystack = match target {
+        KeywordTarget::Title => match notice.body.as_deref() {
+            Some(body) if match_body => {
+                let body: String = body.chars().take(BODY_MATCH_MAX_CHARS).collect();
+                format!("{}\n{}", notice.title, body)
+            }
+            _ => notice.title.clone(),
+        },
+        KeywordTarget::Author => match notice.author.as_deref() {
+            Some(a) => a.to_string(),
+            None => return false,
+        },
+    };
+    let (haystack, term) = (match_form(&haystack), match_form(term));
+    if haystack.contains(&term) {
+        return true;
+    }
+    let compact = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let term = compact(&term);
+    term.chars().count() >= COMPACT_MATCH_MIN_CHARS && compact(&haystack).contains(&term)
+}
+
+/// DM의 매칭 사유 줄. 여러 키워드는 한 줄에 모은다 ("🔍 키워드: 장학금, 등록금"),
+/// 작성자 키워드는 따로 ("✍️ 작성자: 학사과").
+fn match_label(match_type: &str, match_values: &[String], source_display_name: &str) -> String {
+    match match_type {
+        "keyword" => {
+            let (mut titles, mut authors) = (Vec::new(), Vec::new());
+            for value in match_values {
+                match parse_keyword(value) {
+                    (KeywordTarget::Author, term) => authors.push(term),
+                    (KeywordTarget::Title, term) => titles.push(term),
+                }
+            }
+            let mut parts = Vec::new();
+            if !titles.is_empty() {
+                parts.push(format!("\u{1f50d} 키워드: {}", titles.join(", ")));
+            }
+            if !authors.is_empty() {
+                parts.push(format!("\u{270d}\u{fe0f} 작성자: {}", authors.join(", ")));
+            }
+            parts.join(" · ")
+        }
+        "source" => format!("\u{1f3eb} 학과: {}", source_display_name),
+        _ => String::new(),
+    }
+}
+
+/// 일일 요약 DM 본문 (HTML): 미룬 공지 제목 링크 목록.
+fn summary_text(notices: &[Notice]) -> String {
+    let mut text = format!(
+        "\u{1f4ec} <b>오늘 더 온 알림 {}건</b>\n하루 알림 한도를 넘어 한 번에 모아 보냅니다.\n\n",
+        notices.len()
+    );
+    for notice in notices {
+        text.push_str(&format!(
+            "{} <a href=\"{}\">{}</a> — {}\n",
+            Category::from_str_tag(&notice.category).emoji(),
+            html_escape(&notice.url),
+            html_escape(&notice.title),
+            html_escape(&notice.source_display_name),
+        ));
+    }
+    text
+}
+
+/// DM에 넣을 본문 미리보기 길이 (문자 수).
+const SNIPPET_CHARS: usize = 200;
+
+/// 본문 앞부분만 잘라 미리보기 생성. 잘렸으면 말줄임표를 붙인다.
+pub(crate) fn body_snippet(body: &str) -> String {
+    let mut chars = body.chars();
+    let head: String = chars.by_ref().take(SNIPPET_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{}…", head.trim_end())
+    } else {
+        head
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::tests::make_notice;
+    use crate::parser::RawNotice;
+
+    /// 테스트용 공지. 필요한 필드만 struct update로 바꿔 쓴다.
+    fn notice(title: &str) -> Notice {
+        Notice {
+            id: 1,
+            source_key: "cbnu_main".into(),
+            notice_id: "1".into(),
+            title: title.into(),
+            url: "https://www.chungbuk.ac.kr/1".into(),
+            author: None,
+            category: "general".into(),
+            published: None,
+            source_display_name: "충북대 공지".into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        }
+    }
+
+    /// 방금 올라온 목록 항목 (`get_recent_days`로 다시 읽을 수 있게 날짜 없음).
+    fn raw(id: &str, title: &str) -> RawNotice {
+        RawNotice { date: None, original_date: None, ..make_notice(id, title) }
+    }
+
+    #[test]
+    fn test_classify_dm_error() {
+        let cases = [
+            ("A Telegram's error: Forbidden: bot was blocked by the user", DmFailure::Blocked),
+            ("Forbidden: user is deactivated", DmFailure::Blocked),
+            ("Bad Request: chat not found", DmFailure::ChatNotFound),
+            ("Bad Request: Chat not found", DmFailure::ChatNotFound),
+            ("Forbidden: bot can't initiate conversation with a user", DmFailure::ChatNotFound),
+            ("Too Many Requests: retry after 5", DmFailure::Other),
+            ("error sending request: operation timed out", DmFailure::Other),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(classify_dm_error(message), expected, "{}", message);
+        }
+        assert_eq!(DmFailure::ChatNotFound.as_str(), "chat_not_found");
+    }
+
+    #[test]
+    fn test_body_snippet() {
+        assert_eq!(body_snippet("짧은 본문"), "짧은 본문");
+        let long = "가".repeat(SNIPPET_CHARS + 10);
+        let snippet = body_snippet(&long);
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS + 1);
+        assert!(snippet.ends_with('…'));
+    }
+
+    #[test]
+    fn test_author_and_title_keyword_scopes() {
+        let db = Database::init(":memory:").unwrap();
+        let bot = Bot::new("0:test");
+        let engine = DmEngine::new(&bot, &db, 0);
+        let notice = Notice {
+            author: Some("학사과".into()),
+            category: "academic".into(),
+            ..notice("2026학년도 1학기 수강신청 일정")
+        };
+        let subs = vec![
+            (1, "author:학사과".to_string()), // 작성자 일치
+            (2, "학사과".to_string()),        // 제목에는 없음
+            (3, "수강신청".to_string()),      // 제목 일치
+            (4, "author:수강신청".to_string()), // 작성자에는 없음
+        ];
+
+        let matches = engine.find_matches(&notice, &subs).unwrap();
+        let ids: Vec<i64> = matches.iter().map(|m| m.telegram_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        // 작성자 정보가 없는 공지에는 작성자 키워드가 맞지 않는다
+        let anonymous = Notice { author: None, ..notice };
+        assert!(engine.find_matches(&anonymous, &subs[..1]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_multiple_keywords_in_one_dm() {
+        let db = Database::init(":memory:").unwrap();
+        let bot = Bot::new("0:test");
+        let engine = DmEngine::new(&bot, &db, 0);
+        let notice = Notice {
+            author: Some("학생과".into()),
+            category: "scholarship".into(),
+            ..notice("2026학년도 장학금 및 등록금 납부 안내")
+        };
+        let subs = vec![
+            (1, "장학금".to_string()),
+            (1, "채용".to_string()),
+            (2, "등록금".to_string()),
+            (1, "등록금".to_string()),
+            (1, "author:학생과".to_string()),
+        ];
+
+        let matches = engine.find_matches(&notice, &subs).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].telegram_id, 1);
+        assert_eq!(matches[0].match_values, ["장학금", "등록금", "author:학생과"]);
+        assert_eq!(
+            match_label(&matches[0].match_type, &matches[0].match_values, &notice.source_display_name),
+            "\u{1f50d} 키워드: 장학금, 등록금 · \u{270d}\u{fe0f} 작성자: 학생과"
+        );
+        assert_eq!(matches[1].match_values, ["등록금"]);
+
+        // 학과 구독자는 따로, 키워드로 이미 걸린 사용자는 한 번만
+        db.register_user(1, None, None).unwrap();
+        db.register_user(3, None, None).unwrap();
+        db.add_source_sub(1, "cbnu_main").unwrap();
+        db.add_source_sub(3, "cbnu_main").unwrap();
+        let matches = engine.find_matches(&notice, &subs).unwrap();
+        let ids: Vec<i64> = matches.iter().map(|m| m.telegram_id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(matches[2].match_type, "source");
+        assert_eq!(match_label("source", &matches[2].match_values, "충북대 공지"), "\u{1f3eb} 학과: 충북대 공지");
+    }
+
+    #[test]
+    fn test_spacing_and_nfd_insensitive_match() {
+        // 띄어쓰기가 섞인 제목, 띄어 쓴 키워드
+        assert!(keyword_matches(&notice("2026 장 학 금 신청 안내"), "장학금", false));
+        assert!(keyword_matches(&notice("국가장학 금 2차 신청"), "장학금", false));
+        assert!(keyword_matches(&notice("2026 장학금 신청 안내"), "장학 금", false));
+        assert!(keyword_matches(&notice("Global Buddy 모집"), "globalbuddy", false));
+
+        // 짧은 키워드는 단어 경계를 넘어 붙이지 않는다
+        assert!(!keyword_matches(&notice("기숙사 입사 장 학생 명단"), "장학", false));
+        assert!(keyword_matches(&notice("장학 안내"), "장학", false));
+
+        // 같은 글자의 NFD(자모 풀어쓰기) / NFC
+        let nfd = "\u{110c}\u{1161}\u{11bc}\u{1112}\u{1161}\u{11a8}\u{1100}\u{1173}\u{11b7}";
+        assert_eq!(compose_hangul(nfd), "장학금");
+        assert!(keyword_matches(&notice(&format!("2026 {} 신청", nfd)), "장학금", false));
+        assert!(keyword_matches(&notice("2026 장학금 신청"), nfd, false));
+        assert!(!keyword_matches(&notice("2026 장학금 신청"), "채용", false));
+    }
+
+    #[test]
+    fn test_body_match_requires_flag() {
+        let notice = Notice {
+            author: Some("학사과".into()),
+            body: Some("2026학년도 1학기 교내 장학금 신청 기간입니다.".into()),
+            ..notice("학부 안내")
+        };
+        // 본문에만 있는 키워드는 match_body일 때만
+        assert!(!keyword_matches(&notice, "장학금", false));
+        assert!(keyword_matches(&notice, "장학금", true));
+        assert!(keyword_matches(&notice, "학부 안내", true));
+        // 작성자 키워드는 본문을 보지 않는다
+        assert!(!keyword_matches(&notice, "author:장학금", true));
+
+        // 본문 뒤쪽(검사 길이 밖)은 보지 않는다
+        let long = Notice {
+            body: Some(format!("{}장학금", "가".repeat(BODY_MATCH_MAX_CHARS))),
+            ..notice.clone()
+        };
+        assert!(!keyword_matches(&long, "장학금", true));
+        let no_body = Notice { body: None, ..notice };
+        assert!(!keyword_matches(&no_body, "장학금", true));
+    }
 
     #[test]
-    fn test_html_escape() {
-        assert_eq!(html_escape("hello"), "hello");
-        assert_eq!(html_escape("<b>bold</b>"), "&lt;b&gt;bold&lt;/b&gt;");
-        assert_eq!(html_escape("A & B"), "A &amp; B");
+    fn test_daily_cap_defers_to_summary() {
+        let db = Database::init(":memory:").unwrap();
+        let bot = Bot::new("0:test");
+        db.register_user(1, None, None).unwrap();
+        for i in 1..=4 {
+            db.insert_if_new("biz", &raw(&i.to_string(), &format!("장학금 공지 {}", i)), "경영학부", false).unwrap();
+        }
+        let notices = db.get_recent_days(1, 10).unwrap();
+        let by_id = |id: i64| notices.iter().find(|n| n.id == id).unwrap();
+        let pending = |ids: &[i64]| -> Vec<PendingDm> {
+            ids.iter()
+                .map(|id| PendingDm {
+                    notice: by_id(*id),
+                    dm_match: DmMatch {
+                        telegram_id: 1,
+                        match_type: "keyword".into(),
+                        match_values: vec!["장학금".into()],
+                    },
+                    lang_note: None,
+                })
+                .collect()
+        };
+
+        // 오늘 이미 1건 받았고 한도 3: 이번에 2건까지, 세 번째(하루 4번째)는 요약으로
+        db.log_dm(1, 1, "keyword", Some("장학금")).unwrap();
+        let engine = DmEngine::new(&bot, &db, 0).with_daily_cap(3);
+        let mut outcome = DmOutcome::default();
+        let kept = engine.apply_daily_cap(pending(&[2, 3, 4]), &mut outcome).unwrap();
+        assert_eq!(kept.iter().map(|p| p.notice.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(outcome.deferred, 1);
+        assert!(db.is_dm_deferred(4, 1).unwrap());
+
+        // 요약 시각 이전에 미룬 것만 요약 대상. 보내면 대기열에서 빠지고 한도에는 안 센다
+        assert!(db.get_deferred_dms("2000-01-01 00:00:00").unwrap().is_empty());
+        let deferred = db.get_deferred_dms("9999-12-31 00:00:00").unwrap();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].0, 1);
+        assert_eq!(deferred[0].1[0].title, "장학금 공지 4");
+        assert!(summary_text(&deferred[0].1).contains("오늘 더 온 알림 1건"));
+        db.complete_deferred_dms(1, &[4]).unwrap();
+        assert!(!db.is_dm_deferred(4, 1).unwrap());
+        assert!(db.is_dm_sent(4, 1).unwrap());
+        assert_eq!(db.count_dms_today(1).unwrap(), 1);
+
+        // 한도 0은 제한 없음
+        let engine = DmEngine::new(&bot, &db, 0);
+        assert_eq!(engine.apply_daily_cap(pending(&[2, 3]), &mut outcome).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_summary_cutoff() {
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(summary_cutoff(at("2026-03-09 21:30:00")), at("2026-03-09 21:00:00"));
+        assert_eq!(summary_cutoff(at("2026-03-09 21:00:00")), at("2026-03-09 21:00:00"));
+        assert_eq!(summary_cutoff(at("2026-03-10 08:00:00")), at("2026-03-09 21:00:00"));
+    }
+
+    #[test]
+    fn test_lang_preference() {
+        let db = Database::init(":memory:").unwrap();
+        let bot = Bot::new("0:test");
+        db.insert_if_new("intl", &raw("1", "교환학생 모집"), "국제교류본부", false).unwrap();
+        db.insert_if_new("biz", &raw("1", "Career Fair 2026"), "경영학부", false).unwrap();
+        let notices = db.get_recent_days(1, 10).unwrap();
+        let by_source = |key: &str| notices.iter().find(|n| n.source_key == key).unwrap();
+
+        // 소스 설정이 없으면 제목 추정, 있으면 설정값
+        let engine = DmEngine::new(&bot, &db, 0);
+        assert_eq!(engine.notice_lang(by_source("intl")).unwrap(), "ko");
+        assert_eq!(engine.notice_lang(by_source("biz")).unwrap(), "en");
+        let engine = engine.with_source_langs(HashMap::from([("intl".to_string(), "en".to_string())]));
+        assert_eq!(engine.notice_lang(by_source("intl")).unwrap(), "en");
+
+        // 선호 언어와 다를 때만 표시가 붙는다
+        assert_eq!(lang_note(Some("en"), "en"), None);
+        assert_eq!(lang_note(Some("en"), "ko").as_deref(), Some("\u{1f310} 한국어"));
+        assert_eq!(lang_note(Some("ko"), "en").as_deref(), Some("\u{1f310} English"));
+        assert_eq!(lang_note(None, "en"), None);
+    }
+
+    #[test]
+    fn test_preview_uses_dm_predicate() {
+        let db = Database::init(":memory:").unwrap();
+        let bot = Bot::new("0:test");
+        let engine = DmEngine::new(&bot, &db, 0);
+        let base = Notice { author: Some("학생과".into()), ..notice("") };
+        let notices: Vec<Notice> = ["국가장학금 신청 안내", "SCHOLARSHIP Fair", "수강신청 일정", "교내 장학생 선발"]
+            .iter()
+            .map(|t| Notice { title: t.to_string(), ..base.clone() })
+            .collect();
+
+        for keyword in ["장학", "scholarship", "author:학생과", "author:학사과", "수강"] {
+            let subs = vec![(1, keyword.to_string())];
+            let dm: Vec<&str> = notices
+                .iter()
+                .filter(|n| !engine.find_matches(n, &subs).unwrap().is_empty())
+                .map(|n| n.title.as_str())
+                .collect();
+            let preview: Vec<&str> = notices
+                .iter()
+                .filter(|n| keyword_matches(n, keyword, false))
+                .map(|n| n.title.as_str())
+                .collect();
+            assert_eq!(dm, preview, "keyword {}", keyword);
+        }
+    }
+
+    #[test]
+    fn test_source_category_filter() {
+        let db = Database::init(":memory:").unwrap();
+        db.register_user(1, None, None).unwrap();
+        db.register_user(2, None, None).unwrap();
+        db.add_source_sub(1, "biz").unwrap();
+        db.add_source_sub(2, "biz").unwrap();
+        db.set_source_sub_categories(1, "biz", &["scholarship".into()]).unwrap();
+
+        let bot = Bot::new("0:test");
+        let engine = DmEngine::new(&bot, &db, 0);
+        let biz = |category: &str| Notice {
+            source_key: "biz".into(),
+            category: category.into(),
+            ..notice("캠퍼스 도로 보수공사 안내")
+        };
+
+        // 일반 공지: 장학만 허용한 사용자 1은 제외
+        let matches = engine.find_matches(&biz("general"), &[]).unwrap();
+        let ids: Vec<i64> = matches.iter().map(|m| m.telegram_id).collect();
+        assert_eq!(ids, vec![2]);
+
+        let matches = engine.find_matches(&biz("scholarship"), &[]).unwrap();
+        assert_eq!(matches.len(), 2);
     }
 }
@@ -6,6 +6,9 @@ pub enum AppError {
     #[error("HTTP: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("HTTP {status} from {url}")]
+    Status { status: reqwest::StatusCode, url: String },
+
     #[error("Parse [{source_key}]: {detail}")]
     Parse { source_key: String, detail: String },
 
@@ -22,6 +25,41 @@ pub enum AppError {
     Io(#[from] std::io::Error),
 }
 
+impl AppError {
+    /// 잠깐 뒤 다시 하면 될 수 있는 오류인지: 타임아웃·연결 실패·5xx·429.
+    /// 404 같은 4xx나 파싱 오류는 몇 번을 다시 해도 같으므로 `false`.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Http(e) => retryable_http(e),
+            AppError::Status { status, .. } => retryable_status(*status),
+            AppError::Io(_) => true,
+            AppError::Parse { .. } | AppError::Db(_) | AppError::Telegram(_) | AppError::Config(_) => false,
+        }
+    }
+}
+
+fn retryable_http(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_body() || e.is_request() || e.status().is_some_and(retryable_status)
+}
+
+fn retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+}
+
+/// `anyhow`로 감싼 오류의 재시도 여부. `AppError`나 `reqwest::Error`가 아니면
+/// (파싱 실패 등) 재시도하지 않는다.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    if let Some(e) = err.downcast_ref::<AppError>() {
+        return e.is_retryable();
+    }
+    if let Some(e) = err.downcast_ref::<reqwest::Error>() {
+        return retryable_http(e);
+    }
+    false
+}
+
 impl From<teloxide::RequestError> for AppError {
     fn from(e: teloxide::RequestError) -> Self {
         AppError::Telegram(e.to_string())
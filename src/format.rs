@@ -0,0 +1,428 @@
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use teloxide::types::ParseMode;
+use teloxide::{ApiError, RequestError};
+
+use crate::category::Category;
+use crate::db::Notice;
+
+/// 메시지 마크업 종류. 채널은 MarkdownV2, DM/목록은 HTML을 쓴다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Markup {
+    MarkdownV2,
+    Html,
+}
+
+impl Markup {
+    pub fn parse_mode(self) -> ParseMode {
+        match self {
+            Markup::MarkdownV2 => ParseMode::MarkdownV2,
+            Markup::Html => ParseMode::Html,
+        }
+    }
+
+    /// 일반 텍스트를 이 마크업에 맞게 이스케이프.
+    pub fn escape(self, text: &str) -> String {
+        match self {
+            Markup::MarkdownV2 => escape_markdown(text),
+            Markup::Html => html_escape(text),
+        }
+    }
+
+    /// DB 기록용 이름 (`from_name`과 짝).
+    pub fn name(self) -> &'static str {
+        match self {
+            Markup::MarkdownV2 => "markdown_v2",
+            Markup::Html => "html",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "markdown_v2" => Some(Markup::MarkdownV2),
+            "html" => Some(Markup::Html),
+            _ => None,
+        }
+    }
+
+    /// 굵게 (내용은 이스케이프).
+    pub fn bold(self, text: &str) -> String {
+        match self {
+            Markup::MarkdownV2 => format!("*{}*", escape_markdown(text)),
+            Markup::Html => format!("<b>{}</b>", html_escape(text)),
+        }
+    }
+}
+
+/// 채널 공지 메시지 본문.
+pub fn channel_text(notice: &Notice, markup: Markup) -> String {
+    let category = Category::from_str_tag(&notice.category);
+    let cat_tag = if notice.category != "general" {
+        format!("[{}] ", category.label())
+    } else {
+        String::new()
+    };
+    let edited = if notice.edited { "\u{270f}\u{fe0f} 수정됨 " } else { "" };
+
+    format!(
+        "{emoji} {source}\n\n{edited}{cat}{title}\n\n\u{1f4c5} {date} {sep} \u{270d}\u{fe0f} {author}",
+        emoji = category.emoji(),
+        source = markup.bold(&notice.source_display_name),
+        edited = markup.escape(edited),
+        cat = markup.escape(&cat_tag),
+        title = markup.escape(&notice.title),
+        date = markup.escape(notice.published.as_deref().unwrap_or("날짜 미상")),
+        sep = markup.escape("|"),
+        author = markup.escape(notice.author.as_deref().unwrap_or("작성자 미상")),
+    )
+}
+
+/// 긴급 공지(`Notice::urgent`) 채널 메시지 머리말.
+pub fn urgent_header(markup: Markup) -> String {
+    format!("\u{1f6a8} {}\n\n", markup.bold("긴급 공지"))
+}
+
+/// 메시지 템플릿(`[templates]`)에서 쓸 수 있는 자리표시자.
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &["title", "source", "date", "author", "category", "url"];
+
+static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").unwrap());
+
+/// 사용자 템플릿을 공지 값으로 채운다. 결과는 평문이므로 보낼 때 `Markup::escape`를
+/// 통째로 거친다. 모르는 자리표시자는 그대로 남긴다.
+pub fn render_template(template: &str, notice: &Notice) -> String {
+    let category = Category::from_str_tag(&notice.category);
+    PLACEHOLDER_RE
+        .replace_all(template, |caps: &Captures| match &caps[1] {
+            "title" => notice.title.clone(),
+            "source" => notice.source_display_name.clone(),
+            "date" => notice.published.clone().unwrap_or_else(|| "날짜 미상".into()),
+            "author" => notice.author.clone().unwrap_or_else(|| "작성자 미상".into()),
+            "category" => format!("{} {}", category.emoji(), category.label()),
+            "url" => notice.url.clone(),
+            _ => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// 템플릿에 들어 있는 알 수 없는 자리표시자 (config 검증용).
+pub fn unknown_placeholders(template: &str) -> Vec<String> {
+    PLACEHOLDER_RE
+        .captures_iter(template)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !TEMPLATE_PLACEHOLDERS.contains(&name.as_str()))
+        .collect()
+}
+
+/// 텔레그램 메시지 최대 길이 (문자).
+pub const MESSAGE_MAX_CHARS: usize = 4096;
+
+/// 긴 메시지를 `limit`자 이하 조각으로 나눈다. 줄 경계에서 자르고, 한 줄이
+/// `limit`보다 길 때만 그 줄 안에서 자른다 (`cut_point` 참고).
+/// 조각 경계의 개행은 버린다. 항상 한 조각 이상 반환.
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+    split_message_as(text, limit, Markup::Html)
+}
+
+/// `split_message`와 같되 `markup`에 맞는 곳에서만 자른다. MarkdownV2는 `\`와
+/// 그 뒤 문자 사이를 자르지 않는다.
+pub fn split_message_as(text: &str, limit: usize, markup: Markup) -> Vec<String> {
+    if text.chars().count() <= limit {
+        return vec![text.to_string()];
+    }
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in text.split('\n') {
+        let mut rest = line;
+        let mut line_len = line.chars().count();
+        let sep = usize::from(!current.is_empty());
+        if current_len + sep + line_len <= limit {
+            if sep == 1 {
+                current.push('\n');
+            }
+            current.push_str(line);
+            current_len += sep + line_len;
+            continue;
+        }
+        if !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+        while line_len > limit {
+            let cut = match markup {
+                Markup::Html => cut_point(rest, limit),
+                Markup::MarkdownV2 => markdown_cut_point(rest, limit),
+            };
+            parts.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+            line_len = rest.chars().count();
+        }
+        current.push_str(rest);
+        current_len = line_len;
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// 긴 줄을 자를 바이트 위치. HTML 태그나 엔티티 중간은 피하고, 앞 `limit`자 안에서
+/// 열린 태그가 모두 닫힌 마지막 `>`나 공백 뒤를 고른다. 그런 곳이 없으면 태그/엔티티
+/// 밖의 마지막 지점, 그것도 없으면 글자 단위로 자른다.
+fn cut_point(line: &str, limit: usize) -> usize {
+    let hard = line.char_indices().nth(limit).map_or(line.len(), |(i, _)| i);
+    let (mut in_tag, mut closing, mut in_entity) = (false, false, false);
+    let mut depth = 0usize;
+    let mut balanced = None;
+    let mut outside = None;
+    for (i, ch) in line[..hard].char_indices() {
+        let next = i + ch.len_utf8();
+        match ch {
+            '<' => {
+                in_tag = true;
+                in_entity = false;
+                closing = line[next..].starts_with('/');
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                if closing {
+                    depth = depth.saturating_sub(1);
+                } else {
+                    depth += 1;
+                }
+                outside = Some(next);
+                if depth == 0 {
+                    balanced = Some(next);
+                }
+            }
+            '&' if !in_tag => in_entity = true,
+            ';' if in_entity => {
+                in_entity = false;
+                outside = Some(next);
+            }
+            c if c.is_whitespace() && !in_tag => {
+                in_entity = false;
+                outside = Some(next);
+                if depth == 0 {
+                    balanced = Some(next);
+                }
+            }
+            _ => {}
+        }
+    }
+    balanced.or(outside).unwrap_or(hard)
+}
+
+/// MarkdownV2 줄을 자를 바이트 위치. 앞 `limit`자 안의 마지막 공백 뒤를 고르고, 없으면
+/// 글자 단위로 자르되 이스케이프하는 `\` 바로 뒤는 피한다 (뒤 조각이 파싱되지 않는다).
+fn markdown_cut_point(line: &str, limit: usize) -> usize {
+    let hard = line.char_indices().nth(limit).map_or(line.len(), |(i, _)| i);
+    let mut escaping = false;
+    let mut space = None;
+    let mut safe = 0;
+    for (i, ch) in line[..hard].char_indices() {
+        let next = i + ch.len_utf8();
+        if escaping {
+            escaping = false;
+        } else if ch == '\\' {
+            escaping = true;
+            continue;
+        } else if ch.is_whitespace() {
+            space = Some(next);
+        }
+        safe = next;
+    }
+    // 한 글자도 못 넣으면(`\` 한 글자 한도) 진행을 위해 이스케이프 쌍째로
+    let safe = if safe == 0 { line.char_indices().nth(2).map_or(line.len(), |(i, _)| i) } else { safe };
+    space.unwrap_or(safe)
+}
+
+/// 텔레그램이 마크업을 해석하지 못해 거절한 경우 ("can't parse entities").
+pub fn is_parse_error(err: &RequestError) -> bool {
+    matches!(err, RequestError::Api(ApiError::CantParseEntities(_)))
+}
+
+/// Escape special characters for Telegram MarkdownV2 format.
+/// 백슬래시 자체도 이스케이프해야 뒤 문자와 엮이지 않는다.
+pub fn escape_markdown(text: &str) -> String {
+    let special_chars = [
+        '\\', '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.',
+        '!',
+    ];
+    let mut escaped = String::with_capacity(text.len() * 2);
+    for ch in text.chars() {
+        if special_chars.contains(&ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// HTML 특수문자 이스케이프 (속성값에도 쓸 수 있도록 따옴표 포함).
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_message_boundary() {
+        // 정확히 한도면 그대로 한 조각
+        let exact = format!("{}\n{}", "가".repeat(5), "나".repeat(4));
+        assert_eq!(split_message(&exact, 10), vec![exact.clone()]);
+
+        // 한 글자 넘으면 줄 경계에서 나뉜다
+        let over = format!("{}\n{}", "가".repeat(5), "나".repeat(5));
+        assert_eq!(split_message(&over, 10), vec!["가".repeat(5), "나".repeat(5)]);
+
+        // 여러 줄은 한도 안에서 최대한 모은다
+        let lines = "공지 하나\n공지 둘\n공지 셋\n공지 넷";
+        let parts = split_message(lines, 12);
+        assert_eq!(parts, vec!["공지 하나\n공지 둘", "공지 셋\n공지 넷"]);
+
+        // 한 줄이 한도보다 길면 글자 단위로 (한글이 깨지지 않는다)
+        let long = "장학금".repeat(4);
+        let parts = split_message(&long, 5);
+        assert_eq!(parts, vec!["장학금장학", "금장학금장", "학금"]);
+        assert!(parts.iter().all(|p| p.chars().count() <= 5));
+        assert_eq!(parts.concat(), long);
+
+        assert_eq!(split_message("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_split_message_keeps_html_intact() {
+        // 링크가 잔뜩 붙은 긴 한 줄: 태그/엔티티 중간에서 자르지 않는다
+        let link = "<a href=\"https://cse.chungbuk.ac.kr/view?id=1&amp;p=2\">공지 &amp; 안내</a>";
+        let line = [link; 6].join(" ");
+        let parts = split_message(&line, 150);
+        assert!(parts.len() > 1);
+        for part in &parts {
+            assert!(part.chars().count() <= 150);
+            let part = part.trim();
+            assert!(part.starts_with("<a ") && part.ends_with("</a>"), "{part}");
+            assert_eq!(part.matches("<a ").count(), part.matches("</a>").count());
+        }
+        assert_eq!(parts.concat(), line);
+
+        // 닫히지 않은 태그 안쪽만 있으면 태그 밖 마지막 지점에서
+        let bold = format!("<b>{}</b>", "가 ".repeat(10));
+        let parts = split_message(&bold, 12);
+        assert!(parts.iter().all(|p| p.rfind('<') <= p.rfind('>')));
+        assert_eq!(parts.concat(), bold);
+    }
+
+    #[test]
+    fn test_split_markdown_keeps_escapes() {
+        // 공백 없는 긴 줄: `\.` 사이에서 자르지 않는다
+        let line = escape_markdown(&"a.".repeat(20));
+        for limit in 2..10 {
+            let parts = split_message_as(&line, limit, Markup::MarkdownV2);
+            assert_eq!(parts.concat(), line);
+            for part in &parts {
+                assert!(part.chars().count() <= limit);
+                let trailing = part.chars().rev().take_while(|c| *c == '\\').count();
+                assert_eq!(trailing % 2, 0, "{part}");
+            }
+        }
+
+        // 공백이 있으면 공백 뒤에서
+        let line = format!("{} {}", escape_markdown("2026.03.01"), escape_markdown("수강신청 안내!"));
+        let parts = split_message_as(&line, 16, Markup::MarkdownV2);
+        assert_eq!(parts[0], "2026\\.03\\.01 ");
+        assert_eq!(parts.concat(), line);
+        assert_eq!(Markup::from_name(Markup::MarkdownV2.name()), Some(Markup::MarkdownV2));
+        assert_eq!(Markup::from_name(Markup::Html.name()), Some(Markup::Html));
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("hello"), "hello");
+        assert_eq!(escape_markdown("test_var"), "test\\_var");
+        assert_eq!(escape_markdown("[학사]"), "\\[학사\\]");
+        assert_eq!(
+            escape_markdown("2026.02.01 | author"),
+            "2026\\.02\\.01 \\| author"
+        );
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("hello"), "hello");
+        assert_eq!(html_escape("<b>bold</b>"), "&lt;b&gt;bold&lt;/b&gt;");
+        assert_eq!(html_escape("A & B"), "A &amp; B");
+    }
+
+    fn sample_notice() -> Notice {
+        Notice {
+            id: 1,
+            source_key: "biz".into(),
+            notice_id: "42".into(),
+            title: "국가장학금 <2차> 신청".into(),
+            url: "https://biz.chungbuk.ac.kr/?pidx=42".into(),
+            author: None,
+            category: "scholarship".into(),
+            published: Some("2026-02-06".into()),
+            source_display_name: "경영학부".into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let notice = sample_notice();
+        let text = render_template("[{source}] {title} ({date}, {author})\n{url}", &notice);
+        assert_eq!(
+            text,
+            "[경영학부] 국가장학금 <2차> 신청 (2026-02-06, 작성자 미상)\nhttps://biz.chungbuk.ac.kr/?pidx=42"
+        );
+        assert!(render_template("{category}", &notice).ends_with("장학"));
+
+        // 치환 후 이스케이프: 제목의 <, 템플릿의 . 모두 안전하게
+        assert_eq!(
+            Markup::Html.escape(&render_template("<{title}>", &notice)),
+            "&lt;국가장학금 &lt;2차&gt; 신청&gt;"
+        );
+        assert_eq!(Markup::MarkdownV2.escape(&render_template("{date}.", &notice)), "2026\\-02\\-06\\.");
+    }
+
+    #[test]
+    fn test_template_missing_placeholder() {
+        let notice = sample_notice();
+        // 모르는 자리표시자는 그대로 남고, 검증에서 걸러진다
+        assert_eq!(render_template("{title} {views}", &notice), "국가장학금 <2차> 신청 {views}");
+        assert_eq!(unknown_placeholders("{title} {views} {Title} {url}"), vec!["views"]);
+        assert!(unknown_placeholders("{source}: {title}").is_empty());
+    }
+
+    #[test]
+    fn test_pathological_title_both_escapers() {
+        let title = r"C:\temp\ *[긴급]* <안내> a_b \\ `x` & (끝\";
+
+        // MarkdownV2: 특수문자는 전부 앞에 백슬래시가 하나 붙어 있어야 한다
+        let md = escape_markdown(title);
+        let mut chars = md.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\\' {
+                assert!(chars.next().is_some(), "dangling backslash in {md}");
+            } else {
+                assert!(!"_*[]()~`>#+-=|{}.!".contains(ch), "unescaped {ch:?} in {md}");
+            }
+        }
+        assert!(md.ends_with("\\\\"));
+
+        // HTML: 태그/엔티티로 해석될 문자가 남지 않는다
+        let html = html_escape(title);
+        assert!(!html.contains('<') && !html.contains('>'));
+        assert_eq!(html.matches('&').count(), html.matches("&amp;").count() + 2);
+        assert!(html.contains("&lt;안내&gt;"));
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+
+/// 한국 표준시 (UTC+9, 서머타임 없음).
+pub fn kst() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).unwrap()
+}
+
+/// 현재 KST 시각.
+pub fn now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&kst())
+}
+
+/// 오늘 날짜 (KST 기준). "오늘" 경계는 항상 이 값을 쓴다.
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+/// 시각을 KST "YYYY-MM-DD HH:MM:SS" 문자열로. DB에 저장하는 모든 시각은 이 형식이라
+/// 문자열 비교가 곧 시간 비교가 된다.
+pub fn to_sqlite(at: DateTime<Utc>) -> String {
+    at.with_timezone(&kst()).format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 현재 시각 (KST, SQLite 비교용 문자열).
+pub fn now_sqlite() -> String {
+    to_sqlite(Utc::now())
+}
+
+/// 현재로부터 `ago` 전 시각 (KST, SQLite 비교용 문자열). 최근 N일/N시간 윈도우에 사용.
+pub fn sqlite_ago(ago: Duration) -> String {
+    to_sqlite(Utc::now() - ago)
+}
+
+/// DB에 저장된 KST 시각 문자열을 "3분 전", "2시간 전" 같은 상대 표현으로.
+/// 해석할 수 없으면 원문 그대로.
+pub fn humanize_ago(ts: &str) -> String {
+    let Ok(at) = chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S") else {
+        return ts.to_string();
+    };
+    let secs = (now().naive_local() - at).num_seconds().max(0);
+    match secs {
+        0..=59 => "방금".to_string(),
+        60..=3599 => format!("{}분 전", secs / 60),
+        3600..=86_399 => format!("{}시간 전", secs / 3600),
+        _ => format!("{}일 전", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_kst_day_boundary() {
+        // 23:30 KST = 14:30 UTC. 같은 날로 저장된다.
+        let late = Utc.with_ymd_and_hms(2026, 3, 10, 14, 30, 0).unwrap();
+        assert_eq!(to_sqlite(late), "2026-03-10 23:30:00");
+        assert_eq!(late.with_timezone(&kst()).date_naive().to_string(), "2026-03-10");
+
+        // 00:30 KST = 전날 15:30 UTC. UTC로는 전날이지만 KST로는 다음 날.
+        let after_midnight = Utc.with_ymd_and_hms(2026, 3, 10, 15, 30, 0).unwrap();
+        assert_eq!(to_sqlite(after_midnight), "2026-03-11 00:30:00");
+
+        // 문자열 비교 = 시간 비교
+        assert!(to_sqlite(after_midnight) > to_sqlite(late));
+        assert!(to_sqlite(late).as_str() >= "2026-03-10");
+    }
+
+    #[test]
+    fn test_humanize_ago() {
+        assert_eq!(humanize_ago(&now_sqlite()), "방금");
+        assert_eq!(humanize_ago(&sqlite_ago(Duration::minutes(5))), "5분 전");
+        assert_eq!(humanize_ago(&sqlite_ago(Duration::hours(2))), "2시간 전");
+        assert_eq!(humanize_ago(&sqlite_ago(Duration::days(3))), "3일 전");
+        assert_eq!(humanize_ago("어제"), "어제");
+    }
+}
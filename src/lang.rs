@@ -0,0 +1,46 @@
+/// 지원하는 공지 언어 태그.
+pub const LANGS: &[&str] = &["ko", "en"];
+
+/// 사용자 입력/설정값을 언어 태그로. 모르는 값이면 `None`.
+pub fn normalize(input: &str) -> Option<&'static str> {
+    match input.trim().to_lowercase().as_str() {
+        "ko" | "kr" | "kor" | "korean" | "한국어" => Some("ko"),
+        "en" | "eng" | "english" | "영어" => Some("en"),
+        _ => None,
+    }
+}
+
+/// 제목으로 언어 추정: 한글이 하나도 없고 영문자가 있으면 영어, 아니면 한국어.
+/// 병기 제목("장학금 안내 / Scholarship")은 한국어로 본다.
+pub fn detect(title: &str) -> &'static str {
+    let has_hangul = title.chars().any(|c| ('\u{ac00}'..='\u{d7a3}').contains(&c));
+    let has_latin = title.chars().any(|c| c.is_ascii_alphabetic());
+    if !has_hangul && has_latin {
+        "en"
+    } else {
+        "ko"
+    }
+}
+
+/// DM 라벨용 언어 이름.
+pub fn label(lang: &str) -> &'static str {
+    match lang {
+        "en" => "English",
+        _ => "한국어",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_normalize() {
+        assert_eq!(detect("2026 Spring Exchange Student Orientation"), "en");
+        assert_eq!(detect("교환학생 오리엔테이션 안내 (Orientation)"), "ko");
+        assert_eq!(detect("2026-03-01"), "ko");
+        assert_eq!(normalize(" EN "), Some("en"));
+        assert_eq!(normalize("한국어"), Some("ko"));
+        assert_eq!(normalize("jp"), None);
+    }
+}
@@ -3,30 +3,114 @@ mod category;
 mod config;
 mod deadline;
 mod db;
+mod discord;
 mod dm_engine;
 mod error;
+mod format;
+mod kst;
+mod lang;
+mod metrics;
 mod notifier;
 mod parser;
+mod report;
+mod slack;
+mod snapshot;
+mod throttle;
+mod trigger;
+mod webhook;
 
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use clap::Parser;
 use teloxide::prelude::*;
+use teloxide::types::MessageId;
 use teloxide::utils::command::BotCommands;
-use tokio::time::sleep;
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
 
 use crate::parser::{NoticeParser, RawNotice};
 
 #[derive(Parser)]
 #[command(name = "cbnu-notice-bot", about = "충북대 공지사항 자동 알림 봇")]
-enum Cli {
+struct Cli {
+    /// 설정 파일 경로 (생략하면 CONFIG_PATH 환경변수, 그것도 없으면 config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// 모든 채널 게시를 이 채널로 보낸다 ("[PREVIEW]" 표시, DM 없음). `bot.preview_channel`보다 우선
+    #[arg(long, global = true, value_name = "ID")]
+    preview_channel: Option<String>,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(clap::Subcommand)]
+enum Commands {
     /// 크롤링 1회 실행 (GitHub Actions cron에서 호출)
     Crawl,
     /// 봇 서버 시작 + 자동 크롤링 (상시 실행, 이것만 돌리면 됨)
-    Serve,
+    Serve {
+        /// long polling 대신 webhook으로 업데이트 수신 (config의 [webhook] 필요)
+        #[arg(long)]
+        webhook: bool,
+    },
+    /// 소스 설정 점검: 파싱 결과만 출력 (DB/텔레그램 사용 안 함)
+    Test {
+        /// 점검할 소스 key (생략하면 활성화된 전체 소스)
+        source: Option<String>,
+    },
+    /// 새 소스 등록용: 지금 있는 공지를 수집만 하고 발송 완료로 표시 (채널/DM 발송 없음)
+    Seed {
+        /// 시드할 소스 key (생략하면 활성화된 전체 소스)
+        source: Option<String>,
+    },
+    /// 배포 전 점검: config, DB, 텔레그램 토큰, 소스 접속을 확인 (발송 없음)
+    Doctor,
+    /// CSS 선택자 점검: 페이지를 받아 선택자별로 맞은 행 수와 첫 3건을 출력 (설정/DB/텔레그램 사용 안 함)
+    ProbeSelector {
+        /// 게시판 목록 URL
+        url: String,
+        /// 공지 한 건에 해당하는 요소 (필수)
+        #[arg(long)]
+        row_selector: String,
+        /// 행 안의 링크 (기본 a[href])
+        #[arg(long)]
+        link_selector: Option<String>,
+        /// 제목 요소 (기본 링크 텍스트)
+        #[arg(long)]
+        title_selector: Option<String>,
+        /// 게시일 요소
+        #[arg(long)]
+        date_selector: Option<String>,
+        /// 작성자 요소
+        #[arg(long)]
+        author_selector: Option<String>,
+        /// 맞으면 고정 글로 표시
+        #[arg(long)]
+        pinned_selector: Option<String>,
+        /// href에서 공지 ID를 뽑는 정규식 (기본 (\d+)$)
+        #[arg(long)]
+        id_regex: Option<String>,
+        /// TLS 인증서 검증 생략
+        #[arg(long)]
+        insecure: bool,
+    },
+    /// 저장된 공지를 JSON 배열로 출력 (웹 페이지 등 외부 연동용)
+    Export {
+        /// 이 날짜(YYYY-MM-DD) 이후 수집된 공지만 (생략하면 전체)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// 소스 key 변경/병합: 공지, 크롤 상태, 학과 구독을 새 key로 옮긴다 (재발송 없음)
+    Rename {
+        /// 기존 소스 key
+        from: String,
+        /// 새 소스 key (이미 있으면 병합)
+        to: String,
+    },
 }
 
 #[tokio::main]
@@ -40,10 +124,60 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let cli = Cli::parse();
+    let config_path = resolve_config_path(cli.config, std::env::var("CONFIG_PATH").ok());
+    let config_path = config_path.as_path();
+
+    let preview = cli.preview_channel;
+
+    match cli.command {
+        Commands::Crawl => run_crawl(config_path, preview).await,
+        Commands::Serve { webhook } => run_serve(config_path, webhook, preview).await,
+        Commands::Test { source } => run_test(config_path, source.as_deref()).await,
+        Commands::Seed { source } => run_seed(config_path, source.as_deref()).await,
+        Commands::Export { since } => run_export(config_path, since.as_deref()),
+        Commands::Rename { from, to } => run_rename(config_path, &from, &to),
+        Commands::Doctor => run_doctor(config_path).await,
+        Commands::ProbeSelector {
+            url,
+            row_selector,
+            link_selector,
+            title_selector,
+            date_selector,
+            author_selector,
+            pinned_selector,
+            id_regex,
+            insecure,
+        } => {
+            let params: HashMap<String, String> = [
+                ("row_selector", Some(row_selector)),
+                ("link_selector", link_selector),
+                ("title_selector", title_selector),
+                ("date_selector", date_selector),
+                ("author_selector", author_selector),
+                ("pinned_selector", pinned_selector),
+                ("id_regex", id_regex),
+            ]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name.to_string(), v)))
+            .collect();
+            run_probe_selector(&url, &params, insecure).await
+        }
+    }
+}
+
+/// 설정 파일 경로 결정 (`--config` > CONFIG_PATH 환경변수 > config.toml).
+fn resolve_config_path(flag: Option<PathBuf>, env: Option<String>) -> PathBuf {
+    flag.or_else(|| env.filter(|p| !p.trim().is_empty()).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("config.toml"))
+}
 
-    match cli {
-        Cli::Crawl => run_crawl().await,
-        Cli::Serve => run_serve().await,
+/// `--preview-channel` 플래그가 있으면 `bot.preview_channel`을 덮어쓴다.
+fn apply_preview_channel(cfg: &mut config::Config, flag: Option<String>) {
+    if let Some(channel) = flag.filter(|c| !c.trim().is_empty()) {
+        cfg.bot.preview_channel = Some(channel);
+    }
+    if let Some(channel) = &cfg.bot.preview_channel {
+        tracing::warn!(channel = %channel, "Preview mode: all channel posts go to the preview channel, DMs are off");
     }
 }
 
@@ -52,16 +186,49 @@ fn resolve_db_path(cfg: &config::Config) -> String {
     std::env::var("DATABASE_PATH").unwrap_or_else(|_| cfg.database.path.clone())
 }
 
+/// 선택자 점검 (`probe-selector`): `css` 파서로 페이지를 파싱해 단계별 매칭 수와 첫 3건 출력.
+async fn run_probe_selector(url: &str, params: &HashMap<String, String>, insecure: bool) -> anyhow::Result<()> {
+    let clients = HttpClients::build(0, &config::UserAgent::default(), config::HttpTimeouts::default())?;
+    let parser = parser::css::CssParser::from_params("probe", "probe", url, params);
+    let probe = parser.probe(clients.for_source(insecure)).await?;
+
+    println!("== {} ==", url);
+    println!("  rows    (row_selector)  : {}", probe.rows);
+    println!("  links   (link_selector) : {}", probe.with_link);
+    println!("  ids     (id_regex)      : {}", probe.with_id);
+    println!(
+        "  notices (with title)    : {} (date {}, author {}, pinned {})",
+        probe.notices.len(),
+        probe.notices.iter().filter(|n| n.date.is_some()).count(),
+        probe.notices.iter().filter(|n| n.author.is_some()).count(),
+        probe.notices.iter().filter(|n| n.is_pinned).count(),
+    );
+    for notice in probe.notices.iter().take(3) {
+        println!(
+            "\n  [{}] {}{}\n    date: {}  author: {}\n    {}",
+            notice.notice_id,
+            if notice.is_pinned { "[공지] " } else { "" },
+            notice.title,
+            notice.original_date.as_deref().unwrap_or("-"),
+            notice.author.as_deref().unwrap_or("-"),
+            notice.url,
+        );
+    }
+    Ok(())
+}
+
 /// 크롤링 1회 실행 (CLI 또는 cron용).
-async fn run_crawl() -> anyhow::Result<()> {
-    let config_path = Path::new("config.toml");
-    let cfg = if config_path.exists() {
+async fn run_crawl(config_path: &Path, preview_channel: Option<String>) -> anyhow::Result<()> {
+    let mut cfg = if config_path.exists() {
         config::Config::load(config_path)?
     } else {
-        anyhow::bail!("config.toml is required. Please create it first.");
+        anyhow::bail!("{} is required. Please create it first.", config_path.display());
     };
+    apply_preview_channel(&mut cfg, preview_channel);
+    cfg.validate()?;
+    category::install_rules(&cfg.categories, &cfg.category_aliases);
 
-    let client = build_http_client()?;
+    let clients = HttpClients::build(cfg.bot.host_delay_ms, &cfg.bot.user_agent, cfg.bot.http_timeouts())?;
     let db_path = resolve_db_path(&cfg);
 
     let (channel_id, log_channel_id) = resolve_channels(&cfg);
@@ -78,28 +245,343 @@ async fn run_crawl() -> anyhow::Result<()> {
             channel_id,
             log_channel_id,
             cfg.bot.message_delay_ms,
-        ))
+            notifier::SendOptions::from_config(&cfg.bot)
+            .with_channel_template(cfg.templates.channel_template.clone())
+            .with_source_caps(cfg.source_caps())
+            .with_source_topics(cfg.source_topics()),
+        )
+        .with_rate_limit(cfg.bot.send_rate_per_sec))
     } else {
         None
     };
 
-    do_crawl(&cfg, &client, &db_path, notifier_opt.as_ref()).await
+    let dm_bot = if dry_run { None } else { build_dm_bot(&cfg.bot) };
+
+    let database = db::Database::init(&db_path)?
+        .with_near_dup(cfg.bot.near_dup_threshold, cfg.bot.near_dup_window_days);
+    let metrics = metrics::Metrics::new();
+    do_crawl(
+        &cfg,
+        &clients,
+        &database,
+        notifier_opt.as_ref(),
+        dm_bot.as_ref(),
+        &metrics,
+        &CancellationToken::new(),
+        &mut HashSet::new(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// DM 전용 봇 토큰 선택. `dm_token_env`가 가리키는 변수가 비어 있지 않을 때만 `Some`.
+fn select_dm_token(
+    dm_token_env: Option<&str>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let name = dm_token_env.map(str::trim).filter(|n| !n.is_empty())?;
+    lookup(name)
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// `bot.dm_token_env`가 설정돼 있으면 DM용 봇을 따로 만든다. 없으면 `None` (채널 봇 사용).
+fn build_dm_bot(bot_cfg: &config::BotConfig) -> Option<Bot> {
+    let token = select_dm_token(bot_cfg.dm_token_env.as_deref(), |name| std::env::var(name).ok());
+    if token.is_none() {
+        if let Some(name) = &bot_cfg.dm_token_env {
+            tracing::warn!(env = %name, "dm_token_env is set but the variable is empty; DMs use the channel bot");
+        }
+    }
+    token.map(Bot::new)
+}
+
+/// `doctor` 점검 항목 하나의 결과.
+struct Check {
+    name: String,
+    ok: bool,
+    /// 실패하면 종료 코드를 0이 아닌 값으로 만드는 항목.
+    critical: bool,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, critical: bool, result: Result<String, String>) -> Self {
+        let (ok, detail) = match result {
+            Ok(d) => (true, d),
+            Err(d) => (false, d),
+        };
+        Self { name: name.into(), ok, critical, detail }
+    }
+
+    fn icon(&self) -> &'static str {
+        match (self.ok, self.critical) {
+            (true, _) => "\u{2705}",
+            (false, true) => "\u{274c}",
+            (false, false) => "\u{26a0}\u{fe0f}",
+        }
+    }
+}
+
+/// 배포 전 점검. 채널/DM으로는 아무것도 보내지 않고 DB도 읽기만 한다 (없으면 실패).
+/// config·DB·토큰 실패는 치명적(종료 코드 1), 소스 접속 실패는 경고만 한다.
+async fn run_doctor(config_path: &Path) -> anyhow::Result<()> {
+    let mut checks = Vec::new();
+    let config_name = config_path.display().to_string();
+
+    let cfg = match config::Config::load(config_path) {
+        Ok(cfg) => {
+            checks.push(Check::new(&config_name, true, Ok(format!("{} sources", cfg.sources.len()))));
+            Some(cfg)
+        }
+        Err(e) => {
+            checks.push(Check::new(&config_name, true, Err(e.to_string())));
+            None
+        }
+    };
+
+    if let Some(cfg) = &cfg {
+        checks.push(Check::new(
+            "config validate",
+            true,
+            cfg.validate().map(|_| "ok".to_string()).map_err(|e| e.to_string()),
+        ));
+
+        let db_path = resolve_db_path(cfg);
+        let db_result = db::Database::open_read_only(&db_path)
+            .and_then(|db| db.get_user_count())
+            .map(|users| format!("{} ({} users)", db_path, users))
+            .map_err(|e| format!("{}: {}", db_path, e));
+        checks.push(Check::new("database", true, db_result));
+    }
+
+    let token_result = match std::env::var("TELOXIDE_TOKEN") {
+        Err(_) => Err("TELOXIDE_TOKEN not set (crawl would run in dry-run mode)".to_string()),
+        Ok(_) => match Bot::from_env().get_me().await {
+            Ok(me) => Ok(format!("@{}", me.username())),
+            Err(e) => Err(format!("getMe failed: {}", e)),
+        },
+    };
+    checks.push(Check::new("telegram token", true, token_result));
+
+    // DM 전용 토큰을 쓰면 그 봇도 따로 확인
+    // (변수가 비어 있으면 채널 봇으로 보내므로 경고만)
+    let dm_token_env = cfg.as_ref().and_then(|c| c.bot.dm_token_env.as_deref()).filter(|n| !n.trim().is_empty());
+    if let Some(name) = dm_token_env {
+        let check = match select_dm_token(Some(name), |n| std::env::var(n).ok()) {
+            None => Check::new("dm token", false, Err(format!("{} not set (DMs would use the channel bot)", name))),
+            Some(token) => {
+                let result = match Bot::new(token).get_me().await {
+                    Ok(me) => Ok(format!("@{}", me.username())),
+                    Err(e) => Err(format!("getMe failed: {}", e)),
+                };
+                Check::new("dm token", true, result)
+            }
+        };
+        checks.push(check);
+    }
+
+    if let Some(cfg) = &cfg {
+        let clients = HttpClients::build(0, &cfg.bot.user_agent, cfg.bot.http_timeouts())?;
+        for source in cfg.enabled_sources() {
+            let client = clients.for_source(source.tls_insecure(cfg.bot.tls_insecure));
+            let result = match client.head(&source.url).send().await {
+                // HEAD를 막아 둔 서버도 살아 있는 것으로 본다
+                Ok(resp) if resp.status().is_success()
+                    || resp.status().is_redirection()
+                    || resp.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED =>
+                {
+                    Ok(format!("HTTP {}", resp.status().as_u16()))
+                }
+                Ok(resp) => Err(format!("HTTP {} from {}", resp.status().as_u16(), source.url)),
+                Err(e) => Err(e.to_string()),
+            };
+            checks.push(Check::new(format!("source {}", source.key), false, result));
+        }
+    }
+
+    let width = checks.iter().map(|c| c.name.chars().count()).max().unwrap_or(0);
+    for check in &checks {
+        println!("{} {:<width$}  {}", check.icon(), check.name, check.detail, width = width);
+    }
+
+    let failed_critical = checks.iter().filter(|c| c.critical && !c.ok).count();
+    let warnings = checks.iter().filter(|c| !c.critical && !c.ok).count();
+    println!();
+    if failed_critical > 0 {
+        anyhow::bail!("{} critical check(s) failed", failed_critical);
+    }
+    println!("All critical checks passed ({} warning(s))", warnings);
+    Ok(())
+}
+
+/// 공지 아카이브를 JSON으로 stdout에 출력.
+fn run_export(config_path: &Path, since: Option<&str>) -> anyhow::Result<()> {
+    let cfg = config::Config::load(config_path)?;
+    if let Some(date) = since {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow::anyhow!("--since must be YYYY-MM-DD, got '{}'", date))?;
+    }
+
+    let display_names: HashMap<String, String> = cfg
+        .sources
+        .iter()
+        .map(|s| (s.key.clone(), s.display_name.clone()))
+        .collect();
+    let database = db::Database::init(&resolve_db_path(&cfg))?;
+    println!("{}", database.export_json(since, &display_names)?);
+    Ok(())
+}
+
+fn run_rename(config_path: &Path, from: &str, to: &str) -> anyhow::Result<()> {
+    let cfg = config::Config::load(config_path)?;
+    if !cfg.sources.iter().any(|s| s.key == to) {
+        tracing::warn!(source = %to, "New key is not in config yet; update [[source]] key before the next crawl");
+    }
+    let database = db::Database::init(&resolve_db_path(&cfg))?;
+    let merge = database.merge_sources(from, to)?;
+    println!(
+        "{} → {}: 공지 {}건 이동, 중복 {}건 정리, 구독 {}건 이동",
+        from, to, merge.notices, merge.duplicates, merge.subs
+    );
+    Ok(())
+}
+
+/// 현재 목록을 DB에 넣고 곧바로 발송 완료로 표시한다. 여러 번 돌려도 안전하다.
+async fn run_seed(config_path: &Path, source: Option<&str>) -> anyhow::Result<()> {
+    let cfg = config::Config::load(config_path)?;
+    cfg.validate()?;
+    category::install_rules(&cfg.categories, &cfg.category_aliases);
+
+    let sources: Vec<&config::SourceConfig> = match source {
+        Some(key) => match cfg.sources.iter().find(|s| s.key == key) {
+            Some(s) => vec![s],
+            None => anyhow::bail!("Unknown source key: {}", key),
+        },
+        None => cfg.enabled_sources(),
+    };
+
+    let clients = HttpClients::build(cfg.bot.host_delay_ms, &cfg.bot.user_agent, cfg.bot.http_timeouts())?;
+    let retry = RetryPolicy::from_config(&cfg.bot);
+    let database = db::Database::init(&resolve_db_path(&cfg))?;
+
+    let mut total = 0;
+    for source_cfg in sources {
+        let parser = parser::create_parser(source_cfg)?;
+        let client = clients.for_source(source_cfg.tls_insecure(cfg.bot.tls_insecure));
+        let notices = match fetch_with_retry(parser.as_ref(), client, &clients.throttle, &source_cfg.url, &retry).await {
+            Ok(fetched) => fetched.notices,
+            Err(e) => {
+                println!("{:<16} ERROR: {}", source_cfg.key, e);
+                continue;
+            }
+        };
+        database.insert_batch(&source_cfg.key, &notices, &source_cfg.display_name, source_cfg.dedup_global)?;
+        database.update_crawl_state(&source_cfg.key, parser::cursor_id(&notices))?;
+        let seeded = database.mark_all_notified(Some(&source_cfg.key))?;
+        println!("{:<16} {} parsed, {} marked as notified", source_cfg.key, notices.len(), seeded);
+        total += seeded;
+    }
+    println!("Seeded {} notice(s). Only notices posted from now on will be sent.", total);
+
+    Ok(())
+}
+
+/// 소스 점검 모드: 메모리 DB로 크롤링해 파싱 결과를 표로 출력한다.
+/// 설정된 DB 파일은 열지 않고 텔레그램 호출도 하지 않는다.
+async fn run_test(config_path: &Path, source: Option<&str>) -> anyhow::Result<()> {
+    let cfg = config::Config::load(config_path)?;
+    cfg.validate()?;
+    category::install_rules(&cfg.categories, &cfg.category_aliases);
+
+    // key를 지정하면 비활성 소스도 점검할 수 있다
+    let sources: Vec<&config::SourceConfig> = match source {
+        Some(key) => match cfg.sources.iter().find(|s| s.key == key) {
+            Some(s) => vec![s],
+            None => anyhow::bail!("Unknown source key: {}", key),
+        },
+        None => cfg.enabled_sources(),
+    };
+
+    let clients = HttpClients::build(cfg.bot.host_delay_ms, &cfg.bot.user_agent, cfg.bot.http_timeouts())?;
+    let retry = RetryPolicy::from_config(&cfg.bot);
+    let database = db::Database::init(":memory:")?
+        .with_near_dup(cfg.bot.near_dup_threshold, cfg.bot.near_dup_window_days);
+
+    for source_cfg in sources {
+        println!("\n== {} ({}, {}) ==", source_cfg.display_name, source_cfg.key, source_cfg.parser);
+        let parser = match parser::create_parser(source_cfg) {
+            Ok(p) => p,
+            Err(e) => {
+                println!("  ERROR: {}", e);
+                continue;
+            }
+        };
+        let client = clients.for_source(source_cfg.tls_insecure(cfg.bot.tls_insecure));
+
+        let notices = match fetch_with_retry(parser.as_ref(), client, &clients.throttle, &source_cfg.url, &retry).await {
+            Ok(fetched) => fetched.notices,
+            Err(e) => {
+                println!("  ERROR: {}", e);
+                continue;
+            }
+        };
+
+        println!("  {:<10} {:<12} {:<10} {:<4} TITLE", "ID", "CATEGORY", "DATE", "NEW");
+        let mut new_count = 0;
+        for notice in &notices {
+            let is_new = database.insert_if_new(&source_cfg.key, notice, &source_cfg.display_name, source_cfg.dedup_global)?;
+            if is_new {
+                new_count += 1;
+            }
+            let category = database.classify(notice.category.as_deref(), &notice.title)?;
+            println!(
+                "  {:<10} {:<12} {:<10} {:<4} {}{}",
+                notice.notice_id,
+                category.as_str(),
+                notice.date.as_deref().unwrap_or("-"),
+                if is_new { "Y" } else { "-" },
+                if notice.is_pinned { "[공지] " } else { "" },
+                notice.title,
+            );
+        }
+        println!("  -- {} parsed, {} would be inserted", notices.len(), new_count);
+    }
+
+    Ok(())
 }
 
 /// 봇 서버 모드: 텔레그램 커맨드 수신 + 자동 크롤링.
 /// 이 모드 하나만 실행하면 모든 기능이 동작한다.
-async fn run_serve() -> anyhow::Result<()> {
-    let config_path = Path::new("config.toml");
-    let cfg = config::Config::load(config_path)?;
+/// `force_webhook` 또는 `bot.mode = "webhook"`이면 webhook, 아니면 long polling.
+async fn run_serve(config_path: &Path, force_webhook: bool, preview_channel: Option<String>) -> anyhow::Result<()> {
+    let mut cfg = config::Config::load(config_path)?;
+    apply_preview_channel(&mut cfg, preview_channel);
+    cfg.validate()?;
+    category::install_rules(&cfg.categories, &cfg.category_aliases);
     let db_path = resolve_db_path(&cfg);
     let database = db::Database::init(&db_path)?;
 
     let bot = Bot::from_env();
     tracing::info!("Starting serve mode (bot commands + auto crawl)...");
 
+    let (channel_id, log_channel_id) = resolve_channels(&cfg);
+    // `/crawl` → 크롤 루프 깨우기
+    let (crawl_trigger, crawl_requests) = trigger::channel();
+    // `/broadcast`도 크롤 루프와 같은 버킷을 써서 합쳐서 flood 한도를 넘지 않게
+    let limiter = Arc::new(throttle::RateLimiter::new(cfg.bot.send_rate_per_sec));
     let state = Arc::new(bot_commands::BotState {
         db: Arc::new(Mutex::new(database)),
         sources: cfg.sources.clone(),
+        query_tokens: Arc::new(Mutex::new(HashMap::new())),
+        admin: cfg.admin.clone(),
+        message_delay_ms: cfg.bot.message_delay_ms,
+        log_channel: log_channel_id,
+        channel: channel_id,
+        match_body: cfg.bot.match_body,
+        crawl: Some(crawl_trigger),
+        started: std::time::Instant::now(),
+        limiter: limiter.clone(),
     });
 
     // 봇 커맨드 등록
@@ -110,20 +592,18 @@ async fn run_serve() -> anyhow::Result<()> {
         tracing::warn!(error = %e, "Failed to set bot commands menu");
     }
 
-    // 자동 크롤링 백그라운드 스레드 (별도 tokio 런타임).
-    // rusqlite::Connection이 Sync가 아니므로 tokio::spawn 대신 별도 스레드 사용.
-    let crawl_cfg = cfg.clone();
-    let crawl_bot = bot.clone();
-    let db_path_clone = db_path.clone();
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .expect("Failed to build crawl runtime");
-        rt.block_on(crawl_loop(crawl_cfg, crawl_bot, db_path_clone));
-    });
+    let metrics = Arc::new(metrics::Metrics::new());
+    if cfg.metrics.enabled {
+        let server_metrics = metrics.clone();
+        let port = cfg.metrics.port;
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(server_metrics, port).await {
+                tracing::error!(error = %e, "Metrics server failed");
+            }
+        });
+    }
 
-    // 텔레그램 long polling (메인 태스크)
+    // 텔레그램 업데이트 수신 (메인 태스크)
     let handler = dptree::entry()
         .branch(
             Update::filter_message()
@@ -133,10 +613,51 @@ async fn run_serve() -> anyhow::Result<()> {
                         bot_commands::handle_command(bot, msg, cmd, state).await
                     },
                 ),
-        );
+        )
+        .branch(Update::filter_message().endpoint(
+            |bot: Bot, msg: Message, state: Arc<bot_commands::BotState>| async move {
+                bot_commands::handle_unknown(bot, msg, state).await
+            },
+        ))
+        .branch(Update::filter_callback_query().endpoint(
+            |bot: Bot, q: CallbackQuery, state: Arc<bot_commands::BotState>| async move {
+                bot_commands::handle_callback(bot, q, state).await
+            },
+        ))
+        .branch(Update::filter_inline_query().endpoint(
+            |bot: Bot, q: InlineQuery, state: Arc<bot_commands::BotState>| async move {
+                bot_commands::handle_inline_query(bot, q, state).await
+            },
+        ));
+
+    // 종료 신호: 디스패처가 멈추면 크롤 스레드에 알린다.
+    let shutdown = CancellationToken::new();
+
+    // 자동 크롤링 백그라운드 스레드 (별도 tokio 런타임).
+    // rusqlite::Connection이 Sync가 아니므로 tokio::spawn 대신 별도 스레드 사용.
+    let crawl_cfg = cfg.clone();
+    let crawl_bot = bot.clone();
+    let db_path_clone = db_path.clone();
+    let crawl_metrics = metrics.clone();
+    let crawl_shutdown = shutdown.clone();
+    let crawl_thread = std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to build crawl runtime");
+        rt.block_on(crawl_loop(
+            crawl_cfg,
+            crawl_bot,
+            db_path_clone,
+            crawl_metrics,
+            crawl_shutdown,
+            crawl_requests,
+            limiter,
+        ));
+    });
 
-    Dispatcher::builder(bot, handler)
-        .dependencies(dptree::deps![state])
+    let mut dispatcher = Dispatcher::builder(bot.clone(), handler)
+        .dependencies(dptree::deps![state.clone()])
         .default_handler(|_| async {})
         .error_handler(Arc::new(|err| {
             Box::pin(async move {
@@ -144,23 +665,92 @@ async fn run_serve() -> anyhow::Result<()> {
             })
         }))
         .enable_ctrlc_handler()
-        .build()
-        .dispatch()
-        .await;
+        .build();
+
+    match webhook_options(&cfg, force_webhook)? {
+        Some(options) => {
+            tracing::info!(url = %options.url, addr = %options.address, "Receiving updates via webhook");
+            // setWebhook 등록은 리스너가 시작하면서 자동으로 호출한다.
+            let listener = teloxide::update_listeners::webhooks::axum(bot, options).await?;
+            dispatcher
+                .dispatch_with_listener(
+                    listener,
+                    LoggingErrorHandler::with_custom_text("Webhook listener error"),
+                )
+                .await;
+        }
+        None => {
+            if force_webhook || cfg.bot.mode == "webhook" {
+                tracing::warn!("Webhook mode requested but [webhook].url is empty; falling back to long polling");
+            }
+            tracing::info!("Receiving updates via long polling");
+            dispatcher.dispatch().await;
+        }
+    }
+
+    // 크롤 스레드가 현재 사이클(또는 진행 중인 소스)을 마칠 때까지 기다린다.
+    tracing::info!("Shutting down: waiting for crawl loop to finish");
+    shutdown.cancel();
+    if tokio::task::spawn_blocking(move || crawl_thread.join()).await.is_err() {
+        tracing::error!("Crawl thread panicked during shutdown");
+    }
+    if let Err(e) = state.db.lock().unwrap().checkpoint() {
+        tracing::warn!(error = %e, "Final WAL checkpoint failed");
+    }
+    tracing::info!("Shutdown complete");
 
     Ok(())
 }
 
+/// webhook 리스너 옵션 생성. webhook을 쓰지 않으면 `None`.
+fn webhook_options(
+    cfg: &config::Config,
+    force: bool,
+) -> anyhow::Result<Option<teloxide::update_listeners::webhooks::Options>> {
+    let hook = match cfg.webhook_settings(force) {
+        Some(h) => h,
+        None => return Ok(None),
+    };
+
+    let url: reqwest::Url = hook
+        .url
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid webhook url {:?}: {}", hook.url, e))?;
+    let address = std::net::SocketAddr::from(([0, 0, 0, 0], hook.port));
+    let mut options = teloxide::update_listeners::webhooks::Options::new(address, url);
+
+    let secret = std::env::var("WEBHOOK_SECRET")
+        .ok()
+        .or_else(|| hook.secret_token.clone())
+        .filter(|s| !s.is_empty());
+    if let Some(secret) = secret {
+        if !config::WebhookConfig::is_valid_secret(&secret) {
+            anyhow::bail!("Webhook secret_token must be 1-256 chars of A-Z, a-z, 0-9, _ or -");
+        }
+        options = options.secret_token(secret);
+    }
+
+    Ok(Some(options))
+}
+
 /// 백그라운드 자동 크롤링 루프.
 /// 시작 즉시 1회 실행 후, 설정된 간격으로 반복.
-async fn crawl_loop(cfg: config::Config, bot: Bot, db_path: String) {
+async fn crawl_loop(
+    cfg: config::Config,
+    bot: Bot,
+    db_path: String,
+    metrics: Arc<metrics::Metrics>,
+    shutdown: CancellationToken,
+    mut requests: tokio::sync::mpsc::Receiver<trigger::CrawlRequest>,
+    limiter: Arc<throttle::RateLimiter>,
+) {
     let interval = Duration::from_secs(cfg.bot.crawl_interval_secs);
     tracing::info!(
         interval_secs = cfg.bot.crawl_interval_secs,
         "Auto-crawl loop started"
     );
 
-    let client = match build_http_client() {
+    let clients = match HttpClients::build(cfg.bot.host_delay_ms, &cfg.bot.user_agent, cfg.bot.http_timeouts()) {
         Ok(c) => c,
         Err(e) => {
             tracing::error!(error = %e, "Failed to build HTTP client for crawl loop");
@@ -169,33 +759,153 @@ async fn crawl_loop(cfg: config::Config, bot: Bot, db_path: String) {
     };
 
     let (channel_id, log_channel_id) = resolve_channels(&cfg);
+    let dm_bot = build_dm_bot(&cfg.bot);
     let notifier = notifier::Notifier::new(
         bot,
         channel_id,
         log_channel_id,
         cfg.bot.message_delay_ms,
-    );
+        notifier::SendOptions::from_config(&cfg.bot)
+            .with_channel_template(cfg.templates.channel_template.clone())
+            .with_source_caps(cfg.source_caps())
+            .with_source_topics(cfg.source_topics()),
+    )
+    .with_shared_limiter(limiter);
 
+    // 연결은 루프 전체에서 하나만 연다 (스키마/PRAGMA 설정도 한 번). 이 루프는 전용
+    // current-thread 런타임에서 돌아서 `Send`가 아닌 참조를 await 너머로 들고 있어도 된다.
+    let database = match db::Database::init(&db_path) {
+        Ok(db) => db.with_near_dup(cfg.bot.near_dup_threshold, cfg.bot.near_dup_window_days),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to open database for crawl loop");
+            return;
+        }
+    };
+
+    // 수동 요청(`/crawl`)은 예약 사이클과 같은 루프에서 차례로 돌아 서로 겹치지 않는다.
+    // 수동 크롤링은 예약 시각을 미루지 않는다 (다음 예약 사이클은 원래 시각에).
+    let mut manual: Option<trigger::CrawlRequest> = None;
+    let mut next_due = Instant::now();
+    let mut previewed = HashSet::new();
     loop {
-        if let Err(e) = do_crawl(&cfg, &client, &db_path, Some(&notifier)).await {
+        if manual.is_none() {
+            next_due = Instant::now() + interval;
+        }
+        // 소스 키는 `/crawl`에서 이미 확인했다 (활성 소스가 아니면 전체 수집)
+        let only = manual.as_ref().and_then(|req| req.source.as_deref()).and_then(|key| cfg.only_source(key));
+        let result = do_crawl(
+            only.as_ref().unwrap_or(&cfg),
+            &clients,
+            &database,
+            Some(&notifier),
+            dm_bot.as_ref(),
+            &metrics,
+            &shutdown,
+            &mut previewed,
+        )
+        .await;
+        if let Err(e) = &result {
             tracing::error!(error = %e, "Crawl cycle failed");
         }
+        if let Some(req) = manual.take() {
+            let _ = req.reply.send(result);
+        }
+        if cfg.weekly_report.enabled {
+            if let Err(e) = send_weekly_report_if_due(&cfg, &database, &notifier).await {
+                tracing::warn!(error = %e, "Weekly report failed");
+            }
+        }
 
-        tracing::info!(next_in_secs = interval.as_secs(), "Sleeping until next crawl");
-        sleep(interval).await;
+        let next_in = next_due.saturating_duration_since(Instant::now());
+        tracing::info!(next_in_secs = next_in.as_secs(), "Sleeping until next crawl");
+        match wait_next_cycle(next_due, &shutdown, &mut requests).await {
+            Wake::Scheduled => {}
+            Wake::Manual(req) => {
+                tracing::info!(source = ?req.source, "Manual crawl requested");
+                manual = Some(req);
+            }
+            Wake::Shutdown => break,
+        }
+    }
+
+    match database.checkpoint() {
+        Ok(()) => tracing::info!("Crawl loop stopped cleanly"),
+        Err(e) => tracing::warn!(error = %e, "WAL checkpoint failed on crawl loop exit"),
     }
 }
 
+/// 이번 주 리포트 시각(`[weekly_report]`, KST)이 지났는데 아직 안 보냈으면
+/// 최근 7일 집계를 로그 채널로 보낸다. 크롤 주기마다 확인한다.
+async fn send_weekly_report_if_due(
+    cfg: &config::Config,
+    database: &db::Database,
+    notifier: &notifier::Notifier,
+) -> anyhow::Result<()> {
+    let Some(weekday) = cfg.weekly_report.weekday() else {
+        return Ok(());
+    };
+    let now = kst::now().naive_local();
+    let last_sent = database.get_report_sent(report::WEEKLY)?;
+    if !report::is_due(now, weekday, cfg.weekly_report.hour, last_sent.as_deref()) {
+        return Ok(());
+    }
+
+    let stats = database.get_weekly_stats(&kst::sqlite_ago(chrono::Duration::days(7)))?;
+    let display_names: HashMap<String, String> =
+        cfg.sources.iter().map(|s| (s.key.clone(), s.display_name.clone())).collect();
+    for message in report::render(&stats, &display_names) {
+        notifier.send_summary(&message).await?;
+    }
+    database.set_report_sent(report::WEEKLY, &kst::now_sqlite())?;
+    tracing::info!("Weekly report sent");
+    Ok(())
+}
+
+/// 크롤 루프가 깨어난 이유.
+enum Wake {
+    Scheduled,
+    Manual(trigger::CrawlRequest),
+    Shutdown,
+}
+
+/// 예약 시각(`deadline`)까지 기다린다. 수동 크롤링 요청이나 종료 신호가 오면 바로 깬다.
+/// 요청 쪽이 모두 사라져도(봇 종료) 예약 주기는 그대로 돈다.
+async fn wait_next_cycle(
+    deadline: Instant,
+    shutdown: &CancellationToken,
+    requests: &mut tokio::sync::mpsc::Receiver<trigger::CrawlRequest>,
+) -> Wake {
+    tokio::select! {
+        _ = shutdown.cancelled() => Wake::Shutdown,
+        Some(req) = requests.recv() => Wake::Manual(req),
+        _ = sleep_until(deadline) => if shutdown.is_cancelled() { Wake::Shutdown } else { Wake::Scheduled },
+    }
+}
+
+/// 소스별 공평 분배를 위해 발송 한도의 몇 배까지 대기 공지를 읽어 올지.
+const PENDING_SCAN_FACTOR: usize = 5;
+
+/// 목록 수집 시간이 평균(EMA)의 몇 배를 넘으면 경고할지.
+const LATENCY_SPIKE_FACTOR: f64 = 3.0;
+
 /// 크롤링 핵심 로직 (crawl + notify + DM).
 /// `run_crawl()`과 `crawl_loop()` 모두 이 함수를 호출한다.
-/// 매 호출마다 자체 DB 연결을 열어 Send 안전성을 보장한다.
+/// DB 연결은 호출한 쪽이 한 번 열어 사이클마다 넘긴다 (`crawl_loop`은 전용 스레드라 Send 불필요).
+/// `dm_bot`이 있으면 개인 DM은 그 봇으로, 없으면 채널 봇으로 보낸다.
+/// 크롤링 한 사이클: 수집 → 채널 게시 → DM. `previewed`는 미리보기 모드에서 이미 보낸
+/// 공지 ID (DB에 기록하지 않으므로 serve 모드 사이클 사이에 들고 다닌다).
+#[allow(clippy::too_many_arguments)]
 async fn do_crawl(
     cfg: &config::Config,
-    client: &reqwest::Client,
-    db_path: &str,
+    clients: &HttpClients,
+    database: &db::Database,
     notifier_opt: Option<&notifier::Notifier>,
-) -> anyhow::Result<()> {
-    let database = db::Database::init(db_path)?;
+    dm_bot: Option<&Bot>,
+    metrics: &metrics::Metrics,
+    shutdown: &CancellationToken,
+    previewed: &mut HashSet<i64>,
+) -> anyhow::Result<trigger::CrawlCounts> {
+    let retry = RetryPolicy::from_config(&cfg.bot);
     // Build source display name map + channel routing map
     let display_names: HashMap<String, String> = cfg
         .sources
@@ -217,31 +927,198 @@ async fn do_crawl(
     let mut source_stats: Vec<String> = Vec::new();
 
     for source_cfg in &enabled_sources {
-        let parser = parser::create_parser(source_cfg);
+        // 종료 중이면 남은 소스는 건너뛰고 이미 수집한 공지만 발송
+        if shutdown.is_cancelled() {
+            tracing::info!("Shutdown requested; skipping remaining sources");
+            break;
+        }
+
+        let parser = match parser::create_parser(source_cfg) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!(source = %source_cfg.key, error = %e, "Skipping source");
+                source_stats.push(format!("{}:ERR", source_cfg.key));
+                continue;
+            }
+        };
         let source_key = parser.source_key().to_string();
         let display_name = parser.display_name().to_string();
+        let client = clients.for_source(source_cfg.tls_insecure(cfg.bot.tls_insecure));
 
-        match fetch_with_retry(parser.as_ref(), client).await {
-            Ok(notices) => {
-                let mut new_count = 0u32;
-                let last_id = notices.first().map(|n| n.notice_id.clone());
-
-                for notice in &notices {
-                    match database.insert_if_new(&source_key, notice, &display_name) {
-                        Ok(true) => new_count += 1,
-                        Ok(false) => {} // duplicate
-                        Err(e) => {
-                            tracing::error!(
-                                source = %source_key,
-                                notice_id = %notice.notice_id,
-                                error = %e,
-                                "DB insert failed"
-                            );
+        // 연속 실패로 자동 비활성화된 소스는 쿨다운이 끝날 때까지 건너뜀
+        if let Some(until) = database.cooldown_until(&source_key)? {
+            tracing::info!(source = %source_key, until = %until, "Source cooling down; skipping");
+            source_stats.push(format!("{}:PAUSED", source_key));
+            continue;
+        }
+
+        let started = std::time::Instant::now();
+        let fetched = fetch_with_retry(parser.as_ref(), client, &clients.throttle, &source_cfg.url, &retry).await;
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        match fetched {
+            Ok(parser::FetchedList { notices, body }) => {
+                let empty_streak = database.record_empty_parse(&source_key, notices.is_empty())?;
+                if notices.is_empty() && cfg.bot.save_empty_snapshots {
+                    // 로그 채널 알림은 0건이 이어지는 동안 처음 한 번만
+                    let alert = notifier_opt.filter(|_| empty_streak == 1);
+                    save_empty_snapshot(parser.as_ref(), &body, cfg.bot.snapshot_keep, alert).await;
+                }
+
+                let last_id = parser::cursor_id(&notices).map(String::from);
+                // 지난 커서 아래는 이미 본 공지: 삽입/중복 검사 없이 조회수·수정만 확인
+                let cursor = database.get_last_notice_id(&source_key)?;
+                let fresh = parser::fresh_len(&notices, cursor.as_deref()).unwrap_or(notices.len());
+                if fresh < notices.len() {
+                    tracing::debug!(source = %source_key, fresh, seen = notices.len() - fresh, "Resuming from crawl cursor");
+                }
+
+                // 한 소스의 쓰기는 한 트랜잭션으로 (건별 커밋보다 빠르고 리더와 덜 부딪힌다)
+                let written = database.transaction(|database| {
+                    let mut new_count = 0u32;
+                    let mut new_notices: Vec<&RawNotice> = Vec::new();
+                    for (i, notice) in notices.iter().enumerate() {
+                        if !source_cfg.keeps(notice) {
+                            continue;
+                        }
+                        let inserted = i < fresh
+                            && !database.renumber_by_url(&source_key, notice, &notices)?
+                            && database.insert_if_new(&source_key, notice, &display_name, source_cfg.dedup_global)?;
+                        match inserted {
+                            true if source_cfg.min_date.is_some_and(|min| parser::dated_before(notice, min)) => {
+                                // 옛 글이 다시 떠오른 경우: 저장만 하고 알리지 않는다
+                                tracing::info!(
+                                    source = %source_key,
+                                    notice_id = %notice.notice_id,
+                                    date = ?notice.date,
+                                    "Skipping notice older than min_date"
+                                );
+                                database.mark_backfill(&source_key, &notice.notice_id)?;
+                            }
+                            true if source_cfg.mutes_pinned(notice) => {
+                                tracing::info!(
+                                    source = %source_key,
+                                    notice_id = %notice.notice_id,
+                                    "Storing pinned notice without notifying (skip_pinned)"
+                                );
+                                database.mark_backfill(&source_key, &notice.notice_id)?;
+                            }
+                            true => {
+                                if cfg.bot.is_urgent(&notice.title) {
+                                    tracing::info!(
+                                        source = %source_key,
+                                        notice_id = %notice.notice_id,
+                                        "Urgent notice; posting first"
+                                    );
+                                    database.mark_urgent(&source_key, &notice.notice_id)?;
+                                }
+                                new_count += 1;
+                                new_notices.push(notice);
+                            }
+                            false => {
+                                if let Some(views) = notice.views {
+                                    if let Err(e) = database.update_views(&source_key, &notice.notice_id, views) {
+                                        tracing::warn!(source = %source_key, error = %e, "Views update failed");
+                                    }
+                                }
+                                // 이미 본 공지: 제목이 수정됐는지 확인
+                                if let Err(e) = database.update_if_edited(&source_key, notice, cfg.bot.notify_on_edit) {
+                                    tracing::warn!(
+                                        source = %source_key,
+                                        notice_id = %notice.notice_id,
+                                        error = %e,
+                                        "Edit check failed"
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    if let Some(lang) = &source_cfg.lang {
+                        database.set_source_lang(&source_key, lang)?;
+                    }
+                    Ok((new_count, new_notices))
+                });
+                let (new_count, new_notices) = match written {
+                    Ok(written) => written,
+                    Err(e) => {
+                        // 소스 전체가 롤백되고 커서도 그대로: 다음 사이클에 다시 시도
+                        metrics.record_crawl_error(&source_key);
+                        let err_count = database.increment_error(&source_key, &format!("{:#}", e))?;
+                        tracing::error!(
+                            source = %source_key,
+                            error = %e,
+                            consecutive_errors = err_count,
+                            "DB insert failed; rolled back source"
+                        );
+                        source_stats.push(format!("{}:ERR", source_key));
+                        continue;
+                    }
+                };
+
+                // 상세 페이지 보강 (신규 공지만, 요청 간격은 host_delay_ms 게이트가 보장)
+                if source_cfg.fetch_detail {
+                    for notice in &new_notices {
+                        clients.throttle.wait(&notice.url).await;
+                        match parser.fetch_detail(client, &notice.url).await {
+                            Ok(detail) => {
+                                // 보강은 부가 정보라 저장에 실패해도 사이클은 계속 (공지는 이미 기록됨)
+                                let saved = database
+                                    .set_detail(
+                                        &source_key,
+                                        &notice.notice_id,
+                                        detail.body.as_deref(),
+                                        detail.published.as_deref(),
+                                    )
+                                    .and_then(|_| {
+                                        if detail.images.is_empty() {
+                                            Ok(())
+                                        } else {
+                                            database.set_images(&source_key, &notice.notice_id, &detail.images)
+                                        }
+                                    });
+                                if let Err(e) = saved {
+                                    tracing::warn!(
+                                        source = %source_key,
+                                        notice_id = %notice.notice_id,
+                                        error = %e,
+                                        "Detail save failed"
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    source = %source_key,
+                                    notice_id = %notice.notice_id,
+                                    error = %e,
+                                    "Detail fetch failed"
+                                );
+                            }
                         }
                     }
                 }
 
-                database.update_crawl_state(&source_key, last_id.as_deref())?;
+                let prev_errors = database.update_crawl_state(&source_key, last_id.as_deref())?;
+                // 평소보다 크게 느려지면 곧 실패하는 경우가 많다
+                if let Some(avg) = database.record_crawl_duration(&source_key, elapsed_ms)? {
+                    if elapsed_ms > avg * LATENCY_SPIKE_FACTOR {
+                        tracing::warn!(
+                            source = %source_key,
+                            elapsed_ms = elapsed_ms as u64,
+                            avg_ms = avg as u64,
+                            "Crawl latency spike"
+                        );
+                    }
+                }
+                let threshold = cfg.bot.max_consecutive_errors;
+                if threshold > 0 && prev_errors >= threshold {
+                    tracing::info!(source = %source_key, prev_errors, "Source recovered");
+                    if let Some(notifier) = notifier_opt {
+                        let msg = format!(
+                            "\u{2705} 크롤링 복구\n\n소스: {}\n연속 {}회 실패 후 정상화되었습니다.",
+                            source_key, prev_errors
+                        );
+                        let _ = notifier.send_error_alert(&msg).await;
+                    }
+                }
                 tracing::info!(
                     source = %source_key,
                     total = notices.len(),
@@ -249,11 +1126,13 @@ async fn do_crawl(
                     "Crawl complete"
                 );
 
+                metrics.record_crawl(&source_key, new_count as u64);
                 total_new += new_count;
                 source_stats.push(format!("{}:{}", source_key, new_count));
             }
             Err(e) => {
-                let err_count = database.increment_error(&source_key)?;
+                metrics.record_crawl_error(&source_key);
+                let err_count = database.increment_error(&source_key, &format!("{:#}", e))?;
                 tracing::error!(
                     source = %source_key,
                     error = %e,
@@ -261,11 +1140,20 @@ async fn do_crawl(
                     "Crawl failed"
                 );
 
+                let paused_until = database.apply_error_cooldown(
+                    &source_key,
+                    err_count,
+                    cfg.bot.max_consecutive_errors,
+                )?;
+
                 if err_count >= 5 {
-                    let alert = format!(
+                    let mut alert = format!(
                         "\u{26a0}\u{fe0f} 크롤링 경고\n\n소스: {}\n상태: 연속 {}회 실패\n에러: {}",
                         source_key, err_count, e
                     );
+                    if let Some(until) = &paused_until {
+                        alert.push_str(&format!("\n\u{23f8} {}까지 자동 비활성", until));
+                    }
                     if let Some(notifier) = notifier_opt {
                         let _ = notifier.send_error_alert(&alert).await;
                     }
@@ -276,18 +1164,143 @@ async fn do_crawl(
         }
     }
 
-    // Send pending notifications
-    let pending = database.get_pending(cfg.bot.max_notices_per_run, &display_names)?;
-    let sent = if let Some(notifier) = notifier_opt {
-        let sent_ids = notifier.send_batch(&pending, cfg.bot.max_notices_per_run, &channel_map).await?;
+    // Send pending notifications: 재시도 대기열(due)을 먼저, 남는 자리에 신규 공지
+    // 한도보다 넉넉히 읽어 와서 소스별로 돌아가며 고른다 (한 게시판이 자리를 독차지하지 않게)
+    let max = cfg.bot.max_notices_per_run;
+    let mut candidates = database.get_due_retries(max, &display_names)?;
+    candidates.extend(database.get_pending(
+        max.saturating_mul(PENDING_SCAN_FACTOR),
+        cfg.bot.post_order,
+        &display_names,
+    )?);
+    // 미리보기 모드는 운영 DB를 쓰므로 보낸 공지를 기록하지 않는다 (운영 채널/DM 대기열은 그대로).
+    // 대신 이번 프로세스에서 이미 미리 본 공지는 다시 올리지 않는다.
+    let preview = cfg.bot.preview_channel.is_some();
+    if preview {
+        candidates.retain(|n| !previewed.contains(&n.id));
+    }
+    let mut pending: Vec<db::Notice> = notifier::pick_for_post(&candidates, max, &cfg.source_caps(), cfg.bot.post_order)
+        .into_iter()
+        .cloned()
+        .collect();
+    let sent = if !cfg.bot.enable_channel {
+        // 채널 게시 끔: 게시 없이 처리 완료로 기록 (DM 대상은 된다)
+        for notice in pending.iter().filter(|_| !preview) {
+            database.mark_notified(notice.id)?;
+        }
+        if !pending.is_empty() {
+            tracing::info!(count = pending.len(), "Channel posting disabled; marked as processed");
+        }
+        0
+    } else if let Some(notifier) = notifier_opt {
+        // 수정된 공지는 가능하면 원래 메시지를 고친다. 실패하면 새 게시물로 보낸다.
+        let mut edited_ids = Vec::new();
+        if cfg.bot.edit_on_update && !preview {
+            for notice in pending.iter().filter(|n| n.edited) {
+                let Some(message_id) = database.get_editable_message_id(notice.id)? else {
+                    continue;
+                };
+                let ch = channel_map.get(&notice.source_key).map(|s| s.as_str());
+                match notifier.edit_notice(notice, ch, MessageId(message_id)).await {
+                    Ok(()) => {
+                        database.mark_notified(notice.id)?;
+                        edited_ids.push(notice.id);
+                    }
+                    Err(e) => tracing::warn!(
+                        notice_id = %notice.notice_id,
+                        error = %e,
+                        "Edit failed; posting a new message instead"
+                    ),
+                }
+            }
+            pending.retain(|n| !edited_ids.contains(&n.id));
+        }
+
+        let images = if cfg.bot.send_images {
+            database.get_images(&pending.iter().map(|n| n.id).collect::<Vec<_>>())?
+        } else {
+            HashMap::new()
+        };
+        // 지난번에 앞 조각만 나간 공지는 남은 조각부터 이어 보낸다
+        if !preview {
+            let ids: Vec<i64> = pending.iter().map(|n| n.id).collect();
+            notifier.resume_progress(
+                database
+                    .get_send_progress(&ids)?
+                    .into_iter()
+                    .filter_map(|(id, (markup, parts))| Some((id, (format::Markup::from_name(&markup)?, parts))))
+                    .collect(),
+            );
+        }
+        let sent = notifier.send_batch(&pending, max, &channel_map, &images).await?;
+        let sent_ids: Vec<i64> = sent.iter().map(|(id, _)| *id).collect();
+        if preview {
+            previewed.extend(&sent_ids);
+        }
 
-        for id in &sent_ids {
+        for (id, message_id) in sent.iter().filter(|_| !preview) {
             database.mark_notified(*id)?;
+            if let Some(message_id) = message_id {
+                let ch = pending
+                    .iter()
+                    .find(|n| n.id == *id)
+                    .and_then(|n| channel_map.get(&n.source_key))
+                    .map(|s| s.as_str());
+                database.set_channel_message_id(*id, notifier.target_channel(ch), message_id.0)?;
+            }
+        }
+
+        // 채널에 올라간 공지만 디스코드/슬랙에도 (실패해도 발송 기록은 그대로). 미리보기 모드는 제외
+        let mirrored: Vec<&db::Notice> = pending
+            .iter()
+            .filter(|n| !preview && sent_ids.contains(&n.id))
+            .collect();
+        if let Some(url) = cfg.discord.webhook_url().filter(|_| !mirrored.is_empty()) {
+            let discord = webhook::WebhookNotifier::new(
+                clients.for_source(false).clone(),
+                url,
+                cfg.bot.message_delay_ms,
+                "discord",
+                discord::embed_payload,
+            );
+            let count = discord.mirror(&mirrored).await;
+            tracing::info!(count, "Mirrored to Discord");
+        }
+        if let Some(url) = cfg.slack.webhook_url() {
+            let for_slack: Vec<&db::Notice> = mirrored
+                .iter()
+                .copied()
+                .filter(|n| cfg.slack.sources.contains(&n.source_key))
+                .collect();
+            if !for_slack.is_empty() {
+                let slack = webhook::WebhookNotifier::new(
+                    clients.for_source(false).clone(),
+                    url,
+                    cfg.bot.message_delay_ms,
+                    "slack",
+                    slack::blocks_payload,
+                );
+                let count = slack.mirror(&for_slack).await;
+                tracing::info!(count, "Mirrored to Slack");
+            }
         }
 
-        sent_ids.len()
+        // 실패한 공지는 backoff 후 다음 사이클에 재시도
+        for notice in pending.iter().take(max).filter(|n| !preview && !sent_ids.contains(&n.id)) {
+            let attempts = database.record_send_failure(notice.id)?;
+            if let Some((markup, parts)) = notifier.send_progress(notice.id) {
+                database.set_send_progress(notice.id, markup.name(), parts)?;
+            }
+            tracing::warn!(
+                notice_id = %notice.notice_id,
+                attempts,
+                "Channel send failed, queued for retry"
+            );
+        }
+
+        sent_ids.len() + edited_ids.len()
     } else {
-        // Dry-run: print and mark as notified to avoid re-showing
+        // Dry-run: print and mark as notified to avoid re-showing (미리보기 모드는 기록하지 않음)
         for notice in &pending {
             println!(
                 "[DRY-RUN] Would send: {} {} - {}",
@@ -295,42 +1308,91 @@ async fn do_crawl(
                 notice.source_display_name,
                 notice.title
             );
-            database.mark_notified(notice.id)?;
+            if preview {
+                previewed.insert(notice.id);
+            } else {
+                database.mark_notified(notice.id)?;
+            }
         }
         pending.len()
     };
 
+    if notifier_opt.is_some() {
+        metrics.add_channel_sent(sent as u64);
+    }
+
     // 마감일 추출 + 저장
     {
-        use crate::deadline::extract_deadline;
-        let recent = database.get_recent_for_dm(100).unwrap_or_default();
+        use crate::deadline::extract_deadline_detail;
+        let recent = database
+            .get_recent_for_dm(chrono::Duration::hours(cfg.bot.dm_lookback_hours as i64), cfg.bot.dm_scan_limit)
+            .unwrap_or_default();
         for notice in &recent {
-            if let Some(dl) = extract_deadline(&notice.title) {
-                let _ = database.set_deadline(notice.id, &dl.format("%Y-%m-%d").to_string());
+            if let Some(dl) = extract_deadline_detail(&notice.title) {
+                let _ = database.set_deadline(notice.id, &dl.to_db_string());
             }
         }
     }
 
     // DM 발송 (구독자에게 개인 메시지)
-    let dm_sent = if let Some(notifier) = notifier_opt {
-        let engine = dm_engine::DmEngine::new(notifier.bot(), &database, cfg.bot.message_delay_ms);
+    let dm = if preview {
+        // 미리보기 모드: 구독자에게는 보내지 않는다 (건너뛴 것으로 기록하지도 않음)
+        tracing::info!("Preview mode; DMs suppressed");
+        dm_engine::DmOutcome::default()
+    } else if !cfg.bot.enable_dm {
+        let skipped = database.skip_pending_dms(chrono::Duration::hours(cfg.bot.dm_lookback_hours as i64))?;
+        if skipped > 0 {
+            tracing::info!(count = skipped, "DM disabled; skipped DM matching");
+        }
+        dm_engine::DmOutcome::default()
+    } else if let Some(notifier) = notifier_opt {
+        let bot = dm_bot.unwrap_or(notifier.bot());
+        let engine = dm_engine::DmEngine::new(bot, database, cfg.bot.message_delay_ms)
+            .with_template(cfg.templates.dm_template.clone())
+            .with_rate_limiter(notifier.rate_limiter())
+            .with_lookback(cfg.bot.dm_lookback_hours, cfg.bot.dm_scan_limit)
+            .with_daily_cap(cfg.bot.max_dms_per_user_per_day)
+            .with_match_body(cfg.bot.match_body)
+            .with_dismiss_button(dm_bot.is_none())
+            .with_source_langs(
+                cfg.sources
+                    .iter()
+                    .filter_map(|s| s.lang.as_ref().map(|l| (s.key.clone(), l.clone())))
+                    .collect(),
+            );
         match engine.process().await {
-            Ok(count) => count,
+            Ok(outcome) => outcome,
             Err(e) => {
                 tracing::error!(error = %e, "DM engine failed");
-                0
+                dm_engine::DmOutcome::default()
             }
         }
     } else {
-        0
+        dm_engine::DmOutcome::default()
     };
+    let dm_sent = dm.sent;
+
+    metrics.add_dm_sent(dm_sent as u64);
+    metrics.add_dm_failures(dm_engine::DmFailure::Blocked.as_str(), dm.blocked as u64);
+    metrics.add_dm_failures(dm_engine::DmFailure::ChatNotFound.as_str(), dm.chat_not_found as u64);
+    metrics.add_dm_failures(dm_engine::DmFailure::Other.as_str(), dm.other as u64);
+    metrics.add_users_deactivated(dm.deactivated as u64);
 
     // Summary
+    let dm_failed = if dm.failed() > 0 {
+        format!(
+            " (fail: {} blocked / {} not-found / {} other, {} deactivated)",
+            dm.blocked, dm.chat_not_found, dm.other, dm.deactivated
+        )
+    } else {
+        String::new()
+    };
     let summary = format!(
-        "\u{2705} Crawl done: {} new / {} ch-sent / {} dm | {}",
+        "\u{2705} Crawl done: {} new / {} ch-sent / {} dm{} | {}",
         total_new,
         sent,
         dm_sent,
+        dm_failed,
         source_stats.join(" ")
     );
     tracing::info!("{}", summary);
@@ -341,15 +1403,64 @@ async fn do_crawl(
         }
     }
 
-    Ok(())
+    Ok(trigger::CrawlCounts { new: total_new, sent, dm_sent })
+}
+
+/// TLS 검증 여부별 HTTP 클라이언트 + 호스트별 요청 간격 게이트.
+/// 기본은 `strict`, 인증서가 깨진 사이트만 `tls_insecure` 옵트인으로 `insecure` 사용.
+/// `bot.user_agent`가 목록이면 UA마다 클라이언트를 두고 요청마다 차례로 돌려 쓴다.
+/// 모든 클라이언트가 쿠키 저장소 하나를 같이 써서, 첫 페이지에서 받은 세션 쿠키가
+/// 이어지는 목록/AJAX 요청에 실린다.
+struct HttpClients {
+    strict: Vec<reqwest::Client>,
+    insecure: Vec<reqwest::Client>,
+    next: AtomicUsize,
+    throttle: throttle::HostThrottle,
+}
+
+impl HttpClients {
+    fn build(
+        host_delay_ms: u64,
+        user_agent: &config::UserAgent,
+        timeouts: config::HttpTimeouts,
+    ) -> anyhow::Result<Self> {
+        let mut user_agents = user_agent.list();
+        if user_agents.is_empty() {
+            user_agents.push(config::DEFAULT_USER_AGENT.to_string());
+        }
+        let jar = Arc::new(reqwest::cookie::Jar::default());
+        let build = |insecure: bool| -> anyhow::Result<Vec<reqwest::Client>> {
+            user_agents.iter().map(|ua| build_http_client(insecure, ua, jar.clone(), timeouts)).collect()
+        };
+        Ok(Self {
+            strict: build(false)?,
+            insecure: build(true)?,
+            next: AtomicUsize::new(0),
+            throttle: throttle::HostThrottle::new(host_delay_ms),
+        })
+    }
+
+    fn for_source(&self, tls_insecure: bool) -> &reqwest::Client {
+        let clients = if tls_insecure { &self.insecure } else { &self.strict };
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % clients.len();
+        &clients[i]
+    }
 }
 
-/// HTTP 클라이언트 생성 (SSL 인증서 문제 우회).
-fn build_http_client() -> anyhow::Result<reqwest::Client> {
+/// HTTP 클라이언트 생성. `accept_invalid_certs`는 인증서가 깨진 사이트 전용.
+/// 접속 제한(`connect`)과 요청 전체 제한(`request`)을 따로 둔다.
+fn build_http_client(
+    accept_invalid_certs: bool,
+    user_agent: &str,
+    cookies: Arc<reqwest::cookie::Jar>,
+    timeouts: config::HttpTimeouts,
+) -> anyhow::Result<reqwest::Client> {
     Ok(reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .user_agent("CBNU-Notice-Bot/1.0 (student project)")
-        .timeout(Duration::from_secs(15))
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .user_agent(user_agent)
+        .cookie_provider(cookies)
+        .connect_timeout(timeouts.connect)
+        .timeout(timeouts.request)
         .build()?)
 }
 
@@ -368,24 +1479,92 @@ fn resolve_channels(cfg: &config::Config) -> (String, Option<String>) {
     (channel_id, log_channel_id)
 }
 
-/// 최대 3회 재시도 (2초 → 4초 → 8초 backoff)
+/// 파싱은 됐는데 0건이면 (사이트 개편 의심) 원본 HTML을 남긴다. `alert`가 있으면
+/// 로그 채널에도 알린다.
+async fn save_empty_snapshot(
+    parser: &dyn NoticeParser,
+    body: &str,
+    keep: usize,
+    alert: Option<&notifier::Notifier>,
+) {
+    let source_key = parser.source_key();
+    let saved = snapshot::save_snapshot(
+        Path::new(snapshot::SNAPSHOT_DIR),
+        source_key,
+        body,
+        parser.snapshot_ext(),
+        keep,
+        kst::now().naive_local(),
+    );
+    match &saved {
+        Ok(path) => tracing::warn!(source = %source_key, path = %path.display(), "Parsed 0 notices; saved snapshot"),
+        Err(e) => tracing::warn!(source = %source_key, error = %e, "Parsed 0 notices; snapshot failed"),
+    }
+
+    if let Some(notifier) = alert {
+        let mut alert = format!("\u{26a0}\u{fe0f} {} 0건 파싱 — 사이트 구조 변경 의심", source_key);
+        if let Ok(path) = &saved {
+            alert.push_str(&format!("\n스냅샷: {}", path.display()));
+        }
+        let _ = notifier.send_error_alert(&alert).await;
+    }
+}
+
+/// 목록 요청 재시도 정책 (`bot.fetch_retries`, `bot.fetch_backoff_ms`).
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    retries: u32,
+    backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    fn from_config(bot: &config::BotConfig) -> Self {
+        Self {
+            retries: bot.fetch_retries,
+            backoff_ms: bot.fetch_backoff_ms,
+        }
+    }
+
+    /// `attempt`번째(0부터) 재시도 전 대기 시간. `jitter`는 0.5~1.5 배율이며
+    /// 여러 소스가 동시에 실패해도 같은 순간에 몰려 재시도하지 않게 한다.
+    fn delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let base = self.backoff_ms.saturating_mul(1u64 << attempt.min(16));
+        Duration::from_millis((base as f64 * jitter.clamp(0.5, 1.5)) as u64)
+    }
+}
+
+/// 재시도 지터 배율 (0.5 이상 1.5 미만).
+fn jitter_factor() -> f64 {
+    0.5 + fastrand::f64()
+}
+
+/// 목록 요청. 일시적 오류(타임아웃·연결 실패·5xx)만 다시 시도하고
+/// 404나 파싱 오류처럼 다시 해도 같은 오류는 바로 돌려준다.
 async fn fetch_with_retry(
     parser: &dyn NoticeParser,
     client: &reqwest::Client,
-) -> anyhow::Result<Vec<RawNotice>> {
-    let max_retries = 3;
+    throttle: &throttle::HostThrottle,
+    url: &str,
+    retry: &RetryPolicy,
+) -> anyhow::Result<parser::FetchedList> {
+    let max_retries = retry.retries;
     let mut last_err = None;
 
     for attempt in 0..=max_retries {
-        match parser.fetch_notices(client).await {
-            Ok(notices) => return Ok(notices),
+        throttle.wait(url).await;
+        match parser.fetch_notices(client, throttle).await {
+            Ok(fetched) => return Ok(fetched),
             Err(e) => {
+                if !error::is_retryable(&e) {
+                    tracing::warn!(source = %parser.source_key(), error = %e, "Fetch failed, not retryable");
+                    return Err(e);
+                }
                 if attempt < max_retries {
-                    let delay = Duration::from_secs(2u64.pow(attempt as u32 + 1));
+                    let delay = retry.delay(attempt, jitter_factor());
                     tracing::warn!(
                         source = %parser.source_key(),
                         attempt = attempt + 1,
-                        delay_secs = delay.as_secs(),
+                        delay_ms = delay.as_millis() as u64,
                         error = %e,
                         "Fetch failed, retrying"
                     );
@@ -398,3 +1577,440 @@ async fn fetch_with_retry(
 
     Err(last_err.unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 요청의 User-Agent 헤더를 그대로 돌려주는 로컬 서버. 반환: 기본 URL.
+    async fn echo_user_agent_server() -> String {
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                headers
+                    .get(axum::http::header::USER_AGENT)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/", addr)
+    }
+
+    /// 두 건짜리 목록 (`mock_config`의 선택자에 맞춘 모양).
+    const MOCK_LIST_HTML: &str =
+        "<ul><li><a href=\"view?no=2\">둘째 공지</a></li><li><a href=\"view?no=1\">첫 공지</a></li></ul>";
+
+    /// `/list`에서 `html`을 돌려주는 로컬 서버. 반환: 주소.
+    async fn mock_list_server(html: &'static str) -> std::net::SocketAddr {
+        let app = axum::Router::new().route("/list", axum::routing::get(move || async move { axum::response::Html(html) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    /// `url`을 읽는 css 소스(`mock`) 하나짜리 설정. `extra_bot_toml`은 `[bot]`에 덧붙인다.
+    fn mock_config(url: &str, extra_bot_toml: &str) -> config::Config {
+        toml::from_str(&format!(
+            "[[source]]\nkey = \"mock\"\ndisplay_name = \"목\"\nparser = \"css\"\nurl = \"{}\"\n\
+             [source.params]\nrow_selector = \"li\"\nid_regex = \"no=(\\\\d+)\"\n\
+             [bot]\ntelegram_channel = \"@c\"\nhost_delay_ms = 0\n{}\n[database]\n",
+            url, extra_bot_toml
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_php_master_session_cookie() {
+        use axum::http::{header, HeaderMap};
+        use axum::routing::{get, post};
+
+        // 첫 페이지가 세션 쿠키를 주고, AJAX 목록은 그 쿠키가 있어야 내용을 준다
+        let app = axum::Router::new()
+            .route(
+                "/master.php",
+                get(|| async {
+                    (
+                        [(header::SET_COOKIE, "PHPSESSID=s3ss10n; Path=/")],
+                        r#"<input id="bidx" value="5"><input id="id" value="notice">"#,
+                    )
+                }),
+            )
+            .route(
+                "/module/board/_main.php",
+                post(|headers: HeaderMap| async move {
+                    let cookie = headers.get(header::COOKIE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+                    if cookie.contains("PHPSESSID=s3ss10n") {
+                        r#"<div class="board_rows"><div>1</div><div><a href="?mod=view&pidx=42">세션 공지</a></div><div>관리자</div><div>2026-03-01</div><div>10</div></div>"#
+                    } else {
+                        ""
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let source = config::SourceConfig {
+            key: "biz".into(),
+            display_name: "경영학부".into(),
+            parser: "php_master".into(),
+            url: format!("http://{}", addr),
+            params: HashMap::from([("pg_idx".to_string(), "7".to_string())]),
+            ..Default::default()
+        };
+        let parser = parser::create_parser(&source).unwrap();
+        let clients = HttpClients::build(50, &config::UserAgent::default(), config::HttpTimeouts::default()).unwrap();
+        // 첫 요청 전 대기는 호출한 쪽(`fetch_with_retry`), AJAX 요청 전 대기는 파서가 한다
+        clients.throttle.wait(&source.url).await;
+        let started = std::time::Instant::now();
+        let notices = parser.fetch_notices(clients.for_source(false), &clients.throttle).await.unwrap().notices;
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].notice_id, "42");
+        assert_eq!(notices[0].title, "세션 공지");
+    }
+
+    #[tokio::test]
+    async fn test_crawl_cycles_share_one_connection() {
+        let addr = mock_list_server(MOCK_LIST_HTML).await;
+
+        let cfg = mock_config(&format!("http://{}/list", addr), "");
+        let clients = HttpClients::build(0, &cfg.bot.user_agent, cfg.bot.http_timeouts()).unwrap();
+        let metrics = metrics::Metrics::new();
+        let shutdown = CancellationToken::new();
+
+        // 메모리 DB는 연결마다 따로라서, 사이클이 연결을 새로 열면 앞 사이클 기록이 사라진다
+        let database = db::Database::init(":memory:").unwrap();
+        for _ in 0..2 {
+            do_crawl(&cfg, &clients, &database, None, None, &metrics, &shutdown, &mut HashSet::new()).await.unwrap();
+        }
+        let stored = database.get_latest_by_source("mock", 10).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(database.get_pending(10, config::PostOrder::Latest, &HashMap::new()).unwrap().is_empty());
+        assert_eq!(database.get_last_notice_id("mock").unwrap().as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_preview_leaves_pending_queue() {
+        let addr = mock_list_server(MOCK_LIST_HTML).await;
+
+        let mut cfg = mock_config(&format!("http://{}/list", addr), "preview_channel = \"@preview\"");
+        let clients = HttpClients::build(0, &cfg.bot.user_agent, cfg.bot.http_timeouts()).unwrap();
+        let metrics = metrics::Metrics::new();
+        let shutdown = CancellationToken::new();
+        let database = db::Database::init(":memory:").unwrap();
+        let pending = |database: &db::Database| {
+            database.get_pending(10, config::PostOrder::Latest, &HashMap::new()).unwrap().len()
+        };
+
+        // 미리보기 실행은 수집만 하고 대기열은 운영 게시를 위해 남긴다
+        let mut previewed = HashSet::new();
+        let counts = do_crawl(&cfg, &clients, &database, None, None, &metrics, &shutdown, &mut previewed).await.unwrap();
+        assert_eq!(counts.sent, 2);
+        assert_eq!(pending(&database), 2);
+
+        // serve 모드 다음 사이클: 이미 미리 본 공지는 다시 보내지 않는다
+        let counts = do_crawl(&cfg, &clients, &database, None, None, &metrics, &shutdown, &mut previewed).await.unwrap();
+        assert_eq!(counts.sent, 0);
+        assert_eq!(pending(&database), 2);
+
+        cfg.bot.preview_channel = None;
+        let counts = do_crawl(&cfg, &clients, &database, None, None, &metrics, &shutdown, &mut previewed).await.unwrap();
+        assert_eq!(counts.sent, 2);
+        assert_eq!(pending(&database), 0);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_rolls_back_failed_source() {
+        let addr = mock_list_server(
+            "<ul><li><a href=\"view?no=2\">둘째 공지</a></li><li><a href=\"view?no=1\">boom</a></li></ul>",
+        )
+        .await;
+
+        let mut cfg = mock_config(&format!("http://{}/list", addr), "");
+        // 실패한 소스 뒤의 소스도 그대로 돈다
+        let healthy = config::SourceConfig {
+            key: "healthy".into(),
+            url: format!("http://{}/list", mock_list_server(MOCK_LIST_HTML).await),
+            ..cfg.sources[0].clone()
+        };
+        cfg.sources.push(healthy);
+        let clients = HttpClients::build(0, &cfg.bot.user_agent, cfg.bot.http_timeouts()).unwrap();
+        let metrics = metrics::Metrics::new();
+        let shutdown = CancellationToken::new();
+
+        let path = std::env::temp_dir().join(format!("cbnu_crawl_rollback_{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        let database = db::Database::init(&path).unwrap();
+        // 두 번째 공지 저장이 실패하도록
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER fail_insert BEFORE INSERT ON notices WHEN NEW.title = 'boom'
+                 BEGIN SELECT RAISE(ABORT, 'boom'); END;",
+            )
+            .unwrap();
+
+        let counts = do_crawl(&cfg, &clients, &database, None, None, &metrics, &shutdown, &mut HashSet::new()).await.unwrap();
+        // 먼저 저장한 공지도 롤백되고, 커서는 움직이지 않아 다음 사이클에 다시 읽는다
+        assert!(database.get_latest_by_source("mock", 10).unwrap().is_empty());
+        assert_eq!(database.get_last_notice_id("mock").unwrap(), None);
+        assert_eq!(database.get_error_count("mock").unwrap(), 1);
+        assert_eq!(database.get_latest_by_source("healthy", 10).unwrap().len(), 2);
+        assert_eq!(counts.new, 2);
+        drop(database);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries_only_transient_errors() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let (missing, slow) = (hits.clone(), hits.clone());
+        let app = axum::Router::new()
+            .route(
+                "/missing",
+                axum::routing::get(move || async move {
+                    missing.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::NOT_FOUND
+                }),
+            )
+            .route(
+                "/slow",
+                axum::routing::get(move || async move {
+                    slow.fetch_add(1, Ordering::SeqCst);
+                    sleep(Duration::from_millis(500)).await;
+                    "<ul></ul>"
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+        let throttle = throttle::HostThrottle::new(0);
+        let retry = RetryPolicy { retries: 2, backoff_ms: 1 };
+        let mock_parser = |path: &str| {
+            let cfg = mock_config(&format!("http://{}/{}", addr, path), "");
+            parser::create_parser(&cfg.sources[0]).unwrap()
+        };
+
+        // 404: 다시 해도 같으므로 한 번만
+        let err = fetch_with_retry(mock_parser("missing").as_ref(), &client, &throttle, "", &retry)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+        assert_eq!(hits.swap(0, Ordering::SeqCst), 1);
+
+        // 타임아웃: 재시도 횟수만큼 더
+        assert!(fetch_with_retry(mock_parser("slow").as_ref(), &client, &throttle, "", &retry).await.is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_configured_http_timeouts() {
+        let cfg: config::Config = toml::from_str(
+            "source = []\n[bot]\ntelegram_channel = \"@c\"\nconnect_timeout_secs = 1\nrequest_timeout_secs = 1\n[database]\n",
+        )
+        .unwrap();
+        let timeouts = cfg.bot.http_timeouts();
+        assert_eq!(timeouts.connect, Duration::from_secs(1));
+        assert_eq!(timeouts.request, Duration::from_secs(1));
+        assert_eq!(config::HttpTimeouts::default().request, Duration::from_secs(15));
+
+        let app = axum::Router::new().route(
+            "/slow",
+            axum::routing::get(|| async {
+                sleep(Duration::from_secs(3)).await;
+                "late"
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // 요청 전체 제한: 접속은 되지만 응답이 느리면 설정한 시간에 끊긴다
+        let clients = HttpClients::build(0, &config::UserAgent::default(), timeouts).unwrap();
+        let started = std::time::Instant::now();
+        let err = clients.for_source(false).get(format!("http://{}/slow", addr)).send().await.unwrap_err();
+        assert!(err.is_timeout());
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // 접속 제한: 받아 주지 않는 소켓(대기열이 찬 리스너)에는 전체 제한보다 먼저 포기한다
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let full = socket.listen(0).unwrap();
+        let addr = full.local_addr().unwrap();
+        let mut queued = Vec::new();
+        for _ in 0..4 {
+            let connect = tokio::net::TcpStream::connect(addr);
+            if let Ok(Ok(stream)) = tokio::time::timeout(Duration::from_millis(100), connect).await {
+                queued.push(stream);
+            }
+        }
+        let connect_only = config::HttpTimeouts { connect: Duration::from_millis(200), request: Duration::from_secs(30) };
+        let clients = HttpClients::build(0, &config::UserAgent::default(), connect_only).unwrap();
+        let started = std::time::Instant::now();
+        let err = clients.for_source(false).get(format!("http://{}/", addr)).send().await.unwrap_err();
+        assert!(err.is_connect() && err.is_timeout(), "{:?}", err);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_configured_user_agent() {
+        let url = echo_user_agent_server().await;
+        let fetch = |client: &reqwest::Client| {
+            let req = client.get(&url);
+            async move { req.send().await.unwrap().text().await.unwrap() }
+        };
+
+        let clients = HttpClients::build(0, &config::UserAgent::default(), config::HttpTimeouts::default()).unwrap();
+        assert_eq!(fetch(clients.for_source(false)).await, config::DEFAULT_USER_AGENT);
+
+        let fixed = config::UserAgent::Fixed("Mozilla/5.0 (test)".into());
+        let clients = HttpClients::build(0, &fixed, config::HttpTimeouts::default()).unwrap();
+        assert_eq!(fetch(clients.for_source(false)).await, "Mozilla/5.0 (test)");
+        assert_eq!(fetch(clients.for_source(true)).await, "Mozilla/5.0 (test)");
+
+        // 목록이면 요청마다 차례로
+        let rotate = config::UserAgent::Rotate(vec!["ua-a".into(), " ".into(), "ua-b".into()]);
+        let clients = HttpClients::build(0, &rotate, config::HttpTimeouts::default()).unwrap();
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(fetch(clients.for_source(false)).await);
+        }
+        assert_eq!(seen, vec!["ua-a", "ua-b", "ua-a", "ua-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_next_cycle() {
+        let token = CancellationToken::new();
+        let (trigger, mut requests) = trigger::channel();
+        assert!(matches!(
+            wait_next_cycle(Instant::now() + Duration::from_millis(10), &token, &mut requests).await,
+            Wake::Scheduled
+        ));
+
+        // `/crawl` 요청은 긴 대기 중에도 바로 깨우고, 응답이 요청한 쪽으로 돌아간다
+        let asked = tokio::spawn({
+            let trigger = trigger.clone();
+            async move { trigger.run(Some("biz".into())).await }
+        });
+        let started = std::time::Instant::now();
+        match wait_next_cycle(Instant::now() + Duration::from_secs(600), &token, &mut requests).await {
+            Wake::Manual(req) => {
+                assert_eq!(req.source.as_deref(), Some("biz"));
+                req.reply.send(Ok(trigger::CrawlCounts { new: 3, sent: 2, dm_sent: 1 })).unwrap();
+            }
+            _ => panic!("expected manual wake"),
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(asked.await.unwrap().unwrap().new, 3);
+
+        // 요청 쪽이 모두 사라져도 예약 주기는 그대로
+        drop(trigger);
+        assert!(matches!(
+            wait_next_cycle(Instant::now() + Duration::from_millis(10), &token, &mut requests).await,
+            Wake::Scheduled
+        ));
+
+        // 긴 대기 중이라도 종료 신호가 오면 즉시 깨어난다
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            wait_next_cycle(Instant::now() + Duration::from_secs(600), &token, &mut requests).await,
+            Wake::Shutdown
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // 이미 취소된 토큰은 바로 반환
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            wait_next_cycle(Instant::now() + Duration::from_secs(600), &token, &mut requests).await,
+            Wake::Shutdown
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_retry_delay_jitter_range() {
+        let retry = RetryPolicy { retries: 3, backoff_ms: 2000 };
+        // 지터 1.0이면 기존과 같은 2s, 4s, 8s
+        assert_eq!(retry.delay(0, 1.0), Duration::from_secs(2));
+        assert_eq!(retry.delay(2, 1.0), Duration::from_secs(8));
+        // 배율 범위 양 끝
+        assert_eq!(retry.delay(1, 0.5), Duration::from_secs(2));
+        assert_eq!(retry.delay(1, 1.5), Duration::from_secs(6));
+
+        for _ in 0..100 {
+            let d = retry.delay(1, jitter_factor());
+            assert!(d >= Duration::from_secs(2) && d <= Duration::from_secs(6), "{:?}", d);
+        }
+    }
+
+    #[test]
+    fn test_config_path_override() {
+        let cli = Cli::try_parse_from(["cbnu-notice-bot", "crawl", "--config", "/etc/cbnu/second.toml"]).unwrap();
+        assert!(matches!(cli.command, Commands::Crawl));
+        assert_eq!(
+            resolve_config_path(cli.config, Some("env.toml".into())),
+            PathBuf::from("/etc/cbnu/second.toml")
+        );
+
+        // 플래그가 없으면 환경변수, 그것도 없으면 config.toml
+        let cli = Cli::try_parse_from(["cbnu-notice-bot", "serve"]).unwrap();
+        assert_eq!(resolve_config_path(cli.config, Some("env.toml".into())), PathBuf::from("env.toml"));
+        assert_eq!(resolve_config_path(None, Some(" ".into())), PathBuf::from("config.toml"));
+        assert_eq!(resolve_config_path(None, None), PathBuf::from("config.toml"));
+    }
+
+    #[test]
+    fn test_preview_channel_flag_precedence() {
+        let load = |extra: &str| -> config::Config {
+            toml::from_str(&format!("source = []\n[bot]\ntelegram_channel = \"@c\"\n{}\n[database]\n", extra)).unwrap()
+        };
+
+        // 플래그 > config > 없음
+        let cli = Cli::try_parse_from(["cbnu-notice-bot", "crawl", "--preview-channel", "@flag"]).unwrap();
+        let mut cfg = load("preview_channel = \"@config\"");
+        apply_preview_channel(&mut cfg, cli.preview_channel);
+        assert_eq!(cfg.bot.preview_channel.as_deref(), Some("@flag"));
+
+        let mut cfg = load("preview_channel = \"@config\"");
+        apply_preview_channel(&mut cfg, None);
+        assert_eq!(cfg.bot.preview_channel.as_deref(), Some("@config"));
+
+        let cli = Cli::try_parse_from(["cbnu-notice-bot", "serve"]).unwrap();
+        let mut cfg = load("");
+        apply_preview_channel(&mut cfg, cli.preview_channel);
+        assert_eq!(cfg.bot.preview_channel, None);
+    }
+
+    #[test]
+    fn test_select_dm_token() {
+        let lookup = |name: &str| match name {
+            "DM_TOKEN" => Some(" 123:abc ".to_string()),
+            "EMPTY_TOKEN" => Some(String::new()),
+            _ => None,
+        };
+
+        assert_eq!(select_dm_token(Some("DM_TOKEN"), lookup).as_deref(), Some("123:abc"));
+        // 설정이 없거나 변수가 비어 있으면 채널 봇으로 폴백
+        assert_eq!(select_dm_token(None, lookup), None);
+        assert_eq!(select_dm_token(Some(""), lookup), None);
+        assert_eq!(select_dm_token(Some("EMPTY_TOKEN"), lookup), None);
+        assert_eq!(select_dm_token(Some("MISSING"), lookup), None);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+/// Prometheus 노출용 크롤/발송 카운터.
+/// serve 모드에서는 크롤 스레드와 `/metrics` 서버가 `Arc`로 공유한다.
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<MetricsInner>,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    notices_new: BTreeMap<String, u64>,
+    crawl_errors: BTreeMap<String, u64>,
+    last_crawl: BTreeMap<String, i64>,
+    dm_sent: u64,
+    channel_sent: u64,
+    dm_failures: BTreeMap<String, u64>,
+    users_deactivated: u64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 소스별 신규 공지 수 누적 + 마지막 크롤 시각 갱신.
+    pub fn record_crawl(&self, source_key: &str, new_count: u64) {
+        let mut m = self.inner.lock().unwrap();
+        *m.notices_new.entry(source_key.to_string()).or_default() += new_count;
+        m.last_crawl
+            .insert(source_key.to_string(), chrono::Utc::now().timestamp());
+    }
+
+    pub fn record_crawl_error(&self, source_key: &str) {
+        let mut m = self.inner.lock().unwrap();
+        *m.crawl_errors.entry(source_key.to_string()).or_default() += 1;
+    }
+
+    pub fn add_channel_sent(&self, n: u64) {
+        self.inner.lock().unwrap().channel_sent += n;
+    }
+
+    pub fn add_dm_sent(&self, n: u64) {
+        self.inner.lock().unwrap().dm_sent += n;
+    }
+
+    /// 사유별 DM 실패 수와 자동 비활성화된 사용자 수 누적.
+    pub fn add_dm_failures(&self, reason: &str, n: u64) {
+        if n > 0 {
+            let mut m = self.inner.lock().unwrap();
+            *m.dm_failures.entry(reason.to_string()).or_default() += n;
+        }
+    }
+
+    pub fn add_users_deactivated(&self, n: u64) {
+        self.inner.lock().unwrap().users_deactivated += n;
+    }
+
+    /// Prometheus text exposition format (0.0.4)으로 직렬화.
+    pub fn render(&self) -> String {
+        let m = self.inner.lock().unwrap();
+        let mut out = String::new();
+
+        write_labeled(
+            &mut out,
+            "notices_new_total",
+            "counter",
+            "New notices discovered per source",
+            "source",
+            &m.notices_new,
+        );
+        write_labeled(
+            &mut out,
+            "crawl_errors_total",
+            "counter",
+            "Failed crawl attempts per source",
+            "source",
+            &m.crawl_errors,
+        );
+        write_labeled(
+            &mut out,
+            "last_crawl_timestamp",
+            "gauge",
+            "Unix time of the last successful crawl per source",
+            "source",
+            &m.last_crawl,
+        );
+        write_labeled(
+            &mut out,
+            "dm_failures_total",
+            "counter",
+            "Failed direct messages per reason",
+            "reason",
+            &m.dm_failures,
+        );
+
+        out.push_str("# HELP dm_sent_total Direct messages delivered to subscribers\n");
+        out.push_str("# TYPE dm_sent_total counter\n");
+        out.push_str(&format!("dm_sent_total {}\n", m.dm_sent));
+        out.push_str("# HELP channel_sent_total Notices posted to channels\n");
+        out.push_str("# TYPE channel_sent_total counter\n");
+        out.push_str(&format!("channel_sent_total {}\n", m.channel_sent));
+        out.push_str("# HELP users_deactivated_total Subscribers deactivated after blocking the bot\n");
+        out.push_str("# TYPE users_deactivated_total counter\n");
+        out.push_str(&format!("users_deactivated_total {}\n", m.users_deactivated));
+
+        out
+    }
+}
+
+fn write_labeled<V: std::fmt::Display>(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    label: &str,
+    values: &BTreeMap<String, V>,
+) {
+    out.push_str(&format!("# HELP {} {}\n", name, help));
+    out.push_str(&format!("# TYPE {} {}\n", name, kind));
+    for (key, v) in values {
+        out.push_str(&format!(
+            "{}{{{}=\"{}\"}} {}\n",
+            name,
+            label,
+            escape_label(key),
+            v
+        ));
+    }
+}
+
+/// 라벨 값의 `\`, `"`, 개행 이스케이프.
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(),
+    )
+}
+
+/// `0.0.0.0:{port}/metrics` 에서 메트릭 제공.
+pub async fn serve(metrics: Arc<Metrics>, port: u16) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics);
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    tracing::info!(port, "Metrics endpoint listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_handler_exposition() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.record_crawl("biz", 3);
+        metrics.record_crawl("biz", 2);
+        metrics.record_crawl_error("physics");
+        metrics.add_channel_sent(5);
+        metrics.add_dm_sent(7);
+        metrics.add_dm_failures("blocked", 2);
+        metrics.add_dm_failures("other", 0);
+        metrics.add_users_deactivated(2);
+
+        let resp = metrics_handler(State(metrics)).await.into_response();
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "text/plain; version=0.0.4"
+        );
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(text.contains("# TYPE notices_new_total counter"));
+        assert!(text.contains("notices_new_total{source=\"biz\"} 5"));
+        assert!(text.contains("crawl_errors_total{source=\"physics\"} 1"));
+        assert!(text.contains("last_crawl_timestamp{source=\"biz\"}"));
+        assert!(text.contains("channel_sent_total 5"));
+        assert!(text.contains("dm_sent_total 7"));
+        assert!(text.contains("dm_failures_total{reason=\"blocked\"} 2"));
+        assert!(!text.contains("reason=\"other\""));
+        assert!(text.contains("users_deactivated_total 2"));
+    }
+}
@@ -1,95 +1,399 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use teloxide::prelude::*;
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardMarkup, InputFile, InputMedia, InputMediaPhoto, LinkPreviewOptions,
+    MessageId, ParseMode, ThreadId,
+};
 use tokio::time::{sleep, Duration};
 
 use crate::category::Category;
+use crate::config::{BotConfig, PostOrder};
 use crate::db::Notice;
+use crate::format::{self, html_escape, split_message, split_message_as, Markup, MESSAGE_MAX_CHARS};
+use crate::throttle::RateLimiter;
 
 pub struct Notifier {
     bot: Bot,
     channel_id: String,
     log_channel_id: Option<String>,
     delay_ms: u64,
+    options: SendOptions,
+    /// 채널/DM/`/broadcast` 공용 발송 속도 제한 (`bot.send_rate_per_sec`).
+    limiter: Arc<RateLimiter>,
+    /// 공지 DB ID → (마크업, 이미 보낸 앞 조각 수). 나눠 보내다 실패한 공지를 이어 보낼 때 쓴다.
+    progress: Mutex<HashMap<i64, (Markup, usize)>>,
+}
+
+/// 채널 발송 옵션 (링크 미리보기, 카테고리별 포럼 토픽).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SendOptions {
+    pub disable_link_preview: bool,
+    pub category_topics: HashMap<String, i32>,
+    pub group_channel_posts: bool,
+    /// `[templates] channel_template`. 없으면 기본 레이아웃.
+    pub channel_template: Option<String>,
+    /// 소스 키 → 한 번에 보낼 최대 공지 수 (`[[source]] max_per_run`).
+    pub source_caps: HashMap<String, usize>,
+    /// 소스 키 → 포럼 토픽 (`[[source]] topic_id`). 카테고리 토픽보다 우선.
+    pub source_topics: HashMap<String, i32>,
+    /// 상세 페이지 이미지가 있으면 사진(앨범)으로 게시 (`bot.send_images`).
+    pub send_images: bool,
+    /// 게시 순서 (`bot.post_order`).
+    pub post_order: PostOrder,
+    /// 미리보기 채널 (`bot.preview_channel`). 모든 라우팅보다 우선한다.
+    pub preview_channel: Option<String>,
+    /// "원문 보기" 옆 "공유" 버튼 (`bot.show_share_button`).
+    pub show_share_button: bool,
+}
+
+impl SendOptions {
+    pub fn from_config(bot: &BotConfig) -> Self {
+        Self {
+            disable_link_preview: bot.disable_link_preview,
+            category_topics: bot.category_topics.clone(),
+            group_channel_posts: bot.group_channel_posts,
+            channel_template: None,
+            source_caps: HashMap::new(),
+            source_topics: HashMap::new(),
+            send_images: bot.send_images,
+            post_order: bot.post_order,
+            preview_channel: bot.preview_channel.clone(),
+            show_share_button: bot.show_share_button,
+        }
+    }
+
+    /// 채널 메시지 템플릿 지정.
+    pub fn with_channel_template(mut self, template: Option<String>) -> Self {
+        self.channel_template = template;
+        self
+    }
+
+    /// 소스별 발송 한도 지정 (`Config::source_caps`).
+    pub fn with_source_caps(mut self, caps: HashMap<String, usize>) -> Self {
+        self.source_caps = caps;
+        self
+    }
+
+    /// 소스별 포럼 토픽 지정 (`Config::source_topics`).
+    pub fn with_source_topics(mut self, topics: HashMap<String, i32>) -> Self {
+        self.source_topics = topics;
+        self
+    }
+
+    /// 채널 게시물의 "원문 보기" 버튼 (켜져 있으면 같은 줄에 "공유" 버튼).
+    fn source_keyboard(&self, url: &reqwest::Url, title: &str) -> InlineKeyboardMarkup {
+        let mut row = vec![InlineKeyboardButton::url("\u{1f517} 원문 보기", url.clone())];
+        if self.show_share_button {
+            row.push(InlineKeyboardButton::url("\u{1f4e4} 공유", share_url(url, title)));
+        }
+        InlineKeyboardMarkup::new(vec![row])
+    }
+
+    fn link_preview(&self) -> Option<LinkPreviewOptions> {
+        self.disable_link_preview.then_some(LinkPreviewOptions {
+            is_disabled: true,
+            url: None,
+            prefer_small_media: false,
+            prefer_large_media: false,
+            show_above_text: false,
+        })
+    }
+
+    /// 카테고리 태그에 해당하는 포럼 토픽. 설정이 없으면 `None` (일반 게시).
+    fn thread_for(&self, category: &str) -> Option<ThreadId> {
+        self.category_topics
+            .get(category)
+            .map(|id| ThreadId(MessageId(*id)))
+    }
+
+    /// 게시 대상 (채널, 토픽): 미리보기 채널 > 소스별 채널 > 기본 채널.
+    /// 토픽은 소스의 `topic_id`가 있으면 그것, 없으면 기본 채널(포럼 그룹)에서만 카테고리 토픽.
+    /// 둘 다 없으면 일반(General) 스레드.
+    fn route<'a>(
+        &'a self,
+        default_channel: &'a str,
+        channel_override: Option<&'a str>,
+        source_key: &str,
+        category: &str,
+    ) -> (&'a str, Option<ThreadId>) {
+        if let Some(preview) = &self.preview_channel {
+            return (preview, None);
+        }
+        if let Some(topic) = self.source_topics.get(source_key) {
+            return (channel_override.unwrap_or(default_channel), Some(ThreadId(MessageId(*topic))));
+        }
+        match channel_override {
+            Some(channel) => (channel, None),
+            None => (default_channel, self.thread_for(category)),
+        }
+    }
+
+    /// 미리보기 모드면 메시지 앞에 붙일 표시.
+    fn preview_tag(&self, markup: Markup) -> String {
+        if self.preview_channel.is_some() {
+            markup.escape("[PREVIEW] ")
+        } else {
+            String::new()
+        }
+    }
 }
 
 impl Notifier {
-    pub fn new(bot: Bot, channel_id: String, log_channel_id: Option<String>, delay_ms: u64) -> Self {
+    pub fn new(
+        bot: Bot,
+        channel_id: String,
+        log_channel_id: Option<String>,
+        delay_ms: u64,
+        options: SendOptions,
+    ) -> Self {
         Self {
             bot,
             channel_id,
             log_channel_id,
             delay_ms,
+            options,
+            limiter: Arc::new(RateLimiter::new(0)),
+            progress: Mutex::new(HashMap::new()),
         }
     }
 
+    /// 초당 발송 건수 제한 (0이면 제한 없음). DM 엔진도 같은 제한을 쓴다.
+    pub fn with_rate_limit(mut self, per_sec: u32) -> Self {
+        self.limiter = Arc::new(RateLimiter::new(per_sec));
+        self
+    }
+
+    /// 다른 곳(`/broadcast`)과 나눠 쓰는 발송 속도 제한.
+    pub fn with_shared_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// 공용 발송 속도 제한 (DM 엔진용).
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    /// 지난 사이클에 앞 조각만 보낸 공지들 (`Database::get_send_progress`). 기존 기록은 버린다.
+    pub fn resume_progress(&self, progress: HashMap<i64, (Markup, usize)>) {
+        *self.progress.lock().unwrap() = progress;
+    }
+
+    /// 발송 실패한 공지의 (마크업, 이미 보낸 앞 조각 수). 한 조각도 안 나갔으면 `None`.
+    pub fn send_progress(&self, id: i64) -> Option<(Markup, usize)> {
+        self.progress.lock().unwrap().get(&id).copied()
+    }
+
+    /// 소스별 채널 지정을 반영한 실제 게시 채널 (메시지 ID와 함께 기록).
+    pub fn target_channel<'a>(&'a self, channel_override: Option<&'a str>) -> &'a str {
+        self.options.route(&self.channel_id, channel_override, "", "").0
+    }
+
     /// Bot 인스턴스 참조 (DM 엔진용).
     pub fn bot(&self) -> &Bot {
         &self.bot
     }
 
     /// Send a single notice to the specified channel (or default).
-    pub async fn send_notice(&self, notice: &Notice, channel_override: Option<&str>) -> anyhow::Result<()> {
-        let target_channel = channel_override.unwrap_or(&self.channel_id);
-        let category = Category::from_str_tag(&notice.category);
-        let cat_tag = if notice.category != "general" {
-            format!("[{}] ", category.label())
-        } else {
-            String::new()
+    /// MarkdownV2 해석에 실패하면 같은 내용을 HTML로 다시 보낸다 (아직 한 조각도 안 나갔을 때만).
+    /// 앞 조각만 보내고 실패했으면 진행 상황을 남겨 두고, 다음 시도는 남은 조각부터 같은 마크업으로.
+    /// 반환: 채널 메시지 ID.
+    pub async fn send_notice(&self, notice: &Notice, channel_override: Option<&str>) -> anyhow::Result<MessageId> {
+        let url = reqwest::Url::parse(&notice.url)?;
+        let (mut markup, skip) = self.send_progress(notice.id).unwrap_or((Markup::MarkdownV2, 0));
+        let result = match self.send_notice_as(notice, &url, channel_override, markup, skip).await {
+            Err((0, e)) if markup == Markup::MarkdownV2 && format::is_parse_error(&e) => {
+                tracing::warn!(
+                    notice_id = %notice.notice_id,
+                    error = %e,
+                    "MarkdownV2 rejected, retrying as HTML"
+                );
+                markup = Markup::Html;
+                self.send_notice_as(notice, &url, channel_override, markup, 0).await
+            }
+            result => result,
         };
+        match result {
+            Ok(message_id) => {
+                self.progress.lock().unwrap().remove(&notice.id);
+                Ok(message_id)
+            }
+            Err((parts_sent, e)) => {
+                if parts_sent > 0 {
+                    self.progress.lock().unwrap().insert(notice.id, (markup, parts_sent));
+                }
+                Err(anyhow::anyhow!("Telegram send failed: {}", e))
+            }
+        }
+    }
 
-        let date_str = notice
-            .published
-            .as_deref()
-            .unwrap_or("날짜 미상");
-        let author_str = notice
-            .author
-            .as_deref()
-            .unwrap_or("작성자 미상");
-
-        // Build message text (MarkdownV2 escaped)
-        let text = format!(
-            "{emoji} *{source}*\n\n{cat}{title}\n\n\u{1f4c5} {date} \\| \u{270d}\u{fe0f} {author}",
-            emoji = category.emoji(),
-            source = escape_markdown(&notice.source_display_name),
-            cat = escape_markdown(&cat_tag),
-            title = escape_markdown(&notice.title),
-            date = escape_markdown(date_str),
-            author = escape_markdown(author_str),
-        );
+    /// 이미 올린 채널 메시지를 현재 내용(수정됨 표시 포함)으로 고친다 (`bot.edit_on_update`).
+    pub async fn edit_notice(
+        &self,
+        notice: &Notice,
+        channel_override: Option<&str>,
+        message_id: MessageId,
+    ) -> anyhow::Result<()> {
+        let url = reqwest::Url::parse(&notice.url)?;
+        let (target_channel, _) = self.options.route(&self.channel_id, channel_override, &notice.source_key, &notice.category);
+        let edit = |markup: Markup| {
+            let req = self
+                .bot
+                .edit_message_text(target_channel.to_string(), message_id, self.channel_text(notice, markup))
+                .parse_mode(markup.parse_mode())
+                .reply_markup(self.options.source_keyboard(&url, &notice.title));
+            async move {
+                self.limiter.acquire().await;
+                req.await
+            }
+        };
+        let result = match edit(Markup::MarkdownV2).await {
+            Err(e) if format::is_parse_error(&e) => edit(Markup::Html).await,
+            result => result,
+        };
+        result
+            .map(|_| ())
+            .map_err(|e| anyhow::anyhow!("Telegram edit failed: {}", e))
+    }
 
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url(
-            "\u{1f517} 원문 보기",
-            reqwest::Url::parse(&notice.url)?,
-        )]]);
+    fn channel_text(&self, notice: &Notice, markup: Markup) -> String {
+        let text = match &self.options.channel_template {
+            Some(template) => markup.escape(&format::render_template(template, notice)),
+            None => format::channel_text(notice, markup),
+        };
+        let urgent = if notice.urgent { format::urgent_header(markup) } else { String::new() };
+        format!("{}{}{}", self.options.preview_tag(markup), urgent, text)
+    }
 
-        self.bot
-            .send_message(ChatId(0), &text)
-            .chat_id(target_channel.to_string())
-            .parse_mode(ParseMode::MarkdownV2)
-            .reply_markup(keyboard)
+    async fn send_notice_as(
+        &self,
+        notice: &Notice,
+        url: &reqwest::Url,
+        channel_override: Option<&str>,
+        markup: Markup,
+        skip: usize,
+    ) -> Result<MessageId, (usize, teloxide::RequestError)> {
+        let (target_channel, thread) = self.options.route(&self.channel_id, channel_override, &notice.source_key, &notice.category);
+        let text = self.channel_text(notice, markup);
+
+        // 너무 길면 나눠 보내고 "원문 보기" 버튼은 마지막 조각에만. 앞 `skip`개는 이미 보낸 것.
+        // 반환: 마지막 조각 ID, 실패하면 (지금까지 보낸 조각 수, 오류).
+        let mut parts = split_message_as(&text, MESSAGE_MAX_CHARS, markup);
+        let last = parts.pop().unwrap_or_default();
+        let skip = skip.min(parts.len());
+        let send = |part: String| {
+            let mut req = self
+                .bot
+                .send_message(ChatId(0), part)
+                .chat_id(target_channel.to_string())
+                .parse_mode(markup.parse_mode());
+            if let Some(preview) = self.options.link_preview() {
+                req = req.link_preview_options(preview);
+            }
+            if let Some(thread) = thread {
+                req = req.message_thread_id(thread);
+            }
+            req
+        };
+        let mut parts_sent = skip;
+        for part in parts.into_iter().skip(skip) {
+            self.limiter.acquire().await;
+            send(part).await.map_err(|e| (parts_sent, e))?;
+            parts_sent += 1;
+        }
+        self.limiter.acquire().await;
+        send(last)
+            .reply_markup(self.options.source_keyboard(url, &notice.title))
             .await
-            .map_err(|e| anyhow::anyhow!("Telegram send failed: {}", e))?;
+            .map(|message| message.id)
+            .map_err(|e| (parts_sent, e))
+    }
 
-        Ok(())
+    /// 이미지와 함께 게시한다. 1장이면 사진 + "원문 보기" 버튼, 여러 장이면 앨범
+    /// (앨범에는 버튼을 달 수 없어 캡션에 링크를 넣는다).
+    /// 사진 메시지는 텍스트 수정이 안 되므로 메시지 ID를 남기지 않는다.
+    async fn send_album(&self, notice: &Notice, channel_override: Option<&str>, images: &[String]) -> anyhow::Result<()> {
+        let url = reqwest::Url::parse(&notice.url)?;
+        let caption = format!(
+            "{}\n\n<a href=\"{}\">원문 보기</a>",
+            self.channel_text(notice, Markup::Html),
+            html_escape(url.as_str())
+        );
+        let media = album_media(images, &caption).ok_or_else(|| anyhow::anyhow!("No usable images"))?;
+        let (target_channel, thread) = self.options.route(&self.channel_id, channel_override, &notice.source_key, &notice.category);
+        let target_channel = target_channel.to_string();
+
+        self.limiter.acquire().await;
+        let result = if media.len() == 1 {
+            let photo = media.into_iter().next().unwrap();
+            let mut req = self
+                .bot
+                .send_photo(target_channel, photo.media)
+                .caption(photo.caption.unwrap_or_default())
+                .parse_mode(ParseMode::Html)
+                .reply_markup(self.options.source_keyboard(&url, &notice.title));
+            if let Some(thread) = thread {
+                req = req.message_thread_id(thread);
+            }
+            req.await.map(|_| ())
+        } else {
+            let mut req = self
+                .bot
+                .send_media_group(target_channel, media.into_iter().map(InputMedia::Photo));
+            if let Some(thread) = thread {
+                req = req.message_thread_id(thread);
+            }
+            req.await.map(|_| ())
+        };
+        result.map_err(|e| anyhow::anyhow!("Telegram album send failed: {}", e))
     }
 
     /// Send a batch of notices, respecting rate limits and max count.
     /// `channel_map`: source_key → channel override.
-    /// Returns Vec of successfully sent notice DB IDs.
+    /// `images`: 공지 DB ID → 상세 페이지 이미지 (`bot.send_images`일 때만 사용).
+    /// Returns (notice DB ID, 채널 메시지 ID) of successfully sent notices.
+    /// 묶음 발송은 한 메시지에 여러 공지가 들어가므로 메시지 ID가 `None`.
     pub async fn send_batch(
         &self,
         notices: &[Notice],
         max: usize,
         channel_map: &HashMap<String, String>,
-    ) -> anyhow::Result<Vec<i64>> {
+        images: &HashMap<i64, Vec<String>>,
+    ) -> anyhow::Result<Vec<(i64, Option<MessageId>)>> {
+        if self.options.group_channel_posts {
+            return self.send_grouped(notices, max, channel_map).await;
+        }
+
         let mut sent_ids = Vec::new();
-        for notice in notices.iter().take(max) {
+        for notice in pick_for_post(notices, max, &self.options.source_caps, self.options.post_order) {
             let ch = channel_map.get(&notice.source_key).map(|s| s.as_str());
+            // 이미지 발송이 실패하면 평소처럼 텍스트 + 링크로
+            if let Some(list) = images.get(&notice.id).filter(|_| self.options.send_images) {
+                match self.send_album(notice, ch, list).await {
+                    Ok(()) => {
+                        sent_ids.push((notice.id, None));
+                        tracing::info!(
+                            notice_id = %notice.notice_id,
+                            images = list.len(),
+                            "Sent notification with images"
+                        );
+                        if !notice.urgent {
+                            sleep(Duration::from_millis(self.delay_ms)).await;
+                        }
+                        continue;
+                    }
+                    Err(e) => tracing::warn!(
+                        notice_id = %notice.notice_id,
+                        error = %e,
+                        "Image send failed; falling back to text"
+                    ),
+                }
+            }
             match self.send_notice(notice, ch).await {
-                Ok(()) => {
-                    sent_ids.push(notice.id);
+                Ok(message_id) => {
+                    sent_ids.push((notice.id, Some(message_id)));
                     tracing::info!(
                         notice_id = %notice.notice_id,
                         title = %notice.title,
@@ -105,7 +409,100 @@ impl Notifier {
                     // Don't break on individual failures; try the rest
                 }
             }
-            sleep(Duration::from_millis(self.delay_ms)).await;
+            // 긴급 공지는 바로 다음 것으로 (전역 속도 제한은 그대로 적용)
+            if !notice.urgent {
+                sleep(Duration::from_millis(self.delay_ms)).await;
+            }
+        }
+        Ok(sent_ids)
+    }
+
+    /// 묶음 발송: 대상 채널별로 공지를 모아 번호 매긴 목록 메시지로 보낸다.
+    /// 4096자를 넘으면 여러 메시지로 나눈다. 반환: 발송 성공한 공지 DB ID.
+    async fn send_grouped(
+        &self,
+        notices: &[Notice],
+        max: usize,
+        channel_map: &HashMap<String, String>,
+    ) -> anyhow::Result<Vec<(i64, Option<MessageId>)>> {
+        // 긴급 공지는 묶지 않고 먼저 한 건씩, 나머지는 채널별 그룹 (처음 등장한 순서 유지)
+        let mut sent_ids = Vec::new();
+        let mut groups: Vec<(&str, Option<ThreadId>, Vec<&Notice>)> = Vec::new();
+        for notice in pick_for_post(notices, max, &self.options.source_caps, self.options.post_order) {
+            if notice.urgent {
+                let ch = channel_map.get(&notice.source_key).map(|s| s.as_str());
+                match self.send_notice(notice, ch).await {
+                    Ok(message_id) => sent_ids.push((notice.id, Some(message_id))),
+                    Err(e) => tracing::error!(
+                        notice_id = %notice.notice_id,
+                        error = %e,
+                        "Failed to send urgent notification"
+                    ),
+                }
+                continue;
+            }
+            let (ch, thread) = self.options.route(
+                &self.channel_id,
+                channel_map.get(&notice.source_key).map(|s| s.as_str()),
+                &notice.source_key,
+                &notice.category,
+            );
+            // 묶음은 카테고리가 섞이므로 소스 토픽만 따른다
+            let thread = thread.filter(|_| self.options.source_topics.contains_key(&notice.source_key));
+            match groups.iter_mut().find(|(c, t, _)| *c == ch && *t == thread) {
+                Some((_, _, list)) => list.push(notice),
+                None => groups.push((ch, thread, vec![notice])),
+            }
+        }
+
+        for (channel, thread, group) in groups {
+            let lines: Vec<String> = group
+                .iter()
+                .enumerate()
+                .map(|(i, n)| digest_line(i + 1, n))
+                .collect();
+            let header = format!(
+                "{}\u{1f4e2} <b>새 공지 {}건</b>\n\n",
+                self.options.preview_tag(Markup::Html),
+                group.len()
+            );
+
+            let mut next = 0;
+            for (text, count) in split_digest(&header, &lines, MESSAGE_MAX_CHARS) {
+                let chunk = &group[next..next + count];
+                next += count;
+
+                // 한 줄이 한도를 넘는 경우만 더 나뉜다
+                let mut result = Ok(());
+                for part in split_message(&text, MESSAGE_MAX_CHARS) {
+                    let mut req = self
+                        .bot
+                        .send_message(ChatId(0), part)
+                        .chat_id(channel.to_string())
+                        .parse_mode(ParseMode::Html);
+                    if let Some(preview) = self.options.link_preview() {
+                        req = req.link_preview_options(preview);
+                    }
+                    if let Some(thread) = thread {
+                        req = req.message_thread_id(thread);
+                    }
+                    self.limiter.acquire().await;
+                    if let Err(e) = req.await {
+                        result = Err(e);
+                        break;
+                    }
+                }
+                match result {
+                    Ok(()) => {
+                        sent_ids.extend(chunk.iter().map(|n| (n.id, None)));
+                        tracing::info!(channel = %channel, count, "Sent grouped notification");
+                    }
+                    Err(e) => {
+                        tracing::error!(channel = %channel, count, error = %e, "Failed to send grouped notification");
+                    }
+                }
+                sleep(Duration::from_millis(self.delay_ms)).await;
+            }
         }
         Ok(sent_ids)
     }
@@ -120,6 +517,7 @@ impl Notifier {
             }
         };
 
+        self.limiter.acquire().await;
         self.bot
             .send_message(ChatId(0), message)
             .chat_id(channel)
@@ -135,19 +533,158 @@ impl Notifier {
     }
 }
 
-/// Escape special characters for Telegram MarkdownV2 format.
-fn escape_markdown(text: &str) -> String {
-    let special_chars = [
-        '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!',
-    ];
-    let mut escaped = String::with_capacity(text.len() * 2);
-    for ch in text.chars() {
-        if special_chars.contains(&ch) {
-            escaped.push('\\');
+/// 사진 캡션 최대 길이 (텔레그램 제한). HTML 태그까지 세므로 실제보다 보수적이다.
+const CAPTION_MAX_CHARS: usize = 1024;
+
+/// 이미지 주소로 앨범 항목을 만든다. 캡션(HTML)은 첫 장에만 붙는다.
+/// 쓸 수 있는 주소가 없거나 캡션이 너무 길면 `None` (텍스트로 보낸다).
+pub fn album_media(images: &[String], caption: &str) -> Option<Vec<InputMediaPhoto>> {
+    if caption.chars().count() > CAPTION_MAX_CHARS {
+        return None;
+    }
+    let media: Vec<InputMediaPhoto> = images
+        .iter()
+        .filter_map(|src| reqwest::Url::parse(src).ok())
+        .take(crate::parser::DETAIL_MAX_IMAGES)
+        .enumerate()
+        .map(|(i, url)| {
+            let photo = InputMediaPhoto::new(InputFile::url(url));
+            if i == 0 {
+                photo.caption(caption).parse_mode(ParseMode::Html)
+            } else {
+                photo
+            }
+        })
+        .collect();
+    (!media.is_empty()).then_some(media)
+}
+
+/// 텔레그램 공유 링크: 누르면 대화 상대를 골라 공지 링크와 제목을 보낸다.
+/// 원문 URL의 `?`/`&`가 공유 링크 쿼리를 깨지 않도록 인코딩해 넣는다.
+fn share_url(url: &reqwest::Url, title: &str) -> reqwest::Url {
+    let mut share = reqwest::Url::parse("https://t.me/share/url").expect("valid share url");
+    share.query_pairs_mut().append_pair("url", url.as_str()).append_pair("text", title);
+    share
+}
+
+/// 묶음 메시지의 공지 한 줄 (HTML).
+fn digest_line(n: usize, notice: &Notice) -> String {
+    let category = Category::from_str_tag(&notice.category);
+    format!(
+        "{}. {} {}<a href=\"{}\">{}</a> — {}\n",
+        n,
+        category.emoji(),
+        if notice.edited { "\u{270f}\u{fe0f} 수정됨 " } else { "" },
+        html_escape(&notice.url),
+        html_escape(&notice.title),
+        html_escape(&notice.source_display_name),
+    )
+}
+
+/// 머리말 + 줄 목록을 `limit`자 이하 메시지들로 나눈다. 각 메시지는 머리말로 시작.
+/// 반환: (메시지, 담긴 줄 수). 한 줄이 혼자서 한도를 넘으면 그 줄만 단독 메시지.
+fn split_digest(header: &str, lines: &[String], limit: usize) -> Vec<(String, usize)> {
+    let header_len = header.chars().count();
+    let mut messages = Vec::new();
+    let mut current = header.to_string();
+    let mut current_len = header_len;
+    let mut count = 0;
+
+    for line in lines {
+        let line_len = line.chars().count();
+        if count > 0 && current_len + line_len > limit {
+            messages.push((current, count));
+            current = header.to_string();
+            current_len = header_len;
+            count = 0;
+        }
+        current.push_str(line);
+        current_len += line_len;
+        count += 1;
+    }
+    if count > 0 {
+        messages.push((current, count));
+    }
+    messages
+}
+
+/// 429(RetryAfter) 응답 시 최대 재시도 횟수.
+const RETRY_AFTER_MAX: u32 = 3;
+
+/// 텍스트 메시지 전송. 429 응답이면 텔레그램이 알려준 시간만큼 기다렸다 재시도.
+pub async fn send_text_with_backoff(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+) -> Result<(), teloxide::RequestError> {
+    let mut attempt = 0;
+    loop {
+        match bot.send_message(chat_id, text).await {
+            Ok(_) => return Ok(()),
+            Err(teloxide::RequestError::RetryAfter(secs)) if attempt < RETRY_AFTER_MAX => {
+                attempt += 1;
+                tracing::warn!(chat_id = %chat_id, wait_secs = secs.seconds(), "Rate limited, backing off");
+                sleep(secs.duration()).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 전체 `max`건 안에서 소스별로 한 건씩 돌아가며 고른다 (소스 순서는 처음 등장한 순,
+/// 소스 안에서는 원래 순서). 한 게시판이 수십 건을 쏟아내도 다른 소스 몫이 남고,
+/// `caps`에 한도가 있는 소스는 그만큼만 고른다.
+pub fn fair_share<'a>(
+    notices: impl IntoIterator<Item = &'a Notice>,
+    max: usize,
+    caps: &HashMap<String, usize>,
+) -> Vec<&'a Notice> {
+    let mut queues: Vec<(&str, std::collections::VecDeque<&Notice>, usize)> = Vec::new();
+    for notice in notices {
+        match queues.iter_mut().find(|(key, _, _)| *key == notice.source_key) {
+            Some((_, queue, _)) => queue.push_back(notice),
+            None => queues.push((notice.source_key.as_str(), [notice].into(), 0)),
+        }
+    }
+
+    let mut picked = Vec::new();
+    loop {
+        let mut progressed = false;
+        for (key, queue, taken) in queues.iter_mut() {
+            if picked.len() >= max {
+                return picked;
+            }
+            if caps.get(*key).is_some_and(|cap| *taken >= *cap) {
+                continue;
+            }
+            if let Some(notice) = queue.pop_front() {
+                picked.push(notice);
+                *taken += 1;
+                progressed = true;
+            }
+        }
+        if !progressed {
+            return picked;
         }
-        escaped.push(ch);
     }
-    escaped
+}
+
+/// 이번 사이클에 올릴 공지: 긴급 공지를 소스 한도와 관계없이 맨 앞에 두고, 남은 자리는
+/// `fair_share`로 고른다. 순서를 지정한 경우 (`chronological`, `pinned_first`)
+/// 고른 공지를 입력(조회) 순서대로 되돌린다.
+pub fn pick_for_post<'a>(
+    notices: &'a [Notice],
+    max: usize,
+    caps: &HashMap<String, usize>,
+    order: PostOrder,
+) -> Vec<&'a Notice> {
+    let mut picked: Vec<&Notice> = notices.iter().filter(|n| n.urgent).take(max).collect();
+    let mut rest = fair_share(notices.iter().filter(|n| !n.urgent), max - picked.len(), caps);
+    if order != PostOrder::Latest {
+        rest.sort_by_key(|p| notices.iter().position(|n| std::ptr::eq(n, *p)));
+    }
+    picked.extend(rest);
+    picked
 }
 
 #[cfg(test)]
@@ -155,13 +692,252 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_escape_markdown() {
-        assert_eq!(escape_markdown("hello"), "hello");
-        assert_eq!(escape_markdown("test_var"), "test\\_var");
-        assert_eq!(escape_markdown("[학사]"), "\\[학사\\]");
+    fn test_send_options_from_config() {
+        let bot: BotConfig = toml::from_str(
+            r#"
+telegram_channel = "@cbnu_notice"
+disable_link_preview = true
+[category_topics]
+scholarship = 12
+academic = 7
+"#,
+        )
+        .unwrap();
+        let opts = SendOptions::from_config(&bot);
+        assert!(opts.disable_link_preview);
+        assert_eq!(opts.thread_for("scholarship"), Some(ThreadId(MessageId(12))));
+        assert_eq!(opts.thread_for("general"), None);
+
+        // 기본값은 기존 동작 그대로
+        let bot: BotConfig = toml::from_str(r#"telegram_channel = "@cbnu_notice""#).unwrap();
+        assert_eq!(SendOptions::from_config(&bot), SendOptions::default());
+    }
+
+    #[test]
+    fn test_share_button() {
+        let url = reqwest::Url::parse("https://biz.chungbuk.ac.kr/view.php?pg_idx=7&no=42#top").unwrap();
+        let share = share_url(&url, "장학금 신청 & 안내");
+        assert_eq!(share.host_str(), Some("t.me"));
+        assert_eq!(share.path(), "/share/url");
+        let query = share.query().unwrap();
+        assert!(query.contains("view.php%3Fpg_idx%3D7%26no%3D42%23top"), "{query}");
+        assert!(!query.contains("&no="));
+        let pairs: Vec<(String, String)> = share.query_pairs().into_owned().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("url".into(), url.to_string()),
+                ("text".into(), "장학금 신청 & 안내".into()),
+            ]
+        );
+
+        // 기본은 "원문 보기" 하나, 켜면 같은 줄에 "공유"
+        let buttons = |opts: &SendOptions| opts.source_keyboard(&url, "제목").inline_keyboard;
+        assert_eq!(buttons(&SendOptions::default()), vec![vec![InlineKeyboardButton::url("\u{1f517} 원문 보기", url.clone())]]);
+        let opts = SendOptions { show_share_button: true, ..Default::default() };
+        let rows = buttons(&opts);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 2);
+        assert_eq!(rows[0][1].text, "\u{1f4e4} 공유");
+    }
+
+    #[test]
+    fn test_preview_channel_overrides_routing() {
+        let mut opts = SendOptions::default();
+        opts.category_topics.insert("scholarship".into(), 12);
+        assert_eq!(
+            opts.route("@main", None, "biz", "scholarship"),
+            ("@main", Some(ThreadId(MessageId(12))))
+        );
+        assert_eq!(opts.route("@main", Some("@biz"), "biz", "scholarship"), ("@biz", None));
+        assert_eq!(opts.preview_tag(Markup::MarkdownV2), "");
+
+        // 미리보기 채널은 소스별 채널과 카테고리 토픽보다 우선
+        opts.preview_channel = Some("@preview".into());
+        assert_eq!(opts.route("@main", None, "biz", "scholarship"), ("@preview", None));
+        assert_eq!(opts.route("@main", Some("@biz"), "biz", "general"), ("@preview", None));
+        assert_eq!(opts.preview_tag(Markup::MarkdownV2), "\\[PREVIEW\\] ");
+        assert_eq!(opts.preview_tag(Markup::Html), "[PREVIEW] ");
+    }
+
+    #[test]
+    fn test_source_topic_from_config() {
+        let cfg: crate::config::Config = toml::from_str(
+            r#"
+[bot]
+telegram_channel = "@cbnu_notice"
+[bot.category_topics]
+scholarship = 12
+[database]
+path = "notices.db"
+[[source]]
+key = "biz"
+display_name = "경영학부"
+parser = "php_master"
+url = "https://biz.chungbuk.ac.kr"
+topic_id = 5
+[[source]]
+key = "physics"
+display_name = "물리학과"
+parser = "php_master"
+url = "https://physics.chungbuk.ac.kr"
+"#,
+        )
+        .unwrap();
+        let opts = SendOptions::from_config(&cfg.bot).with_source_topics(cfg.source_topics());
+
+        // 소스 토픽이 카테고리 토픽보다 우선, 소스별 채널에도 적용
+        let topic = Some(ThreadId(MessageId(5)));
+        assert_eq!(opts.route("@main", None, "biz", "scholarship"), ("@main", topic));
+        assert_eq!(opts.route("@main", Some("@biz"), "biz", "general"), ("@biz", topic));
+        // 미지정 소스는 기존대로 카테고리 토픽, 없으면 일반 스레드
         assert_eq!(
-            escape_markdown("2026.02.01 | author"),
-            "2026\\.02\\.01 \\| author"
+            opts.route("@main", None, "physics", "scholarship"),
+            ("@main", Some(ThreadId(MessageId(12))))
         );
+        assert_eq!(opts.route("@main", None, "physics", "general"), ("@main", None));
+    }
+
+    #[test]
+    fn test_fair_share_interleaves_sources() {
+        let notice = |id: i64, source: &str| Notice {
+            id,
+            source_key: source.into(),
+            notice_id: id.to_string(),
+            title: format!("공지 {}", id),
+            url: format!("https://example.com/{}", id),
+            author: None,
+            category: "general".into(),
+            published: None,
+            source_display_name: source.into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        };
+        // biz가 먼저 6건을 쏟아냈고 math는 2건
+        let mut notices: Vec<Notice> = (1..=6).map(|i| notice(i, "biz")).collect();
+        notices.extend([notice(7, "math"), notice(8, "math")]);
+        let ids = |picked: Vec<&Notice>| picked.iter().map(|n| n.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(fair_share(&notices, 4, &HashMap::new())), vec![1, 7, 2, 8]);
+        assert_eq!(ids(fair_share(&notices, 6, &HashMap::new())), vec![1, 7, 2, 8, 3, 4]);
+
+        // 소스 한도: biz는 2건까지만, 남는 자리는 비워 둔다
+        let caps = HashMap::from([("biz".to_string(), 2)]);
+        assert_eq!(ids(fair_share(&notices, 10, &caps)), vec![1, 7, 2, 8]);
+        assert!(fair_share(&notices, 0, &caps).is_empty());
+
+        // 순서를 지정하면 같은 공지를 고르되 조회 순서대로 올린다
+        assert_eq!(ids(pick_for_post(&notices, 4, &HashMap::new(), PostOrder::Latest)), vec![1, 7, 2, 8]);
+        assert_eq!(ids(pick_for_post(&notices, 4, &HashMap::new(), PostOrder::Chronological)), vec![1, 2, 7, 8]);
+
+        // 긴급 공지는 순서·소스 한도와 관계없이 맨 앞
+        notices[5].urgent = true;
+        notices[7].urgent = true;
+        assert_eq!(ids(pick_for_post(&notices, 4, &HashMap::new(), PostOrder::Latest)), vec![6, 8, 1, 7]);
+        assert_eq!(ids(pick_for_post(&notices, 5, &HashMap::new(), PostOrder::Chronological)), vec![6, 8, 1, 2, 7]);
+        assert_eq!(ids(pick_for_post(&notices, 1, &HashMap::new(), PostOrder::Latest)), vec![6]);
+    }
+
+    #[test]
+    fn test_album_media_payload() {
+        let mut images = vec!["/upload/relative.png".to_string()];
+        images.extend((1..=12).map(|i| format!("https://cbnu.ac.kr/img/{}.jpg", i)));
+        let media = album_media(&images, "<b>포스터</b>").unwrap();
+
+        // 상대 경로는 건너뛰고 최대 10장
+        assert_eq!(media.len(), 10);
+        assert_eq!(media[0].caption.as_deref(), Some("<b>포스터</b>"));
+        assert_eq!(media[0].parse_mode, Some(ParseMode::Html));
+        assert!(media[1..].iter().all(|m| m.caption.is_none()));
+
+        assert!(album_media(&[], "caption").is_none());
+        assert!(album_media(&images, &"가".repeat(CAPTION_MAX_CHARS + 1)).is_none());
+    }
+
+    #[test]
+    fn test_split_digest_boundary() {
+        let header = "H\n"; // 2자
+        let lines: Vec<String> = vec!["a".repeat(9) + "\n"; 3]; // 10자씩
+
+        // 정확히 한도에 맞으면 한 메시지
+        let msgs = split_digest(header, &lines, 32);
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].1, 3);
+        assert_eq!(msgs[0].0.chars().count(), 32);
+
+        // 1자 모자라면 마지막 줄이 다음 메시지로
+        let msgs = split_digest(header, &lines, 31);
+        assert_eq!(msgs.iter().map(|m| m.1).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(msgs.iter().all(|(m, _)| m.starts_with(header) && m.chars().count() <= 31));
+
+        // 한글도 바이트가 아닌 문자 수로 계산
+        let lines = vec!["공지".repeat(5); 2];
+        assert_eq!(split_digest("", &lines, 20).len(), 1);
+        assert_eq!(split_digest("", &lines, 19).len(), 2);
+
+        assert!(split_digest(header, &[], 100).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_notice_resumes_unsent_parts() {
+        use std::sync::Arc;
+
+        // 가짜 텔레그램 API: 보낸 텍스트를 기록하고 두 번째 요청만 한 번 실패시킨다
+        let texts: Arc<Mutex<Vec<String>>> = Arc::default();
+        let app = axum::Router::new()
+            .fallback(|axum::extract::State(texts): axum::extract::State<Arc<Mutex<Vec<String>>>>,
+                       axum::Json(body): axum::Json<serde_json::Value>| async move {
+                let mut texts = texts.lock().unwrap();
+                texts.push(body["text"].as_str().unwrap_or_default().to_string());
+                if texts.len() == 2 {
+                    return axum::Json(serde_json::json!({
+                        "ok": false, "error_code": 500, "description": "Internal Server Error"
+                    }));
+                }
+                axum::Json(serde_json::json!({
+                    "ok": true,
+                    "result": {
+                        "message_id": texts.len(), "date": 0,
+                        "chat": { "id": -100, "type": "channel", "title": "test" }
+                    }
+                }))
+            })
+            .with_state(texts.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let bot = Bot::new("0:test").set_api_url(reqwest::Url::parse(&format!("http://{}/", addr)).unwrap());
+        let notifier = Notifier::new(bot, "@main".into(), None, 0, SendOptions::default());
+        let notice = Notice {
+            id: 1,
+            source_key: "biz".into(),
+            notice_id: "1".into(),
+            title: "가 ".repeat(4500),
+            url: "https://example.com/1".into(),
+            author: None,
+            category: "general".into(),
+            published: None,
+            source_display_name: "경영학부".into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        };
+
+        // 첫 조각만 나가고 실패 → 진행 상황이 남는다
+        assert!(notifier.send_notice(&notice, None).await.is_err());
+        assert_eq!(notifier.send_progress(1), Some((Markup::MarkdownV2, 1)));
+
+        // 재시도는 남은 조각만 보내고 진행 상황을 지운다
+        notifier.send_notice(&notice, None).await.unwrap();
+        assert_eq!(notifier.send_progress(1), None);
+        let texts = texts.lock().unwrap();
+        let parts = split_message_as(&notifier.channel_text(&notice, Markup::MarkdownV2), MESSAGE_MAX_CHARS, Markup::MarkdownV2);
+        assert!(parts.len() >= 3);
+        // 실패한 두 번째 조각만 다시, 첫 조각은 한 번만
+        let mut expected = parts.clone();
+        expected.insert(1, parts[1].clone());
+        assert_eq!(*texts, expected);
     }
 }
@@ -3,8 +3,9 @@ use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use super::{NoticeParser, RawNotice};
+use super::{FetchedList, NoticeDetail, NoticeParser, RawNotice};
 use crate::config::SourceConfig;
+use crate::throttle::HostThrottle;
 
 /// Parser for CIBoard (CodeIgniter Board) CMS.
 ///
@@ -68,6 +69,7 @@ impl CiBoardParser {
         let a_sel = Selector::parse("a[href]").unwrap();
         let pinned_sel = Selector::parse("span.label").unwrap();
 
+        let year = super::current_year();
         let mut notices = Vec::new();
 
         for sel_str in &table_selectors {
@@ -101,9 +103,9 @@ impl CiBoardParser {
                 let title = link
                     .value()
                     .attr("title")
-                    .map(|t| t.trim().to_string())
+                    .map(super::clean_title)
                     .filter(|t| !t.is_empty())
-                    .unwrap_or_else(|| link.text().collect::<String>().trim().to_string());
+                    .unwrap_or_else(|| super::clean_title(&link.text().collect::<String>()));
 
                 if title.is_empty() {
                     continue;
@@ -127,9 +129,16 @@ impl CiBoardParser {
                     title,
                     url,
                     author: None,
-                    date,
+                    date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                    original_date: date,
                     category: None,
                     is_pinned,
+                    // Views are in the last cell (index 4)
+                    views: if cells.len() >= 5 {
+                        cells.last().and_then(|td| super::parse_views(&td.text().collect::<String>()))
+                    } else {
+                        None
+                    },
                 });
             }
 
@@ -144,17 +153,11 @@ impl CiBoardParser {
 
 #[async_trait]
 impl NoticeParser for CiBoardParser {
-    async fn fetch_notices(&self, client: &Client) -> anyhow::Result<Vec<RawNotice>> {
+    async fn fetch_notices(&self, client: &Client, _throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
         let url = self.board_url();
         tracing::info!(source = %self.source_key, url = %url, "Fetching CIBoard notices");
 
-        let resp = client.get(&url).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            anyhow::bail!("HTTP {} from {}", status, url);
-        }
-
-        let html = resp.text().await?;
+        let html = super::fetch_text(client, &url).await?;
         let notices = self.parse_html(&html)?;
 
         tracing::info!(
@@ -163,7 +166,7 @@ impl NoticeParser for CiBoardParser {
             "Parsed CIBoard notices"
         );
 
-        Ok(notices)
+        Ok(FetchedList { notices, body: html })
     }
 
     fn source_key(&self) -> &str {
@@ -173,6 +176,14 @@ impl NoticeParser for CiBoardParser {
     fn display_name(&self) -> &str {
         &self.display_name
     }
+
+    fn parse_detail(&self, html: &str) -> Option<NoticeDetail> {
+        super::extract_detail(
+            html,
+            &["div.contents-view", "#post-content"],
+            &["ul.info li", "div.info"],
+        )
+    }
 }
 
 #[cfg(test)]
@@ -190,8 +201,7 @@ mod tests {
             parser: "ciboard".into(),
             url: "https://sociology.chungbuk.ac.kr".into(),
             params,
-            enabled: true,
-            channel: None,
+            ..Default::default()
         }
     }
 
@@ -219,4 +229,17 @@ mod tests {
         let unique: std::collections::HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len(), "All notice_ids should be unique");
     }
+
+    #[test]
+    fn test_parse_ciboard_detail_fixture() {
+        let html = std::fs::read_to_string("tests/fixtures/ciboard_detail_sample.html")
+            .expect("Missing fixture: tests/fixtures/ciboard_detail_sample.html");
+        let parser = CiBoardParser::from_config(&test_config());
+        let detail = parser.parse_detail(&html).expect("Should parse detail page");
+
+        assert_eq!(detail.published.as_deref(), Some("2026-01-27 10:11"));
+        let body = detail.body.expect("Should extract body text");
+        assert!(body.contains("학과 조교를 다음과 같이"));
+        assert!(!body.contains('\n'), "Whitespace should be collapsed");
+    }
 }
@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+
+use super::{FetchedList, NoticeParser, RawNotice};
+use crate::config::SourceConfig;
+use crate::throttle::HostThrottle;
+
+/// Generic parser driven entirely by CSS selectors in `[source.params]`.
+///
+/// Lets a new board be added by editing `config.toml` only:
+///
+/// | param             | default    | meaning                                        |
+/// |-------------------|------------|------------------------------------------------|
+/// | `row_selector`    | (required) | one element per notice (`table tbody tr`)      |
+/// | `link_selector`   | `a[href]`  | link to the view page, inside the row          |
+/// | `title_selector`  | -          | title element; defaults to the link text       |
+/// | `date_selector`   | -          | date element (optional)                        |
+/// | `author_selector` | -          | author element (optional)                      |
+/// | `pinned_selector` | -          | row is pinned if this matches (optional)       |
+/// | `id_regex`        | `(\d+)$`   | first capture group on the href = notice id    |
+///
+/// Relative hrefs are resolved against the source `url`.
+pub struct CssParser {
+    source_key: String,
+    display_name: String,
+    url: String,
+    row_selector: String,
+    link_selector: String,
+    title_selector: Option<String>,
+    date_selector: Option<String>,
+    author_selector: Option<String>,
+    pinned_selector: Option<String>,
+    id_regex: String,
+}
+
+/// `probe-selector` 결과: 단계별로 선택자에 맞은 행 수와 파싱된 공지.
+#[derive(Debug)]
+pub struct SelectorProbe {
+    /// `row_selector`에 맞은 행.
+    pub rows: usize,
+    /// 그중 `link_selector`에 맞는 링크가 있는 행.
+    pub with_link: usize,
+    /// 그중 `id_regex`로 공지 ID를 뽑은 행.
+    pub with_id: usize,
+    /// 제목까지 있어 공지가 된 행 (실제 크롤 결과와 같다).
+    pub notices: Vec<RawNotice>,
+}
+
+impl CssParser {
+    pub fn from_config(config: &SourceConfig) -> Self {
+        Self::from_params(&config.key, &config.display_name, &config.url, &config.params)
+    }
+
+    /// 설정 파일 없이 선택자만으로 생성 (`probe-selector`).
+    pub fn from_params(
+        key: &str,
+        display_name: &str,
+        url: &str,
+        params: &std::collections::HashMap<String, String>,
+    ) -> Self {
+        let param = |name: &str| params.get(name).filter(|v| !v.trim().is_empty()).cloned();
+
+        Self {
+            source_key: key.to_string(),
+            display_name: display_name.to_string(),
+            url: url.to_string(),
+            row_selector: param("row_selector").unwrap_or_default(),
+            link_selector: param("link_selector").unwrap_or_else(|| "a[href]".to_string()),
+            title_selector: param("title_selector"),
+            date_selector: param("date_selector"),
+            author_selector: param("author_selector"),
+            pinned_selector: param("pinned_selector"),
+            id_regex: param("id_regex").unwrap_or_else(|| r"(\d+)$".to_string()),
+        }
+    }
+
+    fn parse_html(&self, html: &str) -> anyhow::Result<Vec<RawNotice>> {
+        let document = Html::parse_document(html);
+        let row_sel = parse_selector("row_selector", &self.row_selector)?;
+        let link_sel = parse_selector("link_selector", &self.link_selector)?;
+        let title_sel = optional_selector("title_selector", &self.title_selector)?;
+        let date_sel = optional_selector("date_selector", &self.date_selector)?;
+        let author_sel = optional_selector("author_selector", &self.author_selector)?;
+        let pinned_sel = optional_selector("pinned_selector", &self.pinned_selector)?;
+        let id_re = Regex::new(&self.id_regex)
+            .map_err(|e| anyhow::anyhow!("Invalid id_regex {:?}: {}", self.id_regex, e))?;
+        let base = reqwest::Url::parse(&self.url)?;
+
+        let year = super::current_year();
+        let mut notices = Vec::new();
+
+        for row in document.select(&row_sel) {
+            let link = match row.select(&link_sel).next() {
+                Some(a) => a,
+                None => continue,
+            };
+            let href = link.value().attr("href").unwrap_or("");
+            let notice_id = match id_re.captures(href).and_then(|c| c.get(1)) {
+                Some(m) => m.as_str().to_string(),
+                None => continue,
+            };
+            let url = match base.join(href) {
+                Ok(u) => u.to_string(),
+                Err(_) => continue,
+            };
+
+            let title_el = title_sel
+                .as_ref()
+                .and_then(|sel| row.select(sel).next())
+                .unwrap_or(link);
+            let title = super::clean_title(&title_el.text().collect::<String>());
+            if title.is_empty() {
+                continue;
+            }
+
+            let author = select_text(row, author_sel.as_ref());
+            let date = select_text(row, date_sel.as_ref());
+            let is_pinned = pinned_sel
+                .as_ref()
+                .map(|sel| row.select(sel).next().is_some())
+                .unwrap_or(false);
+
+            notices.push(RawNotice {
+                notice_id,
+                title,
+                url,
+                author,
+                date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                original_date: date,
+                category: None,
+                is_pinned,
+                views: None,
+            });
+        }
+
+        Ok(notices)
+    }
+}
+
+impl CssParser {
+    /// 선택자를 단계별로 적용해 어디서 행이 빠지는지 센다. 공지는 `parse_html` 결과 그대로.
+    pub fn probe_html(&self, html: &str) -> anyhow::Result<SelectorProbe> {
+        let notices = self.parse_html(html)?;
+        let document = Html::parse_document(html);
+        let row_sel = parse_selector("row_selector", &self.row_selector)?;
+        let link_sel = parse_selector("link_selector", &self.link_selector)?;
+        let id_re = Regex::new(&self.id_regex)
+            .map_err(|e| anyhow::anyhow!("Invalid id_regex {:?}: {}", self.id_regex, e))?;
+
+        let (mut rows, mut with_link, mut with_id) = (0, 0, 0);
+        for row in document.select(&row_sel) {
+            rows += 1;
+            let Some(link) = row.select(&link_sel).next() else {
+                continue;
+            };
+            with_link += 1;
+            if id_re.is_match(link.value().attr("href").unwrap_or("")) {
+                with_id += 1;
+            }
+        }
+        Ok(SelectorProbe { rows, with_link, with_id, notices })
+    }
+
+    /// 페이지를 받아 `probe_html`.
+    pub async fn probe(&self, client: &Client) -> anyhow::Result<SelectorProbe> {
+        let html = super::fetch_text(client, &self.url).await?;
+        self.probe_html(&html)
+    }
+}
+
+fn parse_selector(name: &str, value: &str) -> anyhow::Result<Selector> {
+    Selector::parse(value).map_err(|e| anyhow::anyhow!("Invalid {} {:?}: {}", name, value, e))
+}
+
+fn optional_selector(name: &str, value: &Option<String>) -> anyhow::Result<Option<Selector>> {
+    value.as_deref().map(|v| parse_selector(name, v)).transpose()
+}
+
+/// 행 안에서 선택자에 맞는 첫 요소의 텍스트. 비어 있으면 `None`.
+fn select_text(row: ElementRef, sel: Option<&Selector>) -> Option<String> {
+    sel.and_then(|s| row.select(s).next())
+        .map(|el| super::clean_title(&el.text().collect::<String>()))
+        .filter(|t| !t.is_empty())
+}
+
+#[async_trait]
+impl NoticeParser for CssParser {
+    async fn fetch_notices(&self, client: &Client, _throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
+        tracing::info!(source = %self.source_key, url = %self.url, "Fetching CSS-configured notices");
+
+        let html = super::fetch_text(client, &self.url).await?;
+        let notices = self.parse_html(&html)?;
+
+        tracing::info!(
+            source = %self.source_key,
+            count = notices.len(),
+            "Parsed CSS-configured notices"
+        );
+
+        Ok(FetchedList { notices, body: html })
+    }
+
+    fn source_key(&self) -> &str {
+        &self.source_key
+    }
+
+    fn display_name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SourceConfig;
+    use std::collections::HashMap;
+
+    fn test_config() -> SourceConfig {
+        let params = HashMap::from([
+            ("row_selector".to_string(), "ul.notice-list li.item".to_string()),
+            ("link_selector".to_string(), "a.subject".to_string()),
+            ("title_selector".to_string(), "a.subject strong".to_string()),
+            ("date_selector".to_string(), "span.date".to_string()),
+            ("author_selector".to_string(), "span.writer".to_string()),
+            ("pinned_selector".to_string(), "span.num em".to_string()),
+            ("id_regex".to_string(), r"no=(\d+)".to_string()),
+        ]);
+        SourceConfig {
+            key: "edu".into(),
+            display_name: "사범대학".into(),
+            parser: "css".into(),
+            url: "https://edu.chungbuk.ac.kr/bbs/list.php?board=notice".into(),
+            params,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_css_fixture() {
+        let html = std::fs::read_to_string("tests/fixtures/css_sample.html")
+            .expect("Missing fixture: tests/fixtures/css_sample.html");
+        let parser = CssParser::from_config(&test_config());
+        let notices = parser.parse_html(&html).unwrap();
+
+        // The row whose link has no id is skipped
+        assert_eq!(notices.len(), 3);
+
+        let first = &notices[0];
+        assert_eq!(first.notice_id, "3120");
+        assert_eq!(first.title, "2026학년도 1학기 교육실습 신청 안내");
+        assert_eq!(first.url, "https://edu.chungbuk.ac.kr/bbs/view.php?board=notice&no=3120");
+        assert_eq!(first.author.as_deref(), Some("교무팀"));
+        assert_eq!(first.date.as_deref(), Some("2026-02-05"));
+        assert!(first.is_pinned);
+
+        assert_eq!(notices[1].title, "교원자격검정 관련 서류 제출");
+        assert!(!notices[1].is_pinned);
+        assert!(notices[2].author.is_none());
+    }
+
+    #[test]
+    fn test_probe_counts_each_stage() {
+        let html = std::fs::read_to_string("tests/fixtures/css_sample.html")
+            .expect("Missing fixture: tests/fixtures/css_sample.html");
+        let probe = CssParser::from_config(&test_config()).probe_html(&html).unwrap();
+        assert_eq!((probe.rows, probe.with_link, probe.with_id), (4, 4, 3));
+        assert_eq!(probe.notices.len(), 3);
+
+        // 링크 선택자가 틀리면 행은 맞아도 링크 단계에서 전부 빠진다
+        let mut params = test_config().params;
+        params.insert("link_selector".into(), "a.title".into());
+        let parser = CssParser::from_params("probe", "probe", "https://edu.chungbuk.ac.kr/bbs/list.php", &params);
+        let probe = parser.probe_html(&html).unwrap();
+        assert_eq!((probe.rows, probe.with_link, probe.with_id), (4, 0, 0));
+        assert!(probe.notices.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_selector_is_error() {
+        let mut cfg = test_config();
+        cfg.params.insert("row_selector".into(), "ul >>> li".into());
+        let parser = CssParser::from_config(&cfg);
+        let err = parser.parse_html("<ul></ul>").unwrap_err().to_string();
+        assert!(err.contains("row_selector"));
+    }
+}
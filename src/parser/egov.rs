@@ -3,8 +3,9 @@ use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use super::{NoticeParser, RawNotice};
+use super::{FetchedList, NoticeDetail, NoticeParser, RawNotice};
 use crate::config::SourceConfig;
+use crate::throttle::HostThrottle;
 
 pub struct EgovParser {
     source_key: String,
@@ -58,6 +59,7 @@ impl EgovParser {
         let td_sel = Selector::parse("td").unwrap();
         let a_sel = Selector::parse("a[href]").unwrap();
 
+        let year = super::current_year();
         let mut notices = Vec::new();
 
         for sel_str in &table_selectors {
@@ -88,7 +90,7 @@ impl EgovParser {
                     None => continue,
                 };
 
-                let title = link.text().collect::<String>().trim().to_string();
+                let title = super::clean_title(&link.text().collect::<String>());
                 if title.is_empty() {
                     continue;
                 }
@@ -128,9 +130,12 @@ impl EgovParser {
                     title,
                     url,
                     author,
-                    date,
+                    date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                    original_date: date,
                     category,
                     is_pinned,
+                    // 마지막 칸이 조회수
+                    views: cells.last().and_then(|td| super::parse_views(&td.text().collect::<String>())),
                 });
             }
 
@@ -146,17 +151,11 @@ impl EgovParser {
 
 #[async_trait]
 impl NoticeParser for EgovParser {
-    async fn fetch_notices(&self, client: &Client) -> anyhow::Result<Vec<RawNotice>> {
+    async fn fetch_notices(&self, client: &Client, _throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
         let url = self.build_list_url();
         tracing::info!(source = %self.source_key, url = %url, "Fetching eGov notices");
 
-        let resp = client.get(&url).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            anyhow::bail!("HTTP {} from {}", status, url);
-        }
-
-        let html = resp.text().await?;
+        let html = super::fetch_text(client, &url).await?;
         let notices = self.parse_html(&html)?;
 
         tracing::info!(
@@ -165,7 +164,7 @@ impl NoticeParser for EgovParser {
             "Parsed eGov notices"
         );
 
-        Ok(notices)
+        Ok(FetchedList { notices, body: html })
     }
 
     fn source_key(&self) -> &str {
@@ -175,6 +174,14 @@ impl NoticeParser for EgovParser {
     fn display_name(&self) -> &str {
         &self.display_name
     }
+
+    fn parse_detail(&self, html: &str) -> Option<NoticeDetail> {
+        super::extract_detail(
+            html,
+            &["div.view_cont", "div.bbs_content", "td.bbs_content"],
+            &["ul.view_info li", "div.view_info", "table.bbs_view td"],
+        )
+    }
 }
 
 #[cfg(test)]
@@ -194,8 +201,7 @@ mod tests {
             parser: "egov".into(),
             url: "https://www.chungbuk.ac.kr/www/selectBbsNttList.do".into(),
             params,
-            enabled: true,
-            channel: None,
+            ..Default::default()
         }
     }
 
@@ -217,6 +223,7 @@ mod tests {
         assert_eq!(first.author.as_deref(), Some("학사과"));
         assert_eq!(first.date.as_deref(), Some("2026-02-01"));
         assert_eq!(first.category.as_deref(), Some("학사"));
+        assert_eq!(first.views, Some(1523));
 
         // Check a non-pinned notice
         let third = &notices[2];
@@ -228,4 +235,17 @@ mod tests {
         let unique: std::collections::HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len(), "All notice_ids should be unique");
     }
+
+    #[test]
+    fn test_parse_egov_detail_fixture() {
+        let html = std::fs::read_to_string("tests/fixtures/egov_detail_sample.html")
+            .expect("Missing fixture: tests/fixtures/egov_detail_sample.html");
+        let parser = EgovParser::from_config(&test_config());
+        let detail = parser.parse_detail(&html).expect("Should parse detail page");
+
+        assert_eq!(detail.published.as_deref(), Some("2026-02-01 09:30"));
+        let body = detail.body.expect("Should extract body text");
+        assert!(body.contains("수강신청 일정을 다음과 같이"));
+        assert!(!body.contains('\n'), "Whitespace should be collapsed");
+    }
 }
@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{FetchedList, NoticeParser, RawNotice};
+use crate::config::SourceConfig;
+use crate::error::AppError;
+use crate::throttle::HostThrottle;
+
+/// HTML 대신 JSON을 돌려주는 게시판 (도서관 등). 응답 모양은 `[source.params]`의
+/// 필드 이름으로 맞춘다. 필드 경로는 `data.list`처럼 점으로 구분.
+pub struct JsonApiParser {
+    source_key: String,
+    display_name: String,
+    url: String,
+    /// 공지 배열 경로 (`items_path`, 기본 `items`).
+    items_path: String,
+    /// 기본 `id`.
+    id_field: String,
+    /// 기본 `title`.
+    title_field: String,
+    /// 상세 주소 (기본 `url`). 상대 경로면 `url` 기준으로 붙인다.
+    url_field: String,
+    /// 기본 `writer`.
+    author_field: String,
+    /// 기본 `regDate`.
+    date_field: String,
+    /// 주소 필드가 없을 때 쓰는 `{id}` 템플릿.
+    url_template: Option<String>,
+}
+
+impl JsonApiParser {
+    pub fn from_config(config: &SourceConfig) -> Self {
+        let param = |name: &str, default: &str| {
+            config
+                .params
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        Self {
+            source_key: config.key.clone(),
+            display_name: config.display_name.clone(),
+            url: config.url.clone(),
+            items_path: param("items_path", "items"),
+            id_field: param("id_field", "id"),
+            title_field: param("title_field", "title"),
+            url_field: param("url_field", "url"),
+            author_field: param("author_field", "writer"),
+            date_field: param("date_field", "regDate"),
+            url_template: config.params.get("url_template").cloned(),
+        }
+    }
+
+    /// 공지 주소: 절대 주소는 그대로, 상대 주소는 API 주소 기준, 없으면 `url_template`.
+    fn build_view_url(&self, notice_id: &str, raw_url: Option<&str>) -> Option<String> {
+        match raw_url {
+            Some(u) if u.starts_with("http://") || u.starts_with("https://") => Some(u.to_string()),
+            Some(u) => reqwest::Url::parse(&self.url)
+                .and_then(|base| base.join(u))
+                .map(|joined| joined.to_string())
+                .ok(),
+            None => self
+                .url_template
+                .as_ref()
+                .map(|t| t.replace("{id}", notice_id)),
+        }
+    }
+
+    fn parse_json(&self, body: &str) -> anyhow::Result<Vec<RawNotice>> {
+        let root: Value = serde_json::from_str(body)?;
+
+        let items = lookup_path(&root, &self.items_path)
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("No array at '{}' in JSON response", self.items_path))?;
+
+        let year = super::current_year();
+        let mut notices = Vec::new();
+
+        for item in items {
+            let notice_id = match lookup_path(item, &self.id_field).and_then(value_to_string) {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let title = match lookup_path(item, &self.title_field)
+                .and_then(value_to_string)
+                .map(|t| super::clean_title(&t))
+                .filter(|t| !t.is_empty())
+            {
+                Some(t) => t,
+                None => continue,
+            };
+
+            let raw_url = lookup_path(item, &self.url_field).and_then(value_to_string);
+            let url = match self.build_view_url(&notice_id, raw_url.as_deref()) {
+                Some(u) => u,
+                None => continue,
+            };
+
+            let author = lookup_path(item, &self.author_field).and_then(value_to_string);
+            let date = lookup_path(item, &self.date_field).and_then(value_to_string);
+
+            notices.push(RawNotice {
+                notice_id,
+                title,
+                url,
+                author,
+                date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                original_date: date,
+                category: None,
+                is_pinned: false,
+                views: None,
+            });
+        }
+
+        Ok(notices)
+    }
+}
+
+/// 점 경로(`data.list`, `result.items.0`)로 값 찾기. 숫자 조각은 배열 인덱스, 빈 경로는 루트.
+fn lookup_path<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|seg| !seg.is_empty())
+        .try_fold(root, |cur, seg| match cur {
+            Value::Object(map) => map.get(seg),
+            Value::Array(arr) => seg.parse::<usize>().ok().and_then(|i| arr.get(i)),
+            _ => None,
+        })
+}
+
+/// 문자열/숫자 값을 문자열로. 빈 문자열이나 객체/배열은 `None`.
+fn value_to_string(v: &Value) -> Option<String> {
+    let s = match v {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    if s.is_empty() { None } else { Some(s) }
+}
+
+#[async_trait]
+impl NoticeParser for JsonApiParser {
+    async fn fetch_notices(&self, client: &Client, _throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
+        tracing::info!(source = %self.source_key, url = %self.url, "Fetching JSON API notices");
+
+        let resp = client
+            .get(&self.url)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(AppError::Status { status, url: self.url.clone() }.into());
+        }
+
+        let body = super::read_text(resp).await?;
+        let notices = self.parse_json(&body)?;
+
+        tracing::info!(
+            source = %self.source_key,
+            count = notices.len(),
+            "Parsed JSON API notices"
+        );
+
+        Ok(FetchedList { notices, body })
+    }
+
+    fn source_key(&self) -> &str {
+        &self.source_key
+    }
+
+    fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn snapshot_ext(&self) -> &'static str {
+        "json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SourceConfig;
+
+    fn test_config() -> SourceConfig {
+        SourceConfig {
+            key: "library".into(),
+            display_name: "도서관".into(),
+            parser: "json_api".into(),
+            url: "https://library.chungbuk.ac.kr/api/notices".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_json_api_fixture() {
+        let body = std::fs::read_to_string("tests/fixtures/json_api_sample.json")
+            .expect("Missing fixture: tests/fixtures/json_api_sample.json");
+        let parser = JsonApiParser::from_config(&test_config());
+        let notices = parser.parse_json(&body).unwrap();
+
+        // 제목 없는 항목은 건너뜀
+        assert_eq!(notices.len(), 3);
+
+        let first = &notices[0];
+        assert_eq!(first.notice_id, "1024");
+        assert_eq!(first.title, "2026학년도 1학기 도서관 이용 안내");
+        assert_eq!(first.url, "https://library.chungbuk.ac.kr/notice/1024");
+        assert_eq!(first.author.as_deref(), Some("학술정보운영과"));
+        assert_eq!(first.date.as_deref(), Some("2026-02-06"));
+
+        // 상대 주소는 API 주소 기준으로
+        assert_eq!(notices[1].notice_id, "1023");
+        assert_eq!(notices[1].url, "https://library.chungbuk.ac.kr/notice/view/1023");
+        assert!(notices[1].author.is_none());
+    }
+
+    #[test]
+    fn test_custom_field_mapping() {
+        let mut cfg = test_config();
+        cfg.params.insert("items_path".into(), "data.list".into());
+        cfg.params.insert("id_field".into(), "seq".into());
+        cfg.params.insert("title_field".into(), "subject".into());
+        cfg.params.insert(
+            "url_template".into(),
+            "https://library.chungbuk.ac.kr/notice?seq={id}".into(),
+        );
+        let parser = JsonApiParser::from_config(&cfg);
+
+        let body = r#"{"data": {"list": [{"seq": 7, "subject": "휴관 안내"}]}}"#;
+        let notices = parser.parse_json(body).unwrap();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].notice_id, "7");
+        assert_eq!(notices[0].url, "https://library.chungbuk.ac.kr/notice?seq=7");
+    }
+
+    #[test]
+    fn test_missing_items_path_is_error() {
+        let parser = JsonApiParser::from_config(&test_config());
+        assert!(parser.parse_json(r#"{"result": []}"#).is_err());
+    }
+}
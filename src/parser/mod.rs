@@ -1,12 +1,21 @@
 pub mod ciboard;
+pub mod css;
 pub mod egov;
+pub mod json_api;
 pub mod php_master;
 pub mod xe_board;
 
+use std::sync::LazyLock;
+
 use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
 use reqwest::Client;
+use scraper::{Html, Selector};
 
 use crate::config::SourceConfig;
+use crate::error::AppError;
+use crate::throttle::HostThrottle;
 
 #[derive(Debug, Clone)]
 pub struct RawNotice {
@@ -14,26 +23,540 @@ pub struct RawNotice {
     pub title: String,
     pub url: String,
     pub author: Option<String>,
+    /// `YYYY-MM-DD`로 정규화된 날짜 (`normalize_date`). 해석 불가면 `None`.
     pub date: Option<String>,
+    /// 게시판이 표시한 원본 날짜 문자열.
     #[allow(dead_code)]
-    pub category: Option<String>,
+    pub original_date: Option<String>,
     #[allow(dead_code)]
+    pub category: Option<String>,
     pub is_pinned: bool,
+    /// 목록에 표시된 조회수 (없으면 `None`).
+    pub views: Option<u32>,
+}
+
+/// 상세 페이지에서 추출한 본문 요약과 정확한 게시 시각.
+#[derive(Debug, Clone, Default)]
+pub struct NoticeDetail {
+    pub body: Option<String>,
+    pub published: Option<String>,
+    /// 본문 안 이미지 주소 (`fetch_detail`이 절대 URL로 바꾼다).
+    pub images: Vec<String>,
+}
+
+/// `fetch_notices` 결과. `body`는 파서가 실제로 읽은 목록 응답 원문 (0건 스냅샷용,
+/// 여러 페이지를 읽는 파서는 첫 페이지).
+#[derive(Debug)]
+pub struct FetchedList {
+    pub notices: Vec<RawNotice>,
+    pub body: String,
 }
 
+/// DB에 저장하는 본문 최대 길이 (문자 수).
+const BODY_MAX_CHARS: usize = 2000;
+
+/// 상세 페이지에서 모으는 이미지 최대 개수 (텔레그램 앨범 한도).
+pub const DETAIL_MAX_IMAGES: usize = 10;
+
 #[async_trait]
 pub trait NoticeParser: Send + Sync {
-    async fn fetch_notices(&self, client: &Client) -> anyhow::Result<Vec<RawNotice>>;
+    /// 첫 목록 요청 전 호스트 대기는 호출한 쪽이 한다. 요청을 더 보내는 파서(여러 페이지)는
+    /// 요청마다 `throttle`을 거친다.
+    async fn fetch_notices(&self, client: &Client, throttle: &HostThrottle) -> anyhow::Result<FetchedList>;
     fn source_key(&self) -> &str;
     fn display_name(&self) -> &str;
+
+    /// 0건 스냅샷 파일 확장자 (목록 응답 형식).
+    fn snapshot_ext(&self) -> &'static str {
+        "html"
+    }
+
+    /// 상세(view) 페이지 HTML에서 본문/게시 시각 추출. 지원하지 않는 파서는 `None`.
+    fn parse_detail(&self, _html: &str) -> Option<NoticeDetail> {
+        None
+    }
+
+    /// 상세 페이지를 받아 `parse_detail`로 넘긴다 (`fetch_detail = true` 소스 전용).
+    async fn fetch_detail(&self, client: &Client, url: &str) -> anyhow::Result<NoticeDetail> {
+        let html = fetch_text(client, url).await?;
+        let mut detail = self.parse_detail(&html).unwrap_or_default();
+        if let Ok(base) = reqwest::Url::parse(url) {
+            detail.images = detail
+                .images
+                .iter()
+                .filter_map(|src| base.join(src).ok())
+                .filter(|u| matches!(u.scheme(), "http" | "https"))
+                .map(String::from)
+                .collect();
+        }
+        Ok(detail)
+    }
+}
+
+/// GET 요청 후 상태 확인 + 인코딩을 판별해 UTF-8 문자열로 반환.
+pub(crate) async fn fetch_text(client: &Client, url: &str) -> anyhow::Result<String> {
+    let resp = client.get(url).send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(AppError::Status { status, url: url.to_string() }.into());
+    }
+    read_text(resp).await
+}
+
+/// 응답 본문을 UTF-8 문자열로. `resp.text()`는 헤더에 charset이 없으면 UTF-8로
+/// 가정해 EUC-KR 페이지를 깨뜨리므로 본문의 `<meta charset>`까지 본다.
+pub(crate) async fn read_text(resp: reqwest::Response) -> anyhow::Result<String> {
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let bytes = resp.bytes().await?;
+    Ok(decode_body(&bytes, content_type.as_deref()))
+}
+
+/// 바이트 → 문자열. 인코딩은 BOM > Content-Type charset > `<meta charset>` 순으로
+/// 정하고, 아무 표시도 없는데 UTF-8로 깨지면 EUC-KR(CP949)로 다시 시도한다.
+pub(crate) fn decode_body(bytes: &[u8], content_type: Option<&str>) -> String {
+    let declared = content_type
+        .and_then(charset_param)
+        .or_else(|| {
+            let head = String::from_utf8_lossy(&bytes[..bytes.len().min(2048)]);
+            meta_charset(&head)
+        })
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes()));
+
+    match declared {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => {
+            let (text, _, had_errors) = encoding_rs::UTF_8.decode(bytes);
+            if had_errors {
+                let (korean, _, korean_errors) = encoding_rs::EUC_KR.decode(bytes);
+                if !korean_errors {
+                    return korean.into_owned();
+                }
+            }
+            text.into_owned()
+        }
+    }
+}
+
+/// `text/html; charset=euc-kr` → `euc-kr`.
+fn charset_param(content_type: &str) -> Option<String> {
+    content_type.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+    })
+}
+
+static META_CHARSET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?([A-Za-z0-9_\-]+)"#).unwrap());
+
+/// `<meta charset="euc-kr">` 또는 `<meta http-equiv=... content="...; charset=euc-kr">`.
+fn meta_charset(head: &str) -> Option<String> {
+    META_CHARSET_RE.captures(head).map(|caps| caps[1].to_string())
+}
+
+/// 상세 페이지의 게시 날짜(+시각) (`2026.02.06 14:30`).
+static DETAIL_DATETIME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{4})[.\-/](\d{1,2})[.\-/](\d{1,2})\.?(?:\s+(\d{1,2}):(\d{2}))?").unwrap());
+
+/// 상세 페이지 공통 추출 로직.
+/// `body_selectors` 중 처음 매칭되는 요소의 텍스트를 본문으로,
+/// `meta_selectors` 영역에서 처음 발견되는 날짜+시각을 게시 시각으로 사용한다.
+/// 본문 요소 안의 `<img src>`는 그대로(상대 경로 포함) 모아 둔다.
+pub(crate) fn extract_detail(
+    html: &str,
+    body_selectors: &[&str],
+    meta_selectors: &[&str],
+) -> Option<NoticeDetail> {
+    let document = Html::parse_document(html);
+
+    let body_el = body_selectors
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|sel| document.select(&sel).next());
+    let img_sel = Selector::parse("img[src]").unwrap();
+    let images: Vec<String> = body_el
+        .map(|el| {
+            el.select(&img_sel)
+                .filter_map(|img| img.value().attr("src"))
+                .map(str::trim)
+                .filter(|src| !src.is_empty() && !src.starts_with("data:"))
+                .map(String::from)
+                .take(DETAIL_MAX_IMAGES)
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = body_el
+        .map(|el| collapse_whitespace(&el.text().collect::<Vec<_>>().join(" ")))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.chars().take(BODY_MAX_CHARS).collect::<String>());
+
+    let published = meta_selectors
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .flat_map(|sel| {
+            document
+                .select(&sel)
+                .map(|el| el.text().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .find_map(|text| {
+            let caps = DETAIL_DATETIME_RE.captures(&text)?;
+            let date = format!(
+                "{}-{:0>2}-{:0>2}",
+                &caps[1], &caps[2], &caps[3]
+            );
+            Some(match (caps.get(4), caps.get(5)) {
+                (Some(h), Some(m)) => format!("{} {:0>2}:{}", date, h.as_str(), m.as_str()),
+                _ => date,
+            })
+        });
+
+    if body.is_none() && published.is_none() && images.is_empty() {
+        None
+    } else {
+        Some(NoticeDetail { body, published, images })
+    }
+}
+
+/// 정규화된 게시일(`YYYY-MM-DD...`)이 `min`보다 이전인지. 날짜를 모르면 `false`.
+pub fn dated_before(notice: &RawNotice, min: NaiveDate) -> bool {
+    notice
+        .date
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok())
+        .is_some_and(|d| d < min)
+}
+
+/// 목록의 날짜 문자열을 `YYYY-MM-DD`로 정규화한다.
+///
+/// 지원: `2026.02.06`, `2026-02-01`, `2026/2/1`, `26.02.06`, `2026년 2월 6일`,
+/// 그리고 연도 없는 `02-06`, `02.06`, `2월 6일` (뒤에 붙은 시각은 무시).
+/// 연도가 없으면 `default_year`를 쓰되, 그 날짜가 오늘보다 한 달 넘게
+/// 미래라면 작년 글로 본다 (1월에 보이는 `12-24` 등).
+pub fn normalize_date(raw: &str, default_year: i32) -> Option<String> {
+    normalize_date_at(raw, default_year, crate::kst::today())
+}
+
+/// 연도가 있는 날짜 (`2026.02.06`, `26-02-06`, `2026년 2월 6일`).
+static FULL_DATE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4}|\d{2})\s*[.\-/년]\s*(\d{1,2})\s*[.\-/월]\s*(\d{1,2})").unwrap());
+/// 연도 없는 날짜 (`02-06`, `2월 6일`).
+static MONTH_DAY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{1,2})\s*[.\-/월]\s*(\d{1,2})(?:[^\d]|$)").unwrap());
+
+fn normalize_date_at(raw: &str, default_year: i32, today: NaiveDate) -> Option<String> {
+    let raw = raw.trim();
+
+    if let Some(caps) = FULL_DATE_RE.captures(raw) {
+        let mut y: i32 = caps[1].parse().ok()?;
+        if caps[1].len() == 2 {
+            y += 2000;
+        }
+        let d = NaiveDate::from_ymd_opt(y, caps[2].parse().ok()?, caps[3].parse().ok()?)?;
+        return Some(d.format("%Y-%m-%d").to_string());
+    }
+
+    if let Some(caps) = MONTH_DAY_RE.captures(raw) {
+        let m: u32 = caps[1].parse().ok()?;
+        let d: u32 = caps[2].parse().ok()?;
+        let mut date = NaiveDate::from_ymd_opt(default_year, m, d)?;
+        if date.year() == today.year() && date > today + chrono::Duration::days(31) {
+            date = NaiveDate::from_ymd_opt(default_year - 1, m, d)?;
+        }
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+
+    None
 }
 
-pub fn create_parser(source: &SourceConfig) -> Box<dyn NoticeParser> {
-    match source.parser.as_str() {
+/// 날짜 정규화 기본 연도 (현재 연도).
+pub(crate) fn current_year() -> i32 {
+    crate::kst::today().year()
+}
+
+/// 조회수 셀 텍스트 → 숫자 (`1,523` 같은 천 단위 구분 허용). 숫자가 아니면 `None`.
+pub(crate) fn parse_views(text: &str) -> Option<u32> {
+    let digits: String = text.trim().chars().filter(|c| *c != ',').collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// 제목 끝에 붙는 게시판 배지 텍스트 (새 글/인기 글 아이콘의 alt 등).
+const TITLE_BADGES: &[&str] = &["NEW", "New", "new", "HOT", "Hot", "hot", "[새글]", "새글"];
+
+/// 파싱한 제목 정리: HTML 엔티티 디코드, 제로폭 문자 제거, 공백 정리,
+/// 끝에 붙은 배지(`NEW` 등) 제거. 엔티티는 한 번만 디코드하므로
+/// `&amp;lt;` 같은 값은 `&lt;`로 남는다.
+pub fn clean_title(raw: &str) -> String {
+    let decoded = decode_entities(raw);
+    let cleaned: String = decoded
+        .chars()
+        .filter(|c| !matches!(c, '\u{200b}' | '\u{200c}' | '\u{200d}' | '\u{2060}' | '\u{feff}'))
+        .collect();
+    let mut title = collapse_whitespace(&cleaned);
+
+    // 배지는 여러 개 붙을 수 있다 ("제목 NEW HOT")
+    loop {
+        let stripped = TITLE_BADGES.iter().find_map(|badge| {
+            let rest = title.strip_suffix(badge)?;
+            // "RENEW"처럼 영단어 일부인 경우는 건드리지 않는다
+            match rest.chars().last() {
+                Some(c) if c.is_ascii_alphanumeric() => None,
+                None => None,
+                _ => Some(rest.trim_end().to_string()),
+            }
+        });
+        match stripped {
+            Some(rest) => title = rest,
+            None => break,
+        }
+    }
+    title
+}
+
+static ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+
+/// 자주 쓰이는 이름 엔티티와 숫자 엔티티(`&#39;`, `&#x27;`) 디코드. 모르는 엔티티는 그대로 둔다.
+fn decode_entities(s: &str) -> String {
+    ENTITY_RE.replace_all(s, |caps: &regex::Captures| {
+        let name = &caps[1];
+        let decoded = match name {
+            "nbsp" => Some(' '),
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "middot" => Some('·'),
+            _ if name.starts_with("#x") || name.starts_with("#X") => {
+                u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32)
+            }
+            _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        decoded.map(String::from).unwrap_or_else(|| caps[0].to_string())
+    })
+    .into_owned()
+}
+
+/// 연속 공백/개행을 한 칸으로 줄인다.
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 다음 크롤의 커서로 저장할 id: 맨 위 일반 공지 (고정 공지는 매번 위에 떠서 제외).
+pub fn cursor_id(notices: &[RawNotice]) -> Option<&str> {
+    notices
+        .iter()
+        .find(|n| !n.is_pinned)
+        .or_else(|| notices.first())
+        .map(|n| n.notice_id.as_str())
+}
+
+/// 목록(최신순)에서 지난 커서 위쪽, 즉 새 공지 후보의 개수.
+/// 커서가 목록에 없거나(한 페이지 넘게 올라와 밀려났거나 삭제됨) id가 숫자로 늘어나는
+/// 게시판이 아니면 `None` → 전체를 본다.
+pub fn fresh_len(notices: &[RawNotice], cursor: Option<&str>) -> Option<usize> {
+    let cursor = cursor?;
+    let cursor_num: u64 = cursor.parse().ok()?;
+    let pos = notices.iter().position(|n| !n.is_pinned && n.notice_id == cursor)?;
+    let monotonic = notices[..pos]
+        .iter()
+        .filter(|n| !n.is_pinned)
+        .all(|n| n.notice_id.parse::<u64>().is_ok_and(|id| id > cursor_num));
+    monotonic.then_some(pos)
+}
+
+/// 지원하는 파서 종류와 각 파서의 필수 `[source.params]` 키.
+pub const PARSER_TYPES: &[(&str, &[&str])] = &[
+    ("egov", &["bbsNo", "key"]),
+    ("php_master", &["pg_idx"]),
+    ("ciboard", &[]),
+    ("xe_board", &["mid"]),
+    ("json_api", &[]),
+    ("css", &["row_selector"]),
+];
+
+pub fn create_parser(source: &SourceConfig) -> anyhow::Result<Box<dyn NoticeParser>> {
+    let parser: Box<dyn NoticeParser> = match source.parser.as_str() {
         "egov" => Box::new(egov::EgovParser::from_config(source)),
         "php_master" => Box::new(php_master::PhpMasterParser::from_config(source)),
         "ciboard" => Box::new(ciboard::CiBoardParser::from_config(source)),
         "xe_board" => Box::new(xe_board::XeBoardParser::from_config(source)),
-        other => panic!("Unknown parser type: {other}"),
+        "json_api" => Box::new(json_api::JsonApiParser::from_config(source)),
+        "css" => Box::new(css::CssParser::from_config(source)),
+        other => anyhow::bail!("Unknown parser type '{}' for source '{}'", other, source.key),
+    };
+    Ok(parser)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 10).unwrap()
+    }
+
+    #[test]
+    fn test_decode_euc_kr_fixture() {
+        let bytes = std::fs::read("tests/fixtures/euckr_sample.html")
+            .expect("Missing fixture: tests/fixtures/euckr_sample.html");
+        assert!(std::str::from_utf8(&bytes).is_err(), "fixture must not be UTF-8");
+
+        // <meta> 선언으로 판별
+        let html = decode_body(&bytes, Some("text/html"));
+        assert!(html.contains("2026학년도 학과 오리엔테이션 안내"));
+        assert!(!html.contains('\u{fffd}'));
+
+        // 헤더 charset이 우선, 선언이 전혀 없어도 EUC-KR로 복구
+        assert!(decode_body(&bytes, Some("text/html; charset=EUC-KR")).contains("오리엔테이션"));
+        let no_meta = decode_body(&bytes[bytes.len() - 120..], None);
+        assert!(no_meta.contains("안내"), "{no_meta}");
+
+        // 보통의 UTF-8 본문은 그대로
+        assert_eq!(decode_body("공지 안내".as_bytes(), None), "공지 안내");
+    }
+
+    #[test]
+    fn test_parse_views() {
+        assert_eq!(parse_views(" 423 "), Some(423));
+        assert_eq!(parse_views("1,523"), Some(1523));
+        assert_eq!(parse_views("-"), None);
+        assert_eq!(parse_views("2026-02-01"), None);
+        assert_eq!(parse_views(""), None);
+    }
+
+    #[test]
+    fn test_normalize_full_dates() {
+        for raw in ["2026.02.06", "2026-02-06", "2026/2/6", "26.02.06", "2026년 2월 6일", "2026.02.06 14:02"] {
+            assert_eq!(
+                normalize_date_at(raw, 2026, today()).as_deref(),
+                Some("2026-02-06"),
+                "raw = {raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_month_day() {
+        assert_eq!(normalize_date_at("02-06", 2026, today()).as_deref(), Some("2026-02-06"));
+        assert_eq!(normalize_date_at("2.6", 2026, today()).as_deref(), Some("2026-02-06"));
+        assert_eq!(normalize_date_at("2월 6일", 2026, today()).as_deref(), Some("2026-02-06"));
+        // 한 달 이내의 미래는 올해로 유지 (예약 게시 등)
+        assert_eq!(normalize_date_at("03-01", 2026, today()).as_deref(), Some("2026-03-01"));
+    }
+
+    #[test]
+    fn test_normalize_year_rollover() {
+        // 2월에 보이는 12월 글은 작년 글
+        assert_eq!(normalize_date_at("12-24", 2026, today()).as_deref(), Some("2025-12-24"));
+        assert_eq!(normalize_date_at("06-12", 2026, today()).as_deref(), Some("2025-06-12"));
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        assert!(normalize_date_at("", 2026, today()).is_none());
+        assert!(normalize_date_at("어제", 2026, today()).is_none());
+        assert!(normalize_date_at("2026.13.40", 2026, today()).is_none());
+    }
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("장학금&nbsp;신청NEW"), "장학금 신청");
+        assert_eq!(clean_title("  수강신청   안내 \n\t"), "수강신청 안내");
+        assert_eq!(clean_title("졸업\u{200b}사정 안내 NEW"), "졸업사정 안내");
+        assert_eq!(clean_title("특강 안내 new HOT"), "특강 안내");
+        // 디코드 후의 &는 유지
+        assert_eq!(clean_title("R&amp;D 사업 공고"), "R&D 사업 공고");
+        assert_eq!(clean_title("Q&A 게시판"), "Q&A 게시판");
+        assert_eq!(clean_title("&lt;공지&gt; 휴관 &#40;임시&#x29;"), "<공지> 휴관 (임시)");
+        // 영단어 일부는 배지가 아님
+        assert_eq!(clean_title("Program RENEW"), "Program RENEW");
+        assert_eq!(clean_title("NEW"), "NEW");
+    }
+
+    #[test]
+    fn test_dated_before() {
+        let min = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let dated = |date: Option<&str>| RawNotice {
+            notice_id: "1".into(),
+            title: "공지".into(),
+            url: "https://example.com/1".into(),
+            author: None,
+            date: date.map(String::from),
+            original_date: None,
+            category: None,
+            is_pinned: false,
+            views: None,
+        };
+        assert!(dated_before(&dated(Some("2019-03-04")), min));
+        assert!(dated_before(&dated(Some("2024-12-31 18:00")), min));
+        assert!(!dated_before(&dated(Some("2025-01-01")), min));
+        assert!(!dated_before(&dated(None), min));
+    }
+
+    #[test]
+    fn test_crawl_cursor() {
+        let raw = |id: &str, is_pinned: bool| RawNotice {
+            notice_id: id.into(),
+            title: format!("공지 {}", id),
+            url: format!("https://biz.chungbuk.ac.kr/{}", id),
+            author: None,
+            date: None,
+            original_date: None,
+            category: None,
+            is_pinned,
+            views: None,
+        };
+        // 고정 공지 2개 + 새 글 2개 + 지난번 맨 위였던 105 이하
+        let listing = vec![
+            raw("90", true),
+            raw("80", true),
+            raw("107", false),
+            raw("106", false),
+            raw("105", false),
+            raw("104", false),
+        ];
+        assert_eq!(cursor_id(&listing), Some("107"));
+        assert_eq!(fresh_len(&listing, Some("105")), Some(4));
+        // 새 글이 없으면 고정 공지만 남는다
+        assert_eq!(fresh_len(&listing, Some("107")), Some(2));
+        // 커서가 목록에 없으면 (공백) 전체 처리
+        assert_eq!(fresh_len(&listing, Some("99")), None);
+        assert_eq!(fresh_len(&listing, None), None);
+        // id가 커서보다 작은 새 글이 위에 있으면 순서를 믿을 수 없어 전체 처리
+        let shuffled = vec![raw("103", false), raw("105", false), raw("104", false)];
+        assert_eq!(fresh_len(&shuffled, Some("105")), None);
+        // 숫자가 아닌 id (json_api 등)
+        assert_eq!(fresh_len(&[raw("a1", false)], Some("a1")), None);
+        // 고정 공지 id와 같아도 커서로 보지 않는다
+        assert_eq!(fresh_len(&listing, Some("90")), None);
+        // 전부 고정이면 맨 위를 커서로
+        assert_eq!(cursor_id(&listing[..2]), Some("90"));
+        assert_eq!(cursor_id(&[]), None);
+    }
+
+    #[test]
+    fn test_create_parser_unknown_type_is_err() {
+        let mut source = SourceConfig {
+            key: "lib".into(),
+            display_name: "도서관".into(),
+            parser: "rss".into(),
+            url: "https://library.chungbuk.ac.kr".into(),
+            ..Default::default()
+        };
+        let err = create_parser(&source).err().expect("unknown parser should be an error");
+        assert!(err.to_string().contains("Unknown parser type 'rss'"));
+
+        source.parser = "json_api".into();
+        assert_eq!(create_parser(&source).unwrap().source_key(), "lib");
     }
 }
@@ -3,8 +3,10 @@ use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use super::{NoticeParser, RawNotice};
+use super::{FetchedList, NoticeDetail, NoticeParser, RawNotice};
 use crate::config::SourceConfig;
+use crate::error::AppError;
+use crate::throttle::HostThrottle;
 
 /// Parser for PHP master.php CMS used by many CBNU departments.
 ///
@@ -12,6 +14,10 @@ use crate::config::SourceConfig;
 /// The main page contains hidden form fields (`bidx`, `id`) that must be
 /// extracted first, then sent with the AJAX request.
 ///
+/// Some department hosts tie the AJAX endpoint to the session cookie set by
+/// the main page, so both requests must go through the same cookie-enabled
+/// client (see `HttpClients`).
+///
 /// The response HTML uses Bootstrap grid divs (not `<table>`), with each row
 /// having class `board_rows`.
 pub struct PhpMasterParser {
@@ -56,7 +62,7 @@ impl PhpMasterParser {
     async fn extract_form_params(&self, client: &Client) -> anyhow::Result<FormParams> {
         let url = self.main_page_url();
         let resp = client.get(&url).send().await?;
-        let html = resp.text().await?;
+        let html = super::read_text(resp).await?;
         let document = Html::parse_document(&html);
 
         let bidx_sel = Selector::parse("input#bidx").unwrap();
@@ -89,6 +95,7 @@ impl PhpMasterParser {
         let div_sel = Selector::parse("div").unwrap();
         let a_sel = Selector::parse("a[href]").unwrap();
 
+        let year = super::current_year();
         let mut notices = Vec::new();
 
         for row in document.select(&row_sel) {
@@ -109,7 +116,7 @@ impl PhpMasterParser {
                 None => continue,
             };
 
-            let title = link.text().collect::<String>().trim().to_string();
+            let title = super::clean_title(&link.text().collect::<String>());
             if title.is_empty() {
                 continue;
             }
@@ -141,9 +148,15 @@ impl PhpMasterParser {
                 title,
                 url,
                 author,
-                date,
+                date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                original_date: date,
                 category: None, // PHP CMS doesn't have categories
                 is_pinned,
+                views: if divs.len() >= 5 {
+                    divs.last().and_then(|d| super::parse_views(&d.text().collect::<String>()))
+                } else {
+                    None
+                },
             });
         }
 
@@ -153,7 +166,7 @@ impl PhpMasterParser {
 
 #[async_trait]
 impl NoticeParser for PhpMasterParser {
-    async fn fetch_notices(&self, client: &Client) -> anyhow::Result<Vec<RawNotice>> {
+    async fn fetch_notices(&self, client: &Client, throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
         tracing::info!(
             source = %self.source_key,
             pg_idx = %self.pg_idx,
@@ -163,8 +176,9 @@ impl NoticeParser for PhpMasterParser {
         // Step 1: Fetch main page to get form params (bidx, id)
         let params = self.extract_form_params(client).await?;
 
-        // Step 2: AJAX POST for board content
+        // Step 2: AJAX POST for board content (같은 호스트 두 번째 요청이므로 간격을 지킨다)
         let ajax_url = self.ajax_url();
+        throttle.wait(&ajax_url).await;
         let form_params = [
             ("pg_idx", self.pg_idx.as_str()),
             ("bidx", params.bidx.as_str()),
@@ -186,10 +200,10 @@ impl NoticeParser for PhpMasterParser {
 
         let status = resp.status();
         if !status.is_success() {
-            anyhow::bail!("HTTP {} from {}", status, ajax_url);
+            return Err(AppError::Status { status, url: ajax_url }.into());
         }
 
-        let html = resp.text().await?;
+        let html = super::read_text(resp).await?;
         if html.trim().is_empty() {
             anyhow::bail!("Empty response from {}", ajax_url);
         }
@@ -202,7 +216,7 @@ impl NoticeParser for PhpMasterParser {
             "Parsed PHP master notices"
         );
 
-        Ok(notices)
+        Ok(FetchedList { notices, body: html })
     }
 
     fn source_key(&self) -> &str {
@@ -212,6 +226,14 @@ impl NoticeParser for PhpMasterParser {
     fn display_name(&self) -> &str {
         &self.display_name
     }
+
+    fn parse_detail(&self, html: &str) -> Option<NoticeDetail> {
+        super::extract_detail(
+            html,
+            &["div.board_view_contents", "div.view_content"],
+            &["div.board_view_info", "div.board_view_top"],
+        )
+    }
 }
 
 #[cfg(test)]
@@ -229,8 +251,7 @@ mod tests {
             parser: "php_master".into(),
             url: "https://biz.chungbuk.ac.kr".into(),
             params,
-            enabled: true,
-            channel: None,
+            ..Default::default()
         }
     }
 
@@ -259,4 +280,17 @@ mod tests {
         let unique: std::collections::HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len(), "All notice_ids should be unique");
     }
+
+    #[test]
+    fn test_parse_php_master_detail_fixture() {
+        let html = std::fs::read_to_string("tests/fixtures/php_master_detail_sample.html")
+            .expect("Missing fixture: tests/fixtures/php_master_detail_sample.html");
+        let parser = PhpMasterParser::from_config(&test_config());
+        let detail = parser.parse_detail(&html).expect("Should parse detail page");
+
+        assert_eq!(detail.published.as_deref(), Some("2026-01-28 16:05"));
+        let body = detail.body.expect("Should extract body text");
+        assert!(body.contains("졸업논문을 기한 내에"));
+        assert!(!body.contains('\n'), "Whitespace should be collapsed");
+    }
 }
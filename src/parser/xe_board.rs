@@ -1,10 +1,18 @@
+use std::sync::LazyLock;
+
 use async_trait::async_trait;
 use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 
-use super::{NoticeParser, RawNotice};
+use super::{FetchedList, NoticeDetail, NoticeParser, RawNotice};
 use crate::config::SourceConfig;
+use crate::throttle::HostThrottle;
+
+/// 짧은 주소(`/{mid}/{srl}`)의 글 번호.
+static SRL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/(\d+)(?:\?|#|$)").unwrap());
+/// 쿼리 주소(`?document_srl=`)의 글 번호.
+static DOCUMENT_SRL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"document_srl=(\d+)").unwrap());
 
 /// Parser for XpressEngine (XE) board modules.
 ///
@@ -28,11 +36,15 @@ use crate::config::SourceConfig;
 ///   </tbody>
 /// </table>
 /// ```
+///
+/// `params.max_pages`를 주면 `?page=N`으로 다음 페이지까지 읽어 `document_srl`
+/// 기준으로 합친다. 새 글이 하나도 없는 페이지를 만나면 멈춘다. 기본은 1페이지.
 pub struct XeBoardParser {
     source_key: String,
     display_name: String,
     base_url: String,
     mid: String,
+    max_pages: u32,
 }
 
 impl XeBoardParser {
@@ -42,6 +54,12 @@ impl XeBoardParser {
             display_name: config.display_name.clone(),
             base_url: config.url.trim_end_matches('/').to_string(),
             mid: config.params.get("mid").cloned().unwrap_or_default(),
+            max_pages: config
+                .params
+                .get("max_pages")
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(1)
+                .max(1),
         }
     }
 
@@ -49,13 +67,20 @@ impl XeBoardParser {
         format!("{}/{}", self.base_url, self.mid)
     }
 
+    fn page_url(&self, page: u32) -> String {
+        if page <= 1 {
+            self.board_url()
+        } else {
+            format!("{}?page={}", self.board_url(), page)
+        }
+    }
+
     fn build_view_url(&self, document_srl: &str) -> String {
         format!("{}/{}/{}", self.base_url, self.mid, document_srl)
     }
 
     fn parse_html(&self, html: &str) -> anyhow::Result<Vec<RawNotice>> {
         let document = Html::parse_document(html);
-        let srl_re = Regex::new(r"/(\d+)(?:\?|#|$)")?;
 
         let table_selectors = [
             "table.bd_lst tbody tr",
@@ -69,7 +94,9 @@ impl XeBoardParser {
         let title_sel = Selector::parse("td.title").unwrap();
         let author_sel = Selector::parse("td.author").unwrap();
         let time_sel = Selector::parse("td.time").unwrap();
+        let views_sel = Selector::parse("td.m_no").unwrap();
 
+        let year = super::current_year();
         let mut notices = Vec::new();
 
         for sel_str in &table_selectors {
@@ -100,18 +127,17 @@ impl XeBoardParser {
                 };
 
                 let href = link.value().attr("href").unwrap_or("");
-                let notice_id = if let Some(caps) = srl_re.captures(href) {
+                let notice_id = if let Some(caps) = SRL_RE.captures(href) {
                     caps[1].to_string()
                 } else {
                     // Try document_srl parameter
-                    let dsrl_re = Regex::new(r"document_srl=(\d+)").unwrap();
-                    match dsrl_re.captures(href) {
+                    match DOCUMENT_SRL_RE.captures(href) {
                         Some(caps) => caps[1].to_string(),
                         None => continue,
                     }
                 };
 
-                let title = link.text().collect::<String>().trim().to_string();
+                let title = super::clean_title(&link.text().collect::<String>());
                 if title.is_empty() {
                     continue;
                 }
@@ -144,9 +170,14 @@ impl XeBoardParser {
                     title,
                     url,
                     author,
-                    date,
+                    date: date.as_deref().and_then(|d| super::normalize_date(d, year)),
+                    original_date: date,
                     category: None,
                     is_pinned,
+                    views: row
+                        .select(&views_sel)
+                        .next()
+                        .and_then(|td| super::parse_views(&td.text().collect::<String>())),
                 });
             }
 
@@ -161,26 +192,46 @@ impl XeBoardParser {
 
 #[async_trait]
 impl NoticeParser for XeBoardParser {
-    async fn fetch_notices(&self, client: &Client) -> anyhow::Result<Vec<RawNotice>> {
+    async fn fetch_notices(&self, client: &Client, throttle: &HostThrottle) -> anyhow::Result<FetchedList> {
         let url = self.board_url();
         tracing::info!(source = %self.source_key, url = %url, "Fetching XE board notices");
 
-        let resp = client.get(&url).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            anyhow::bail!("HTTP {} from {}", status, url);
+        let body = super::fetch_text(client, &url).await?;
+        let mut notices = self.parse_html(&body)?;
+        let mut seen: std::collections::HashSet<String> =
+            notices.iter().map(|n| n.notice_id.clone()).collect();
+        for page in 2..=self.max_pages {
+            let page_url = self.page_url(page);
+            throttle.wait(&page_url).await;
+            // 뒷 페이지 실패는 앞 페이지 결과를 버리지 않고 거기서 멈춘다
+            let parsed = match super::fetch_text(client, &page_url)
+                .await
+                .and_then(|html| self.parse_html(&html))
+            {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    tracing::warn!(source = %self.source_key, page, error = %e, "Page fetch failed; keeping earlier pages");
+                    break;
+                }
+            };
+            // 고정 공지는 매 페이지 위에 다시 나오므로 id로 거른다
+            let fresh: Vec<RawNotice> = parsed
+                .into_iter()
+                .filter(|n| seen.insert(n.notice_id.clone()))
+                .collect();
+            if fresh.is_empty() {
+                break;
+            }
+            notices.extend(fresh);
         }
 
-        let html = resp.text().await?;
-        let notices = self.parse_html(&html)?;
-
         tracing::info!(
             source = %self.source_key,
             count = notices.len(),
             "Parsed XE board notices"
         );
 
-        Ok(notices)
+        Ok(FetchedList { notices, body })
     }
 
     fn source_key(&self) -> &str {
@@ -190,6 +241,14 @@ impl NoticeParser for XeBoardParser {
     fn display_name(&self) -> &str {
         &self.display_name
     }
+
+    fn parse_detail(&self, html: &str) -> Option<NoticeDetail> {
+        super::extract_detail(
+            html,
+            &["div.rd_body div.xe_content", "div.xe_content"],
+            &["div.rd_hd .date", "div.top_area .date"],
+        )
+    }
 }
 
 #[cfg(test)]
@@ -207,8 +266,7 @@ mod tests {
             parser: "xe_board".into(),
             url: "https://civil.chungbuk.ac.kr".into(),
             params,
-            enabled: true,
-            channel: None,
+            ..Default::default()
         }
     }
 
@@ -226,6 +284,7 @@ mod tests {
         assert!(!first.notice_id.is_empty());
         assert!(!first.title.is_empty());
         assert!(first.url.contains("board_jIDW98"));
+        assert_eq!(first.views, Some(4817));
         println!(
             "First: id={} title={} pinned={} author={:?} date={:?}",
             first.notice_id, first.title, first.is_pinned, first.author, first.date
@@ -236,4 +295,88 @@ mod tests {
         let unique: std::collections::HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len(), "All notice_ids should be unique");
     }
+
+    /// 테스트용 XE 목록 한 페이지. (document_srl, 고정 여부)
+    fn board_page(rows: &[(u32, bool)]) -> String {
+        let rows: String = rows
+            .iter()
+            .map(|(srl, pinned)| {
+                format!(
+                    r#"<tr><td class="no">{}</td><td class="title"><a href="/board_jIDW98/{}">공지 {}</a></td><td class="author">학과</td><td class="time">2026.03.02</td></tr>"#,
+                    if *pinned { "<strong>공지</strong>".to_string() } else { srl.to_string() },
+                    srl,
+                    srl
+                )
+            })
+            .collect();
+        format!(r#"<table class="bd_lst"><tbody>{}</tbody></table>"#, rows)
+    }
+
+    #[tokio::test]
+    async fn test_pages_merge() {
+        use axum::extract::Query;
+
+        // 1페이지: 고정 1 + 105~104, 2페이지: 고정 1 + 103~102, 3페이지 이후: 고정만
+        let app = axum::Router::new().route(
+            "/board_jIDW98",
+            axum::routing::get(|Query(q): Query<HashMap<String, String>>| async move {
+                let rows: &[(u32, bool)] = match q.get("page").map(String::as_str) {
+                    None | Some("1") => &[(1, true), (105, false), (104, false)],
+                    Some("2") => &[(1, true), (103, false), (102, false)],
+                    _ => &[(1, true)],
+                };
+                axum::response::Html(board_page(rows))
+            }),
+        )
+        .route(
+            "/broken",
+            axum::routing::get(|Query(q): Query<HashMap<String, String>>| async move {
+                match q.get("page").map(String::as_str) {
+                    None | Some("1") => Ok(axum::response::Html(board_page(&[(105, false), (104, false), (103, false)]))),
+                    _ => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut config = test_config();
+        config.url = format!("http://{}", addr);
+        config.params.insert("max_pages".into(), "5".into());
+        let client = Client::new();
+        // 뒷 페이지 요청도 호스트 간격을 지킨다 (2, 3페이지 사이 한 번 대기)
+        let throttle = HostThrottle::new(50);
+        let started = std::time::Instant::now();
+        let notices = XeBoardParser::from_config(&config).fetch_notices(&client, &throttle).await.unwrap().notices;
+        assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+        let ids: Vec<&str> = notices.iter().map(|n| n.notice_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "105", "104", "103", "102"]);
+        assert!(notices[0].is_pinned);
+
+        // 기본은 1페이지만
+        let throttle = HostThrottle::new(0);
+        config.params.remove("max_pages");
+        let notices = XeBoardParser::from_config(&config).fetch_notices(&client, &throttle).await.unwrap().notices;
+        assert_eq!(notices.len(), 3);
+
+        // 2페이지가 실패해도 1페이지 결과는 남는다
+        config.params.insert("max_pages".into(), "5".into());
+        config.params.insert("mid".into(), "broken".into());
+        let notices = XeBoardParser::from_config(&config).fetch_notices(&client, &throttle).await.unwrap().notices;
+        assert_eq!(notices.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_xe_board_detail_fixture() {
+        let html = std::fs::read_to_string("tests/fixtures/xe_board_detail_sample.html")
+            .expect("Missing fixture: tests/fixtures/xe_board_detail_sample.html");
+        let parser = XeBoardParser::from_config(&test_config());
+        let detail = parser.parse_detail(&html).expect("Should parse detail page");
+
+        assert_eq!(detail.published.as_deref(), Some("2026-02-06 14:02"));
+        let body = detail.body.expect("Should extract body text");
+        assert!(body.contains("오리엔테이션을 아래와 같이"));
+        assert!(!body.contains('\n'), "Whitespace should be collapsed");
+    }
 }
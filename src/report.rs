@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::db::WeeklyStats;
+use crate::format::{split_message, MESSAGE_MAX_CHARS};
+
+/// `report_log`에 기록하는 주간 리포트 종류 이름.
+pub const WEEKLY: &str = "weekly";
+
+/// `now` 이전(같은 시각 포함) 가장 최근의 `weekday` `hour`시 정각.
+pub fn weekly_slot(now: NaiveDateTime, weekday: Weekday, hour: u32) -> NaiveDateTime {
+    let days_back = (now.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    let time = NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or(NaiveTime::MIN);
+    let slot = (now.date() - Duration::days(days_back as i64)).and_time(time);
+    if slot > now {
+        slot - Duration::days(7)
+    } else {
+        slot
+    }
+}
+
+/// 마지막 발송 시각(`last_sent`, DB 문자열)이 이번 주 슬롯보다 이전이면 보낼 차례.
+pub fn is_due(now: NaiveDateTime, weekday: Weekday, hour: u32, last_sent: Option<&str>) -> bool {
+    let slot = weekly_slot(now, weekday, hour);
+    match last_sent.and_then(|s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok()) {
+        Some(sent) => sent < slot,
+        None => true,
+    }
+}
+
+/// 주간 리포트 본문 (평문). 길면 여러 메시지로 나눠 반환.
+pub fn render(stats: &WeeklyStats, display_names: &HashMap<String, String>) -> Vec<String> {
+    let name = |key: &str| display_names.get(key).map(String::as_str).unwrap_or(key).to_string();
+    let total: u32 = stats.crawled.iter().map(|(_, n)| n).sum();
+
+    let mut text = format!(
+        "\u{1f4ca} 주간 리포트 (최근 7일)\n\n\
+         수집 공지: {}건\n\
+         신규 구독자: {}명\n\
+         DM 발송: {}건\n",
+        total, stats.new_users, stats.dms_sent
+    );
+
+    if !stats.crawled.is_empty() {
+        text.push_str("\n소스별 수집:\n");
+        for (key, count) in &stats.crawled {
+            text.push_str(&format!("  • {}: {}건\n", name(key), count));
+        }
+    }
+
+    if stats.failing.is_empty() {
+        text.push_str("\n\u{2705} 계속 실패 중인 소스 없음\n");
+    } else {
+        text.push_str("\n\u{26a0}\u{fe0f} 계속 실패 중인 소스:\n");
+        for (key, errors, last_success) in &stats.failing {
+            text.push_str(&format!(
+                "  • {}: 연속 {}회 실패 (마지막 성공: {})\n",
+                name(key),
+                errors,
+                last_success.as_deref().unwrap_or("없음")
+            ));
+        }
+    }
+
+    split_message(text.trim_end(), MESSAGE_MAX_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_weekly_slot_and_due() {
+        // 2026-03-09는 월요일
+        assert_eq!(weekly_slot(at("2026-03-11 15:00:00"), Weekday::Mon, 9), at("2026-03-09 09:00:00"));
+        assert_eq!(weekly_slot(at("2026-03-09 09:00:00"), Weekday::Mon, 9), at("2026-03-09 09:00:00"));
+        // 월요일이지만 아직 9시 전이면 지난주
+        assert_eq!(weekly_slot(at("2026-03-09 08:59:00"), Weekday::Mon, 9), at("2026-03-02 09:00:00"));
+        assert_eq!(weekly_slot(at("2026-03-09 10:00:00"), Weekday::Fri, 18), at("2026-03-06 18:00:00"));
+
+        let now = at("2026-03-09 09:15:00");
+        assert!(is_due(now, Weekday::Mon, 9, None));
+        assert!(is_due(now, Weekday::Mon, 9, Some("2026-03-02 09:05:00")));
+        assert!(!is_due(now, Weekday::Mon, 9, Some("2026-03-09 09:05:00")));
+    }
+
+    #[test]
+    fn test_render_splits_long_report() {
+        let stats = WeeklyStats {
+            crawled: (0..400).map(|i| (format!("source_{:03}", i), 1)).collect(),
+            new_users: 2,
+            dms_sent: 5,
+            failing: vec![("physics".into(), 4, None)],
+        };
+        let names = HashMap::from([("physics".to_string(), "물리학과".to_string())]);
+        let messages = render(&stats, &names);
+        assert!(messages.len() > 1);
+        assert!(messages.iter().all(|m| m.chars().count() <= MESSAGE_MAX_CHARS));
+        assert!(messages[0].contains("수집 공지: 400건"));
+        assert!(messages.last().unwrap().contains("물리학과: 연속 4회 실패 (마지막 성공: 없음)"));
+    }
+}
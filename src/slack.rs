@@ -0,0 +1,82 @@
+use serde_json::{json, Value};
+
+use crate::category::Category;
+use crate::db::Notice;
+
+/// 슬랙 mrkdwn 이스케이프 (`&`, `<`, `>`만 특수 문자).
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// 슬랙 incoming webhook 요청 본문: 제목 링크 section + 소스/분류/게시일 context.
+/// `[slack] sources`에 넣은 소스의 공지만 `webhook::WebhookNotifier`로 게시한다.
+/// `text`는 알림 미리보기용 대체 문구.
+pub fn blocks_payload(notice: &Notice) -> Value {
+    let category = Category::from_str_tag(&notice.category);
+    let edited = if notice.edited { "[수정됨] " } else { "" };
+    let title = escape_mrkdwn(&notice.title);
+    let context = format!(
+        "{} · {} {} · {} · {}",
+        escape_mrkdwn(&notice.source_display_name),
+        category.emoji(),
+        category.label(),
+        escape_mrkdwn(notice.published.as_deref().unwrap_or("날짜 미상")),
+        escape_mrkdwn(notice.author.as_deref().unwrap_or("작성자 미상")),
+    );
+    json!({
+        "text": format!("{}{} - {}", edited, notice.title, notice.source_display_name),
+        "blocks": [
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("{}*<{}|{}>*", edited, notice.url, title) },
+            },
+            {
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": context }],
+            },
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_payload() {
+        let notice = Notice {
+            id: 1,
+            source_key: "physics".into(),
+            notice_id: "7".into(),
+            title: "연구실 안전교육 <필수> & 이수 안내".into(),
+            url: "https://physics.chungbuk.ac.kr/view?no=7".into(),
+            author: None,
+            category: "academic".into(),
+            published: Some("2026-03-05".into()),
+            source_display_name: "물리학과".into(),
+            body: None,
+            edited: false,
+            urgent: false,
+        };
+        let payload = blocks_payload(&notice);
+        assert_eq!(payload["text"], "연구실 안전교육 <필수> & 이수 안내 - 물리학과");
+
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["type"], "section");
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            "*<https://physics.chungbuk.ac.kr/view?no=7|연구실 안전교육 &lt;필수&gt; &amp; 이수 안내>*"
+        );
+        assert_eq!(blocks[1]["type"], "context");
+        let context = blocks[1]["elements"][0]["text"].as_str().unwrap();
+        assert!(context.starts_with("물리학과 · "));
+        assert!(context.ends_with(" · 2026-03-05 · 작성자 미상"));
+
+        let edited = Notice { edited: true, ..notice };
+        assert!(blocks_payload(&edited)["blocks"][0]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("[수정됨] *<"));
+    }
+}
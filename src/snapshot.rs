@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+/// 0건 파싱 스냅샷을 저장하는 디렉터리 (`bot.save_empty_snapshots`).
+pub const SNAPSHOT_DIR: &str = "debug_snapshots";
+
+/// 목록 응답 원문을 `{source}_{YYYYMMDD_HHMMSSmmm}.{ext}`로 저장하고,
+/// 같은 소스의 스냅샷은 최근 `keep`개만 남긴다. 반환: 저장한 파일 경로.
+pub fn save_snapshot(
+    dir: &Path,
+    source_key: &str,
+    body: &str,
+    ext: &str,
+    keep: usize,
+    at: NaiveDateTime,
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}_{}.{}", source_key, at.format("%Y%m%d_%H%M%S%3f"), ext));
+    std::fs::write(&path, body)?;
+    prune(dir, source_key, keep)?;
+    Ok(path)
+}
+
+/// 접두사 뒤가 `YYYYMMDD_HHMMSSmmm.{ext}` 형태인지.
+fn is_snapshot_stamp(rest: &str) -> bool {
+    let Some((stamp, ext)) = rest.split_once('.') else {
+        return false;
+    };
+    !ext.is_empty()
+        && stamp.len() == 18
+        && stamp.char_indices().all(|(i, c)| if i == 8 { c == '_' } else { c.is_ascii_digit() })
+}
+
+/// 같은 소스의 스냅샷 중 오래된 것부터 지워 `keep`개만 남긴다.
+/// 파일명의 시각이 고정 폭이라 이름순 = 시간순.
+fn prune(dir: &Path, source_key: &str, keep: usize) -> anyhow::Result<()> {
+    let prefix = format!("{}_", source_key);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(&prefix))
+                // `biz`가 `biz_grad_...`까지 지우지 않도록 나머지가 타임스탬프인지 확인
+                .is_some_and(is_snapshot_stamp)
+        })
+        .collect();
+    files.sort();
+    let excess = files.len().saturating_sub(keep);
+    for old in &files[..excess] {
+        std::fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_retention_cap() {
+        let dir = std::env::temp_dir().join(format!("cbnu_snapshots_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let base = chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+
+        for i in 0..5 {
+            let at = base + chrono::Duration::minutes(i);
+            save_snapshot(&dir, "biz", &format!("<html>{}</html>", i), "html", 3, at).unwrap();
+        }
+        // 다른 소스(접두사가 겹치는 키 포함)는 건드리지 않는다
+        save_snapshot(&dir, "biz_grad", "<html></html>", "html", 3, base).unwrap();
+        // JSON 소스는 확장자만 다르다
+        save_snapshot(&dir, "library", "{\"items\": []}", "json", 3, base).unwrap();
+
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "biz_20260301_090200000.html",
+                "biz_20260301_090300000.html",
+                "biz_20260301_090400000.html",
+                "biz_grad_20260301_090000000.html",
+                "library_20260301_090000000.json",
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("biz_20260301_090400000.html")).unwrap(),
+            "<html>4</html>"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::time::sleep;
+
+/// 호스트별 최소 요청 간격 (politeness delay).
+/// 같은 호스트로 가는 요청은 마지막 요청 이후 `delay`가 지나야 나간다.
+/// 목록/상세/재시도 요청이 모두 같은 게이트를 거친다.
+pub struct HostThrottle {
+    delay: Duration,
+    /// 호스트 → 다음 요청 가능 시각.
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostThrottle {
+    pub fn new(delay_ms: u64) -> Self {
+        Self {
+            delay: Duration::from_millis(delay_ms),
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `url`의 호스트 차례가 될 때까지 기다린다.
+    pub async fn wait(&self, url: &str) {
+        let host = match reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(String::from)) {
+            Some(h) => h,
+            None => return,
+        };
+        let wait = self.reserve(&host, Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!(host = %host, wait_ms = wait.as_millis() as u64, "Politeness delay");
+            sleep(wait).await;
+        }
+    }
+
+    /// `now` 기준으로 호스트의 다음 슬롯을 예약하고 기다려야 할 시간을 반환.
+    fn reserve(&self, host: &str, now: Instant) -> Duration {
+        if self.delay.is_zero() {
+            return Duration::ZERO;
+        }
+        let mut slots = self.next_slot.lock().unwrap();
+        let start = match slots.get(host) {
+            Some(&next) if next > now => next,
+            _ => now,
+        };
+        slots.insert(host.to_string(), start + self.delay);
+        start - now
+    }
+}
+
+/// 전역 발송 속도 제한 (token bucket). 채널 게시와 DM이 같은 버킷을 나눠 써서
+/// 인기 공지 하나가 수백 명에게 DM을 보내도 텔레그램 flood 한도(초당 30건)를 넘지 않는다.
+/// 토큰이 모자라면 마이너스로 예약해 두고 그만큼 기다리므로 요청 순서가 유지된다.
+pub struct RateLimiter {
+    /// 초당 토큰. 0이면 제한 없음.
+    rate: f64,
+    /// 버킷 크기 (한 번에 몰아 보낼 수 있는 양).
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// 초당 `per_sec`건. 버킷 크기도 `per_sec` (1초 분량).
+    pub fn new(per_sec: u32) -> Self {
+        Self {
+            rate: per_sec as f64,
+            burst: per_sec as f64,
+            bucket: Mutex::new(Bucket {
+                tokens: per_sec as f64,
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// 토큰 하나를 얻을 때까지 기다린다.
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!(wait_ms = wait.as_millis() as u64, "Send rate limit");
+            sleep(wait).await;
+        }
+    }
+
+    /// `now` 기준으로 버킷을 채우고 토큰 하나를 예약한 뒤 기다릴 시간을 반환.
+    fn reserve(&self, now: Instant) -> Duration {
+        if self.rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        let mut bucket = self.bucket.lock().unwrap();
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.last = now.max(bucket.last);
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_spaces_requests_per_host() {
+        let throttle = HostThrottle::new(500);
+        let t0 = Instant::now();
+
+        assert_eq!(throttle.reserve("a.chungbuk.ac.kr", t0), Duration::ZERO);
+        // 같은 호스트는 간격만큼 밀린다 (연속 예약은 누적)
+        assert_eq!(throttle.reserve("a.chungbuk.ac.kr", t0), Duration::from_millis(500));
+        assert_eq!(
+            throttle.reserve("a.chungbuk.ac.kr", t0 + Duration::from_millis(100)),
+            Duration::from_millis(900)
+        );
+        // 다른 호스트는 독립
+        assert_eq!(throttle.reserve("b.chungbuk.ac.kr", t0), Duration::ZERO);
+        // 간격이 지난 뒤에는 바로
+        assert_eq!(
+            throttle.reserve("b.chungbuk.ac.kr", t0 + Duration::from_secs(2)),
+            Duration::ZERO
+        );
+
+        let off = HostThrottle::new(0);
+        assert_eq!(off.reserve("a", t0), Duration::ZERO);
+        assert_eq!(off.reserve("a", t0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let limiter = RateLimiter::new(4); // 초당 4건, 버스트 4
+        let t0 = limiter.bucket.lock().unwrap().last;
+
+        // 버스트만큼은 바로 나간다
+        for _ in 0..4 {
+            assert_eq!(limiter.reserve(t0), Duration::ZERO);
+        }
+        // 이후는 250ms 간격으로 줄을 선다
+        assert_eq!(limiter.reserve(t0), Duration::from_millis(250));
+        assert_eq!(limiter.reserve(t0), Duration::from_millis(500));
+
+        // 1초 뒤: 토큰 4개가 찼지만 빚(-2)을 갚고 2개 남음
+        let t1 = t0 + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(t1), Duration::ZERO);
+        assert_eq!(limiter.reserve(t1), Duration::ZERO);
+        assert_eq!(limiter.reserve(t1), Duration::from_millis(250));
+
+        // 오래 쉬어도 버킷 크기 이상은 쌓이지 않는다
+        let t2 = t1 + Duration::from_secs(60);
+        for _ in 0..4 {
+            assert_eq!(limiter.reserve(t2), Duration::ZERO);
+        }
+        assert_eq!(limiter.reserve(t2), Duration::from_millis(250));
+
+        let off = RateLimiter::new(0);
+        for _ in 0..100 {
+            assert_eq!(off.reserve(t0), Duration::ZERO);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use tokio::sync::{mpsc, oneshot};
+
+/// 한 사이클의 처리 결과 (`do_crawl` 반환값, `/crawl` 응답).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CrawlCounts {
+    pub new: u32,
+    pub sent: usize,
+    pub dm_sent: u32,
+}
+
+/// 크롤 루프에 보내는 수동 크롤링 요청. `source`가 있으면 그 소스만 수집한다.
+pub struct CrawlRequest {
+    pub source: Option<String>,
+    pub reply: oneshot::Sender<anyhow::Result<CrawlCounts>>,
+}
+
+/// 봇 핸들러 쪽 손잡이 (`/crawl`). 크롤 루프는 별도 스레드/런타임에서 돌기 때문에
+/// 채널로 깨운다. 대기열은 한 칸이라 이미 요청이 밀려 있으면 바로 거절한다.
+#[derive(Clone)]
+pub struct CrawlTrigger {
+    tx: mpsc::Sender<CrawlRequest>,
+}
+
+/// 손잡이와 크롤 루프가 받을 수신기.
+pub fn channel() -> (CrawlTrigger, mpsc::Receiver<CrawlRequest>) {
+    let (tx, rx) = mpsc::channel(1);
+    (CrawlTrigger { tx }, rx)
+}
+
+impl CrawlTrigger {
+    /// 크롤링을 요청하고 그 사이클이 끝날 때까지 기다린다.
+    /// 예약된 사이클이 도는 중이면 그게 끝난 뒤에 이어서 돈다 (겹쳐 돌지 않음).
+    pub async fn run(&self, source: Option<String>) -> anyhow::Result<CrawlCounts> {
+        let (reply, done) = oneshot::channel();
+        self.tx.try_send(CrawlRequest { source, reply }).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => anyhow::anyhow!("이미 대기 중인 수동 크롤링이 있습니다"),
+            mpsc::error::TrySendError::Closed(_) => anyhow::anyhow!("크롤 루프가 멈춰 있습니다"),
+        })?;
+        done.await.map_err(|_| anyhow::anyhow!("크롤 루프가 응답 없이 종료됐습니다"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_trigger_round_trip() {
+        let (trigger, mut rx) = channel();
+
+        // 크롤 루프 흉내: 요청을 받아 소스에 따라 다른 결과를 돌려준다
+        let looper = tokio::spawn(async move {
+            while let Some(req) = rx.recv().await {
+                let new = if req.source.as_deref() == Some("biz") { 2 } else { 5 };
+                let _ = req.reply.send(Ok(CrawlCounts { new, sent: 1, dm_sent: 0 }));
+            }
+        });
+        assert_eq!(trigger.run(Some("biz".into())).await.unwrap().new, 2);
+        assert_eq!(trigger.run(None).await.unwrap(), CrawlCounts { new: 5, sent: 1, dm_sent: 0 });
+
+        drop(trigger);
+        looper.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_trigger_rejects_queued_and_closed() {
+        let (trigger, mut rx) = channel();
+
+        // 대기열 한 칸이 차 있으면 두 번째 요청은 바로 거절
+        let first = tokio::spawn({
+            let trigger = trigger.clone();
+            async move { trigger.run(None).await }
+        });
+        while rx.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        assert!(trigger.run(None).await.unwrap_err().to_string().contains("대기 중"));
+
+        // 루프가 응답 없이 요청을 버리면 에러
+        drop(rx.recv().await.unwrap());
+        assert!(first.await.unwrap().is_err());
+
+        drop(rx);
+        assert!(trigger.run(None).await.unwrap_err().to_string().contains("멈춰"));
+    }
+}
@@ -0,0 +1,63 @@
+use serde_json::Value;
+use tokio::time::{sleep, Duration};
+
+use crate::db::Notice;
+
+/// 외부 webhook 미러링 (`[discord]`, `[slack]`). 텔레그램 채널에 올라간 공지만 따라 올리고,
+/// 실패는 로그만 남긴다 (발송 기록 `notified`에는 영향 없음). 요청 본문은 서비스별 `payload`.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+    delay_ms: u64,
+    /// 로그에 쓸 서비스 이름.
+    service: &'static str,
+    payload: fn(&Notice) -> Value,
+}
+
+impl WebhookNotifier {
+    pub fn new(
+        client: reqwest::Client,
+        webhook_url: String,
+        delay_ms: u64,
+        service: &'static str,
+        payload: fn(&Notice) -> Value,
+    ) -> Self {
+        Self {
+            client,
+            webhook_url,
+            delay_ms,
+            service,
+            payload,
+        }
+    }
+
+    /// 공지 한 건을 게시.
+    pub async fn send(&self, notice: &Notice) -> anyhow::Result<()> {
+        self.client
+            .post(&self.webhook_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body((self.payload)(notice).to_string())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// 여러 공지를 차례로 게시. 반환: 성공 수.
+    pub async fn mirror(&self, notices: &[&Notice]) -> usize {
+        let mut sent = 0;
+        for notice in notices {
+            match self.send(notice).await {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::warn!(
+                    service = self.service,
+                    notice_id = %notice.notice_id,
+                    error = %e,
+                    "Webhook mirror failed"
+                ),
+            }
+            sleep(Duration::from_millis(self.delay_ms)).await;
+        }
+        sent
+    }
+}